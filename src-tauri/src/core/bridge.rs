@@ -0,0 +1,260 @@
+use crate::core::daemon;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{channel, Sender},
+        Arc, Condvar, Mutex,
+    },
+    time::Duration,
+};
+use tauri::{AppHandle, Emitter, Listener, Manager};
+use tauri_plugin_eco_window::MAIN_WINDOW_LABEL;
+
+// 发送给主窗口执行的方法
+const BRIDGE_REQUEST_EVENT: &str = "bridge-request";
+
+// 主窗口返回的执行结果
+const BRIDGE_RESPONSE_EVENT: &str = "bridge-response";
+
+// 主窗口开始接收请求后发送的事件
+const BRIDGE_READY_EVENT: &str = "bridge-ready";
+
+// 主窗口新增历史记录后发送的事件，载荷为历史记录
+pub const HISTORY_ADDED_EVENT: &str = "history-added";
+
+// 暂停或恢复记录时发送的事件，载荷为是否正在记录
+pub const RECORDING_CHANGED_EVENT: &str = "toggle-listen-clipboard";
+
+// 等待主窗口返回结果的时间
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(10);
+
+// 守护模式下等待界面加载完成的时间
+pub const READY_TIMEOUT: Duration = Duration::from_secs(30);
+
+// 命令行、本地 api 等外部调用共用的方法，除了显示窗口都由主窗口执行
+pub const BRIDGE_METHODS: &[&str] = &[
+    // 显示主窗口
+    "window.show",
+    // 显示主窗口并搜索：{ query }
+    "window.search",
+    // 查询历史记录：{ search?, group?, favorite?, limit?, offset? }
+    "history.query",
+    // 获取历史记录：{ id }
+    "history.get",
+    // 把历史记录复制到剪贴板：{ id }
+    "history.copy",
+    // 粘贴历史记录：{ id, plain? }
+    "history.paste",
+    // 新增文本的历史记录：{ text }
+    "history.add",
+    // 把文本写入剪贴板，会被当作复制记录下来：{ text }
+    "clipboard.write",
+    // 暂停记录，指定秒数后自动恢复：{ seconds? }
+    "recording.pause",
+    // 恢复记录
+    "recording.resume",
+    // 是否正在记录
+    "recording.status",
+];
+
+// 可以订阅的事件
+#[derive(Debug, Clone)]
+pub enum BridgeEvent {
+    // 新增的历史记录
+    HistoryAdded(Value),
+    // 是否正在记录
+    RecordingChanged(bool),
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct BridgeRequest<'a> {
+    id: u64,
+    method: &'a str,
+    params: Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct BridgeResponse {
+    id: u64,
+    result: Option<Value>,
+    error: Option<String>,
+}
+
+// 等待主窗口返回结果的请求
+#[derive(Default)]
+struct BridgeRequests {
+    next_id: AtomicU64,
+    pending: Mutex<HashMap<u64, Sender<Result<Value, String>>>>,
+    // 主窗口是否已经开始接收请求
+    ready: Mutex<bool>,
+    ready_changed: Condvar,
+}
+
+pub fn is_bridge_ready(app_handle: &AppHandle) -> bool {
+    app_handle
+        .try_state::<BridgeRequests>()
+        .is_some_and(|requests| *requests.ready.lock().unwrap())
+}
+
+// 等待主窗口开始接收请求，超时返回 `false`，不能在主线程中调用
+pub fn wait_bridge_ready(app_handle: &AppHandle, timeout: Duration) -> bool {
+    let Some(requests) = app_handle.try_state::<BridgeRequests>() else {
+        return false;
+    };
+
+    let ready = requests.ready.lock().unwrap();
+
+    let (ready, _) = requests
+        .ready_changed
+        .wait_timeout_while(ready, timeout, |ready| !*ready)
+        .unwrap();
+
+    *ready
+}
+
+fn request_main_window(
+    app_handle: &AppHandle,
+    method: &str,
+    params: Value,
+) -> Result<Value, String> {
+    let requests = app_handle.state::<BridgeRequests>();
+
+    let id = requests.next_id.fetch_add(1, Ordering::Relaxed);
+    let (sender, receiver) = channel();
+
+    requests.pending.lock().unwrap().insert(id, sender);
+
+    let request = BridgeRequest { id, method, params };
+
+    let result = match app_handle.emit_to(MAIN_WINDOW_LABEL, BRIDGE_REQUEST_EVENT, request) {
+        Ok(_) => receiver
+            .recv_timeout(RESPONSE_TIMEOUT)
+            .unwrap_or_else(|_| Err("timed out waiting for the main window".to_string())),
+        Err(err) => Err(err.to_string()),
+    };
+
+    requests.pending.lock().unwrap().remove(&id);
+
+    result
+}
+
+// 历史记录单行的摘要，用于命令行和桌面集成的输出
+pub fn summarize(item: &Value) -> String {
+    let field = |key: &str| item.get(key).and_then(Value::as_str).unwrap_or_default();
+
+    let summary = match field("type") {
+        "image" => field("value").to_string(),
+        "files" => serde_json::from_str::<Vec<String>>(field("value"))
+            .unwrap_or_default()
+            .join(" "),
+        _ if !field("search").is_empty() => field("search").to_string(),
+        _ => field("value").to_string(),
+    };
+
+    summary
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .chars()
+        .take(80)
+        .collect()
+}
+
+// 调用方法，会阻塞到主窗口返回结果，不能在主线程中调用
+pub fn call(app_handle: &AppHandle, method: &str, params: Value) -> Result<Value, String> {
+    if !BRIDGE_METHODS.contains(&method) {
+        return Err(format!("unknown method: {}", method));
+    }
+
+    if method == "window.show" {
+        daemon::show_ui(app_handle, MAIN_WINDOW_LABEL);
+
+        return Ok(Value::Null);
+    }
+
+    // 守护模式下界面没有加载时，不需要界面的方法由后台执行，其余的先创建界面
+    if !is_bridge_ready(app_handle) {
+        if let Some(result) = daemon::call(app_handle, method, &params) {
+            return result;
+        }
+
+        daemon::spawn_ui(app_handle);
+
+        if !wait_bridge_ready(app_handle, READY_TIMEOUT) {
+            return Err("timed out waiting for the main window to load".to_string());
+        }
+    }
+
+    request_main_window(app_handle, method, params)
+}
+
+// 接收主窗口返回的结果
+pub fn listen_bridge(app_handle: &AppHandle) {
+    app_handle.manage(BridgeRequests::default());
+
+    let handle = app_handle.clone();
+
+    app_handle.listen(BRIDGE_READY_EVENT, move |_| {
+        let requests = handle.state::<BridgeRequests>();
+
+        *requests.ready.lock().unwrap() = true;
+
+        requests.ready_changed.notify_all();
+    });
+
+    let handle = app_handle.clone();
+
+    app_handle.listen(BRIDGE_RESPONSE_EVENT, move |event| {
+        let Ok(response) = serde_json::from_str::<BridgeResponse>(event.payload()) else {
+            return;
+        };
+
+        let sender = handle
+            .state::<BridgeRequests>()
+            .pending
+            .lock()
+            .unwrap()
+            .remove(&response.id);
+
+        let Some(sender) = sender else {
+            return;
+        };
+
+        let _ = sender.send(match response.error {
+            Some(error) => Err(error),
+            None => Ok(response.result.unwrap_or_default()),
+        });
+    });
+}
+
+// 监听新增历史记录和记录状态的变化，记录状态没有变化时不会重复通知
+pub fn listen_bridge_events<F>(app_handle: &AppHandle, handler: F)
+where
+    F: Fn(BridgeEvent) + Send + Sync + 'static,
+{
+    let handler = Arc::new(handler);
+    let recording = Mutex::new(None);
+
+    let history_handler = handler.clone();
+
+    app_handle.listen(HISTORY_ADDED_EVENT, move |event| {
+        if let Ok(item) = serde_json::from_str(event.payload()) {
+            history_handler(BridgeEvent::HistoryAdded(item));
+        }
+    });
+
+    app_handle.listen(RECORDING_CHANGED_EVENT, move |event| {
+        let Ok(value) = serde_json::from_str::<bool>(event.payload()) else {
+            return;
+        };
+
+        if recording.lock().unwrap().replace(value) == Some(value) {
+            return;
+        }
+
+        handler(BridgeEvent::RecordingChanged(value));
+    });
+}
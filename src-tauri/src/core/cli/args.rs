@@ -0,0 +1,301 @@
+use serde::{Deserialize, Serialize};
+use std::io::{stdin, Read};
+
+// `--list` 默认输出的条数
+const DEFAULT_LIST_LIMIT: usize = 20;
+
+pub const USAGE: &str = "Usage: xeco-paste [OPTIONS]
+
+Options:
+  --show               Show the main window
+  --paste <n>          Paste the n-th history entry (starting from 1)
+  --copy <text|->      Copy text to the clipboard, `-` reads it from stdin
+  --search <query>     Show the main window and search the history
+  --pause <duration>   Pause recording, e.g. 30s, 5m, 1h
+  --resume             Resume recording
+  --list               Print the latest history entries
+      --json           Print the entries as JSON
+      --limit <n>      Number of entries to print [default: 20]
+  --daemon             Run in the background and create the window on demand,
+                       sync starts once the window is created
+  -h, --help           Print help";
+
+// 可以出现在第一个位置的参数，第一个参数不在其中时不当作命令行输入处理
+const OPTIONS: [&str; 13] = [
+    "-h",
+    "--help",
+    "--show",
+    "--paste",
+    "--copy",
+    "--search",
+    "--pause",
+    "--resume",
+    "--list",
+    "--json",
+    "--limit",
+    "--daemon",
+    "--auto-launch",
+];
+
+// 命令行支持的命令，转发给正在运行的实例执行
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum CliCommand {
+    Show,
+    Paste { index: usize },
+    Copy { text: String },
+    Search { query: String },
+    Pause { seconds: u64 },
+    Resume,
+    List { json: bool, limit: usize },
+}
+
+// 解析的结果
+#[derive(Debug, PartialEq, Eq)]
+pub enum CliArgs {
+    // 没有命令，正常启动应用
+    None,
+    Help,
+    Command(CliCommand),
+}
+
+// 解析时长：纯数字为秒，支持 s、m、h 后缀
+fn parse_duration(value: &str) -> Result<u64, String> {
+    let invalid = || format!("invalid duration: {}", value);
+
+    let (number, unit) = match value.find(|char: char| !char.is_ascii_digit()) {
+        Some(index) => value.split_at(index),
+        None => (value, "s"),
+    };
+
+    let number: u64 = number.parse().map_err(|_| invalid())?;
+
+    let multiplier = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        _ => return Err(invalid()),
+    };
+
+    match number.checked_mul(multiplier) {
+        Some(seconds) if seconds > 0 => Ok(seconds),
+        _ => Err(invalid()),
+    }
+}
+
+fn parse_number(option: &str, value: &str) -> Result<usize, String> {
+    match value.parse() {
+        Ok(number) if number > 0 => Ok(number),
+        _ => Err(format!(
+            "{} expects a positive number, got: {}",
+            option, value
+        )),
+    }
+}
+
+// 读取 `--copy -` 的标准输入
+fn read_stdin() -> Result<String, String> {
+    let mut text = String::new();
+
+    stdin()
+        .read_to_string(&mut text)
+        .map_err(|err| err.to_string())?;
+
+    Ok(text)
+}
+
+// 系统或桌面环境附加的参数，例如 macOS 的 `-psn_0_12345`
+fn is_system_arg(arg: &str) -> bool {
+    arg.starts_with("-psn_")
+}
+
+// 解析命令行参数，不包含程序名称
+pub fn parse(args: &[String]) -> Result<CliArgs, String> {
+    let args: Vec<&String> = args.iter().filter(|arg| !is_system_arg(arg)).collect();
+
+    // 文件管理器、桌面文件的 `%U` 等传入的参数不是命令行输入，正常启动应用
+    match args.first() {
+        Some(arg) if OPTIONS.contains(&arg.as_str()) => {}
+        _ => return Ok(CliArgs::None),
+    }
+
+    let mut command = None;
+    let mut json = false;
+    let mut limit = None;
+    let mut daemon = false;
+
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let mut value = |option: &str| {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("{} requires a value", option))
+        };
+
+        let next = match arg.as_str() {
+            "-h" | "--help" => return Ok(CliArgs::Help),
+            "--show" => CliCommand::Show,
+            "--paste" => CliCommand::Paste {
+                index: parse_number(arg, &value(arg)?)?,
+            },
+            "--copy" => {
+                let text = value(arg)?;
+
+                CliCommand::Copy {
+                    text: if text == "-" { read_stdin()? } else { text },
+                }
+            }
+            "--search" => CliCommand::Search { query: value(arg)? },
+            "--pause" => CliCommand::Pause {
+                seconds: parse_duration(&value(arg)?)?,
+            },
+            "--resume" => CliCommand::Resume,
+            "--list" => CliCommand::List {
+                json: false,
+                limit: DEFAULT_LIST_LIMIT,
+            },
+            "--json" => {
+                json = true;
+                continue;
+            }
+            "--limit" => {
+                limit = Some(parse_number(arg, &value(arg)?)?);
+                continue;
+            }
+            "--daemon" => {
+                daemon = true;
+                continue;
+            }
+            // 自启动插件添加的参数
+            "--auto-launch" => continue,
+            _ => return Err(format!("unexpected argument: {}", arg)),
+        };
+
+        if command.is_some() {
+            return Err(format!("only one command is allowed, got: {}", arg));
+        }
+
+        command = Some(next);
+    }
+
+    // 守护模式由新启动的实例处理，不能与转发的命令一起使用
+    if daemon && command.is_some() {
+        return Err("--daemon cannot be combined with other commands".to_string());
+    }
+
+    match command {
+        Some(CliCommand::List { .. }) => Ok(CliArgs::Command(CliCommand::List {
+            json,
+            limit: limit.unwrap_or(DEFAULT_LIST_LIMIT),
+        })),
+        _ if json || limit.is_some() => Err("--json and --limit require --list".to_string()),
+        Some(command) => Ok(CliArgs::Command(command)),
+        None => Ok(CliArgs::None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Result<CliArgs, String> {
+        parse(&args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn parses_durations_with_units() {
+        assert_eq!(parse_duration("30"), Ok(30));
+        assert_eq!(parse_duration("30s"), Ok(30));
+        assert_eq!(parse_duration("5m"), Ok(300));
+        assert_eq!(parse_duration("2h"), Ok(7200));
+    }
+
+    #[test]
+    fn rejects_invalid_durations() {
+        for value in ["", "0", "0m", "m", "5d", "5 m", "-5", "1.5h"] {
+            assert!(parse_duration(value).is_err(), "{}", value);
+        }
+    }
+
+    // 换算为秒时溢出
+    #[test]
+    fn rejects_overflowing_durations() {
+        assert_eq!(parse_duration("18446744073709551615"), Ok(u64::MAX));
+        assert!(parse_duration("18446744073709551615m").is_err());
+        assert!(parse_duration("99999999999999999999").is_err());
+    }
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!(
+            parse_args(&["--paste", "3"]),
+            Ok(CliArgs::Command(CliCommand::Paste { index: 3 }))
+        );
+        assert_eq!(
+            parse_args(&["--search", "query"]),
+            Ok(CliArgs::Command(CliCommand::Search {
+                query: "query".to_string()
+            }))
+        );
+        assert_eq!(
+            parse_args(&["--pause", "5m"]),
+            Ok(CliArgs::Command(CliCommand::Pause { seconds: 300 }))
+        );
+        assert_eq!(parse_args(&["--show", "-h"]), Ok(CliArgs::Help));
+    }
+
+    // `--json` 和 `--limit` 可以出现在 `--list` 之前
+    #[test]
+    fn parses_list_options() {
+        assert_eq!(
+            parse_args(&["--list"]),
+            Ok(CliArgs::Command(CliCommand::List {
+                json: false,
+                limit: DEFAULT_LIST_LIMIT
+            }))
+        );
+        assert_eq!(
+            parse_args(&["--limit", "5", "--list", "--json"]),
+            Ok(CliArgs::Command(CliCommand::List {
+                json: true,
+                limit: 5
+            }))
+        );
+        assert!(parse_args(&["--json"]).is_err());
+        assert!(parse_args(&["--show", "--limit", "5"]).is_err());
+    }
+
+    // 自启动、守护模式、macOS 和桌面文件传入的参数正常启动应用
+    #[test]
+    fn launches_without_commands() {
+        assert_eq!(parse_args(&[]), Ok(CliArgs::None));
+        assert_eq!(
+            parse_args(&["--auto-launch", "--daemon"]),
+            Ok(CliArgs::None)
+        );
+        assert_eq!(parse_args(&["-psn_0_12345"]), Ok(CliArgs::None));
+        assert_eq!(parse_args(&["%U"]), Ok(CliArgs::None));
+        assert_eq!(
+            parse_args(&["file:///tmp/a.txt", "--show"]),
+            Ok(CliArgs::None)
+        );
+        assert_eq!(
+            parse_args(&["-psn_0_12345", "--show"]),
+            Ok(CliArgs::Command(CliCommand::Show))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        assert!(parse_args(&["--paste"]).is_err());
+        assert!(parse_args(&["--paste", "0"]).is_err());
+        assert!(parse_args(&["--show", "--resume"]).is_err());
+        assert!(parse_args(&["--show", "unknown"]).is_err());
+        assert!(parse_args(&["--daemon", "--show"]).is_err());
+    }
+}
@@ -0,0 +1,151 @@
+use interprocess::local_socket::{prelude::*, Name, Stream};
+use serde::{Deserialize, Serialize};
+use std::{
+    env,
+    io::{self, BufRead, BufReader, Write},
+};
+
+mod args;
+mod server;
+
+pub use args::*;
+pub use server::*;
+
+// 发送给正在运行的实例的请求，请求和响应各占一行 json
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliRequest {
+    pub command: CliCommand,
+}
+
+// 命令的执行结果，输出会打印到调用的终端
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliResponse {
+    pub output: Option<String>,
+    pub error: Option<String>,
+}
+
+impl CliResponse {
+    pub fn error(message: impl Into<String>) -> Self {
+        Self {
+            output: None,
+            error: Some(message.into()),
+        }
+    }
+}
+
+// 本地套接字的名称，开发模式使用单独的名称，避免与安装的应用冲突
+#[cfg(debug_assertions)]
+const SOCKET_NAME: &str = "xeco-paste-cli-dev.sock";
+
+#[cfg(not(debug_assertions))]
+const SOCKET_NAME: &str = "xeco-paste-cli.sock";
+
+// Windows 使用命名管道，其它平台使用只有当前用户可以访问的运行时目录中的套接字文件
+#[cfg(target_os = "windows")]
+fn socket_name() -> io::Result<Name<'static>> {
+    use interprocess::local_socket::GenericNamespaced;
+
+    SOCKET_NAME.to_ns_name::<GenericNamespaced>()
+}
+
+#[cfg(not(target_os = "windows"))]
+fn socket_name() -> io::Result<Name<'static>> {
+    use interprocess::local_socket::GenericFilePath;
+
+    socket_path()?.to_fs_name::<GenericFilePath>()
+}
+
+// 与 rpc 使用同一个只有当前用户可以访问的目录
+#[cfg(not(target_os = "windows"))]
+fn socket_path() -> io::Result<std::path::PathBuf> {
+    Ok(crate::core::rpc::socket_dir()?.join(SOCKET_NAME))
+}
+
+// release 模式的 Windows 程序没有控制台，附加到调用的终端以便打印结果
+#[cfg(target_os = "windows")]
+fn attach_console() {
+    use winapi::um::wincon::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn attach_console() {}
+
+// 把命令发送给正在运行的实例，并等待执行结果
+fn send(mut stream: Stream, command: CliCommand) -> io::Result<CliResponse> {
+    let mut content = serde_json::to_string(&CliRequest { command })?;
+    content.push('\n');
+
+    stream.write_all(content.as_bytes())?;
+
+    let mut line = String::new();
+
+    BufReader::new(stream).read_line(&mut line)?;
+
+    Ok(serde_json::from_str(&line)?)
+}
+
+// 打印执行结果，返回进程的退出码
+fn print_response(response: CliResponse) -> i32 {
+    if let Some(output) = response.output.filter(|output| !output.is_empty()) {
+        println!("{}", output);
+    }
+
+    match response.error {
+        Some(error) => {
+            eprintln!("error: {}", error);
+
+            1
+        }
+        None => 0,
+    }
+}
+
+// 启动应用前处理命令行参数，返回退出码时不再启动应用
+pub fn forward_args() -> Option<i32> {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+
+    let command = match parse(&args) {
+        Ok(CliArgs::None) => return None,
+        Ok(CliArgs::Help) => {
+            attach_console();
+            println!("{}", USAGE);
+
+            return Some(0);
+        }
+        Ok(CliArgs::Command(command)) => command,
+        Err(err) => {
+            attach_console();
+            eprintln!("error: {}\n\n{}", err, USAGE);
+
+            return Some(2);
+        }
+    };
+
+    attach_console();
+
+    let stream = match socket_name().and_then(|name| Stream::connect(name)) {
+        Ok(stream) => stream,
+        // 没有正在运行的实例时，`--show` 直接启动应用
+        Err(_) if command == CliCommand::Show => return None,
+        Err(_) => {
+            eprintln!("error: XEcoPaste is not running");
+
+            return Some(1);
+        }
+    };
+
+    match send(stream, command) {
+        Ok(response) => Some(print_response(response)),
+        Err(err) => {
+            eprintln!("error: {}", err);
+
+            Some(1)
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use super::{socket_name, CliCommand, CliRequest, CliResponse};
+use crate::core::bridge::{call, summarize};
+use interprocess::local_socket::{prelude::*, ListenerOptions, Stream};
+use serde_json::{json, Value};
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    thread::spawn,
+};
+use tauri::AppHandle;
+
+// 执行命令，返回打印到终端的输出
+fn execute(app_handle: &AppHandle, command: CliCommand) -> Result<Option<String>, String> {
+    match command {
+        CliCommand::Show => call(app_handle, "window.show", Value::Null).map(|_| None),
+        CliCommand::Paste { index } => {
+            let items = call(
+                app_handle,
+                "history.query",
+                json!({ "limit": 1, "offset": index - 1 }),
+            )?;
+
+            let id = items
+                .get(0)
+                .and_then(|item| item.get("id"))
+                .and_then(Value::as_str)
+                .ok_or_else(|| format!("no entry at index {}", index))?;
+
+            call(app_handle, "history.paste", json!({ "id": id })).map(|_| None)
+        }
+        CliCommand::Copy { text } => {
+            call(app_handle, "clipboard.write", json!({ "text": text })).map(|_| None)
+        }
+        CliCommand::Search { query } => {
+            call(app_handle, "window.search", json!({ "query": query })).map(|_| None)
+        }
+        CliCommand::Pause { seconds } => {
+            let result = call(app_handle, "recording.pause", json!({ "seconds": seconds }))?;
+
+            let resume_at = result
+                .get("resumeAt")
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+
+            Ok(Some(format!("Recording paused until {}", resume_at)))
+        }
+        CliCommand::Resume => call(app_handle, "recording.resume", Value::Null)
+            .map(|_| Some("Recording resumed".to_string())),
+        CliCommand::List { json, limit } => {
+            let items = call(app_handle, "history.query", json!({ "limit": limit }))?;
+
+            if json {
+                return Ok(Some(items.to_string()));
+            }
+
+            let lines = items
+                .as_array()
+                .map(|items| {
+                    items
+                        .iter()
+                        .enumerate()
+                        .map(|(index, item)| {
+                            let kind = item.get("type").and_then(Value::as_str);
+
+                            format!(
+                                "{}\t{}\t{}",
+                                index + 1,
+                                kind.unwrap_or_default(),
+                                summarize(item)
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+
+            Ok(Some(lines.join("\n")))
+        }
+    }
+}
+
+// 每个连接只处理一个请求
+fn handle_stream(app_handle: &AppHandle, stream: Stream) -> io::Result<()> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+
+    reader.read_line(&mut line)?;
+
+    let response = match serde_json::from_str::<CliRequest>(&line)
+        .map_err(|err| err.to_string())
+        .and_then(|request| execute(app_handle, request.command))
+    {
+        Ok(output) => CliResponse {
+            output,
+            error: None,
+        },
+        Err(err) => CliResponse::error(err),
+    };
+
+    let mut content = serde_json::to_string(&response)?;
+    content.push('\n');
+
+    reader.get_mut().write_all(content.as_bytes())
+}
+
+// 监听命令行转发的请求
+pub fn listen_cli(app_handle: &AppHandle) {
+    // 只有一个实例在运行，可以覆盖上次异常退出时残留的套接字文件
+    let listener = match socket_name().and_then(|name| {
+        ListenerOptions::new()
+            .name(name)
+            .try_overwrite(true)
+            .create_sync()
+    }) {
+        Ok(listener) => listener,
+        Err(err) => {
+            log::warn!("Failed to listen for cli requests: {}", err);
+
+            return;
+        }
+    };
+
+    // 套接字文件只允许当前用户访问
+    #[cfg(unix)]
+    {
+        use std::{fs, os::unix::fs::PermissionsExt};
+
+        if let Ok(path) = super::socket_path() {
+            let _ = fs::set_permissions(path, fs::Permissions::from_mode(0o600));
+        }
+    }
+
+    let app_handle = app_handle.clone();
+
+    spawn(move || {
+        for stream in listener.incoming().filter_map(Result::ok) {
+            let app_handle = app_handle.clone();
+
+            spawn(move || {
+                if let Err(err) = handle_stream(&app_handle, stream) {
+                    log::warn!("Failed to handle cli request: {}", err);
+                }
+            });
+        }
+    });
+}
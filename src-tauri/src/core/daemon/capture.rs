@@ -0,0 +1,364 @@
+use super::{database::HistoryItem, emit_added, now, store::Store, Daemon};
+use nanoid::nanoid;
+use percent_encoding::percent_decode_str;
+use std::{fs, path::Path};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_eco_clipboard::ClipboardManager;
+use tauri_plugin_eco_ocr::{OcrJob, OcrQueue};
+
+// 与 JavaScript 字符串的长度相同
+pub(super) fn text_count(text: &str) -> i64 {
+    text.encode_utf16().count() as i64
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+// 与前端使用的 is-url 规则相同：可选的协议、`//`、包含点的主机名或 localhost，没有空白字符
+fn is_url(value: &str) -> bool {
+    if value.chars().any(char::is_whitespace) {
+        return false;
+    }
+
+    let Some((scheme, rest)) = value.split_once("//") else {
+        return false;
+    };
+
+    let valid_scheme = scheme.is_empty()
+        || scheme.strip_suffix(':').is_some_and(|scheme| {
+            !scheme.is_empty()
+                && scheme
+                    .chars()
+                    .all(|char| char.is_ascii_alphanumeric() || char == '_')
+        });
+
+    if !valid_scheme {
+        return false;
+    }
+
+    if rest.starts_with("localhost") {
+        return true;
+    }
+
+    rest.split_once('.')
+        .is_some_and(|(name, domain)| !name.is_empty() && domain.chars().count() >= 2)
+}
+
+// 与前端的 `isEmail` 规则相同
+fn is_email(value: &str) -> bool {
+    let Some((name, domain)) = value.split_once('@') else {
+        return false;
+    };
+
+    let is_name_char =
+        |char: char| char.is_ascii_alphanumeric() || ('\u{4e00}'..='\u{9fa5}').contains(&char);
+
+    let is_domain_char = |char: char| char.is_ascii_alphanumeric() || char == '_' || char == '-';
+
+    !name.is_empty()
+        && name.chars().all(is_name_char)
+        && domain.contains('.')
+        && domain
+            .split('.')
+            .all(|label| !label.is_empty() && label.chars().all(is_domain_char))
+}
+
+// 前端由浏览器解析颜色，这里只识别十六进制和函数形式的颜色，不识别颜色名称
+fn is_color(value: &str) -> bool {
+    if let Some(hex) = value.strip_prefix('#') {
+        return [3, 4, 6, 8].contains(&hex.len())
+            && hex.chars().all(|char| char.is_ascii_hexdigit());
+    }
+
+    let functions = [
+        "rgb(", "rgba(", "hsl(", "hsla(", "hwb(", "lab(", "lch(", "oklab(", "oklch(",
+    ];
+
+    let lower = value.to_ascii_lowercase();
+
+    lower.ends_with(')') && functions.iter().any(|function| lower.starts_with(function))
+}
+
+// 与前端的 `getClipboardSubtype` 相同
+fn get_subtype(value: &str) -> Option<String> {
+    let subtype = if is_url(value) {
+        "url"
+    } else if is_email(value) {
+        "email"
+    } else if is_color(value) {
+        "color"
+    } else if Path::new(value).exists() {
+        "path"
+    } else {
+        return None;
+    };
+
+    Some(subtype.to_string())
+}
+
+pub(super) fn new_item(
+    kind: &str,
+    group: &str,
+    value: String,
+    search: String,
+    count: i64,
+) -> HistoryItem {
+    HistoryItem {
+        id: nanoid!(),
+        kind: kind.to_string(),
+        group: group.to_string(),
+        value,
+        search: Some(search),
+        count: Some(count),
+        create_time: now(),
+        ..Default::default()
+    }
+}
+
+fn read_files(manager: &ClipboardManager) -> Result<HistoryItem, String> {
+    let files = manager
+        .read_files()?
+        .iter()
+        .map(|path| percent_decode_str(path).decode_utf8_lossy().into_owned())
+        .collect::<Vec<_>>();
+
+    let mut count = 0;
+    let mut names = Vec::new();
+
+    for path in &files {
+        let path = Path::new(path);
+
+        count += fs::metadata(path)
+            .map(|metadata| metadata.len())
+            .unwrap_or_default();
+
+        names.push(file_name(path));
+    }
+
+    let value = serde_json::to_string(&files).map_err(|err| err.to_string())?;
+
+    Ok(new_item(
+        "files",
+        "files",
+        value,
+        names.join(" "),
+        count as i64,
+    ))
+}
+
+fn read_image(manager: &ClipboardManager, image_dir: &Path) -> Result<HistoryItem, String> {
+    let image = manager.read_image(image_dir)?;
+
+    let path = Path::new(&image.image);
+
+    let count = fs::metadata(path).map_err(|err| err.to_string())?.len();
+
+    // 识别结果由后台识别队列写回
+    let mut item = new_item(
+        "image",
+        "image",
+        file_name(path),
+        String::new(),
+        count as i64,
+    );
+
+    item.width = Some(image.width.into());
+    item.height = Some(image.height.into());
+
+    Ok(item)
+}
+
+fn read_text(manager: &ClipboardManager) -> Result<HistoryItem, String> {
+    let text = manager.read_text()?;
+
+    let mut item = new_item(
+        "text",
+        "text",
+        text.clone(),
+        text.clone(),
+        text_count(&text),
+    );
+
+    item.subtype = get_subtype(&text);
+
+    Ok(item)
+}
+
+// HTML 和富文本使用纯文本作为搜索内容
+fn read_rich(manager: &ClipboardManager, kind: &str, value: String) -> Result<HistoryItem, String> {
+    let text = manager.read_text()?;
+
+    Ok(new_item(
+        kind,
+        "text",
+        value,
+        text.clone(),
+        text_count(&text),
+    ))
+}
+
+// 与前端的 `readClipboard` 使用相同的优先级读取剪贴板
+fn read_clipboard(
+    manager: &ClipboardManager,
+    store: &Store,
+    image_dir: &Path,
+) -> Result<HistoryItem, String> {
+    let copy_plain = store.clipboard_store.content.copy_plain;
+
+    if manager.has_files() {
+        return read_files(manager);
+    }
+
+    if manager.has_image() {
+        return read_image(manager, image_dir).or_else(|err| {
+            log::warn!(
+                "Failed to read the clipboard image, falling back to text: {}",
+                err
+            );
+
+            read_text(manager)
+        });
+    }
+
+    if !copy_plain && manager.has_html() {
+        return read_rich(manager, "html", manager.read_html()?);
+    }
+
+    if !copy_plain && manager.has_rtf() {
+        return read_rich(manager, "rtf", manager.read_rtf()?);
+    }
+
+    read_text(manager)
+}
+
+// 读取剪贴板并保存为历史记录，相同的内容只更新创建时间
+pub async fn capture(app_handle: &AppHandle) -> Result<(), String> {
+    let store = Store::load(app_handle);
+    let image_dir = store.image_dir(app_handle).map_err(|err| err.to_string())?;
+
+    let item = read_clipboard(&app_handle.state::<ClipboardManager>(), &store, &image_dir)?;
+
+    if item.group == "text" && item.count == Some(0) {
+        return Ok(());
+    }
+
+    let daemon = app_handle.state::<Daemon>();
+
+    let existed = daemon
+        .database
+        .find(&item.kind, &item.value)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    if let Some(existed) = existed {
+        if store.clipboard_store.content.auto_sort {
+            daemon
+                .database
+                .update_create_time(&existed.id, &now())
+                .await
+                .map_err(|err| err.to_string())?;
+        }
+
+        return Ok(());
+    }
+
+    daemon
+        .database
+        .insert(&item)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    emit_added(app_handle, &store, &item);
+
+    if item.kind == "image" && store.clipboard_store.content.ocr {
+        app_handle.state::<OcrQueue>().push(OcrJob {
+            id: item.id.clone(),
+            path: image_dir.join(&item.value),
+            options: Default::default(),
+        })?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_utf16_units() {
+        assert_eq!(text_count("abc"), 3);
+        assert_eq!(text_count("中文"), 2);
+        assert_eq!(text_count("😀"), 2);
+    }
+
+    #[test]
+    fn detects_urls() {
+        for value in [
+            "https://example.com",
+            "http://localhost:3000",
+            "//example.com/path",
+            "ftp://files.example.org",
+        ] {
+            assert!(is_url(value), "{}", value);
+        }
+
+        for value in [
+            "example.com",
+            "https://example",
+            "https://example.c",
+            "https://example.com/a b",
+            "ht-tp://example.com",
+        ] {
+            assert!(!is_url(value), "{}", value);
+        }
+    }
+
+    #[test]
+    fn detects_emails() {
+        assert!(is_email("name@example.com"));
+        assert!(is_email("名字@example.com"));
+
+        for value in [
+            "name@example",
+            "@example.com",
+            "na.me@example.com",
+            "name@.com",
+        ] {
+            assert!(!is_email(value), "{}", value);
+        }
+    }
+
+    #[test]
+    fn detects_colors() {
+        for value in [
+            "#fff",
+            "#ffff",
+            "#ffffff",
+            "#ffffff80",
+            "rgb(0, 0, 0)",
+            "HSL(0 0% 0%)",
+        ] {
+            assert!(is_color(value), "{}", value);
+        }
+
+        for value in ["#ff", "#gggggg", "red", "rgb(0, 0, 0"] {
+            assert!(!is_color(value), "{}", value);
+        }
+    }
+
+    #[test]
+    fn detects_subtypes() {
+        assert_eq!(get_subtype("https://example.com").as_deref(), Some("url"));
+        assert_eq!(get_subtype("name@example.com").as_deref(), Some("email"));
+        assert_eq!(get_subtype("#ffffff").as_deref(), Some("color"));
+        assert_eq!(
+            get_subtype(env!("CARGO_MANIFEST_DIR")).as_deref(),
+            Some("path")
+        );
+        assert_eq!(get_subtype("plain text"), None);
+    }
+}
@@ -0,0 +1,376 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqlitePool},
+    FromRow, QueryBuilder, Sqlite,
+};
+use std::{fs, path::Path};
+
+// 与前端创建的 `history` 表保持一致
+const CREATE_HISTORY_TABLE: &str = "
+    CREATE TABLE IF NOT EXISTS history (
+        id TEXT PRIMARY KEY,
+        type TEXT,
+        [group] TEXT,
+        value TEXT,
+        search TEXT,
+        count INTEGER,
+        width INTEGER,
+        height INTEGER,
+        favorite INTEGER DEFAULT 0,
+        createTime TEXT,
+        note TEXT,
+        subtype TEXT,
+        codes TEXT
+    );
+";
+
+// 历史记录，序列化后与前端查询到的数据结构相同
+#[derive(Debug, Clone, Default, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryItem {
+    pub id: String,
+    #[serde(rename = "type")]
+    #[sqlx(rename = "type")]
+    pub kind: String,
+    pub group: String,
+    pub value: String,
+    pub search: Option<String>,
+    pub count: Option<i64>,
+    pub width: Option<i64>,
+    pub height: Option<i64>,
+    pub favorite: bool,
+    #[sqlx(rename = "createTime")]
+    pub create_time: String,
+    pub note: Option<String>,
+    pub subtype: Option<String>,
+    pub codes: Option<String>,
+}
+
+// 查询历史记录的条件
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct HistoryQuery {
+    pub id: Option<String>,
+    pub search: Option<String>,
+    pub group: Option<String>,
+    pub favorite: Option<bool>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+pub struct Database {
+    pool: SqlitePool,
+}
+
+impl Database {
+    pub async fn open(path: &Path) -> Result<Self, sqlx::Error> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true);
+
+        let pool = SqlitePool::connect_with(options).await?;
+
+        sqlx::query(CREATE_HISTORY_TABLE).execute(&pool).await?;
+
+        // 旧版本的数据库没有 `codes` 字段
+        let has_codes =
+            sqlx::query("SELECT 1 FROM pragma_table_info('history') WHERE name = 'codes';")
+                .fetch_optional(&pool)
+                .await?
+                .is_some();
+
+        if !has_codes {
+            sqlx::query("ALTER TABLE history ADD COLUMN codes TEXT;")
+                .execute(&pool)
+                .await?;
+        }
+
+        Ok(Self { pool })
+    }
+
+    // 按创建时间倒序查询，与前端的 `selectSQL` 相同
+    pub async fn select(&self, query: &HistoryQuery) -> Result<Vec<HistoryItem>, sqlx::Error> {
+        let mut builder = QueryBuilder::<Sqlite>::new("SELECT * FROM history WHERE 1 = 1");
+
+        if let Some(id) = &query.id {
+            builder.push(" AND id = ").push_bind(id.clone());
+        }
+
+        if let Some(search) = &query.search {
+            let pattern = format!("%{}%", search);
+
+            builder
+                .push(" AND (search LIKE ")
+                .push_bind(pattern.clone())
+                .push(" OR note LIKE ")
+                .push_bind(pattern)
+                .push(")");
+        }
+
+        if let Some(group) = &query.group {
+            builder.push(" AND [group] = ").push_bind(group.clone());
+        }
+
+        if let Some(favorite) = query.favorite {
+            builder.push(" AND favorite = ").push_bind(favorite);
+        }
+
+        builder.push(" ORDER BY createTime DESC");
+
+        // sqlite 需要先指定 LIMIT 才能指定 OFFSET，-1 为不限制
+        if query.limit.is_some() || query.offset.is_some() {
+            builder
+                .push(" LIMIT ")
+                .push_bind(query.limit.unwrap_or(-1))
+                .push(" OFFSET ")
+                .push_bind(query.offset.unwrap_or_default());
+        }
+
+        builder
+            .build_query_as::<HistoryItem>()
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Option<HistoryItem>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM history WHERE id = ?;")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    // 查找相同内容的历史记录
+    pub async fn find(&self, kind: &str, value: &str) -> Result<Option<HistoryItem>, sqlx::Error> {
+        sqlx::query_as("SELECT * FROM history WHERE type = ? AND value = ?;")
+            .bind(kind)
+            .bind(value)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    pub async fn insert(&self, item: &HistoryItem) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO history (id, type, [group], value, search, count, width, height, favorite, createTime, note, subtype, codes) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?);",
+        )
+        .bind(&item.id)
+        .bind(&item.kind)
+        .bind(&item.group)
+        .bind(&item.value)
+        .bind(&item.search)
+        .bind(item.count)
+        .bind(item.width)
+        .bind(item.height)
+        .bind(item.favorite)
+        .bind(&item.create_time)
+        .bind(&item.note)
+        .bind(&item.subtype)
+        .bind(&item.codes)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_create_time(&self, id: &str, create_time: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE history SET createTime = ? WHERE id = ?;")
+            .bind(create_time)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    // 写回图片的识别结果
+    pub async fn update_search(
+        &self,
+        id: &str,
+        search: &str,
+        codes: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE history SET search = ?, codes = ? WHERE id = ?;")
+            .bind(search)
+            .bind(codes)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(&self, id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM history WHERE id = ?;")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nanoid::nanoid;
+    use std::{env, path::PathBuf};
+    use tauri::async_runtime::block_on;
+
+    // 测试结束后删除数据库文件
+    struct TempDatabase(PathBuf);
+
+    impl TempDatabase {
+        fn new() -> Self {
+            Self(env::temp_dir().join(format!("eco-paste-test-{}.db", nanoid!())))
+        }
+    }
+
+    impl Drop for TempDatabase {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    fn item(id: &str, group: &str, value: &str, create_time: &str) -> HistoryItem {
+        HistoryItem {
+            id: id.to_string(),
+            kind: group.to_string(),
+            group: group.to_string(),
+            value: value.to_string(),
+            search: Some(value.to_string()),
+            create_time: create_time.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn ids(items: &[HistoryItem]) -> Vec<&str> {
+        items.iter().map(|item| item.id.as_str()).collect()
+    }
+
+    #[test]
+    fn adds_codes_to_old_databases() {
+        let file = TempDatabase::new();
+
+        block_on(async {
+            let options = SqliteConnectOptions::new()
+                .filename(&file.0)
+                .create_if_missing(true);
+            let pool = SqlitePool::connect_with(options).await.unwrap();
+
+            sqlx::query(&CREATE_HISTORY_TABLE.replace(",\n        codes TEXT", ""))
+                .execute(&pool)
+                .await
+                .unwrap();
+            pool.close().await;
+
+            let database = Database::open(&file.0).await.unwrap();
+
+            let mut saved = item("1", "text", "text", "2024-01-01 00:00:00");
+            saved.codes = Some("[]".to_string());
+
+            database.insert(&saved).await.unwrap();
+
+            let loaded = database.get("1").await.unwrap().unwrap();
+
+            assert_eq!(loaded.codes.as_deref(), Some("[]"));
+        });
+    }
+
+    #[test]
+    fn selects_newest_first_with_filters() {
+        let file = TempDatabase::new();
+
+        block_on(async {
+            let database = Database::open(&file.0).await.unwrap();
+
+            let mut favorite = item("1", "text", "hello", "2024-01-01 00:00:00");
+            favorite.favorite = true;
+            let mut noted = item("2", "image", "image.png", "2024-01-02 00:00:00");
+            noted.note = Some("hello note".to_string());
+            let other = item("3", "text", "other", "2024-01-03 00:00:00");
+
+            for item in [&favorite, &noted, &other] {
+                database.insert(item).await.unwrap();
+            }
+
+            let select = |query: HistoryQuery| {
+                let database = &database;
+
+                async move { database.select(&query).await.unwrap() }
+            };
+
+            assert_eq!(ids(&select(HistoryQuery::default()).await), ["3", "2", "1"]);
+
+            let search = HistoryQuery {
+                search: Some("hello".to_string()),
+                ..Default::default()
+            };
+            assert_eq!(ids(&select(search).await), ["2", "1"]);
+
+            let group = HistoryQuery {
+                group: Some("text".to_string()),
+                ..Default::default()
+            };
+            assert_eq!(ids(&select(group).await), ["3", "1"]);
+
+            let not_favorite = HistoryQuery {
+                favorite: Some(false),
+                ..Default::default()
+            };
+            assert_eq!(ids(&select(not_favorite).await), ["3", "2"]);
+
+            let page = HistoryQuery {
+                limit: Some(1),
+                offset: Some(1),
+                ..Default::default()
+            };
+            assert_eq!(ids(&select(page).await), ["2"]);
+
+            // 只指定 OFFSET 时不限制条数
+            let offset = HistoryQuery {
+                offset: Some(1),
+                ..Default::default()
+            };
+            assert_eq!(ids(&select(offset).await), ["2", "1"]);
+        });
+    }
+
+    #[test]
+    fn finds_updates_and_deletes_items() {
+        let file = TempDatabase::new();
+
+        block_on(async {
+            let database = Database::open(&file.0).await.unwrap();
+
+            database
+                .insert(&item("1", "text", "hello", "2024-01-01 00:00:00"))
+                .await
+                .unwrap();
+
+            assert!(database.find("text", "hello").await.unwrap().is_some());
+            assert!(database.find("html", "hello").await.unwrap().is_none());
+
+            database
+                .update_create_time("1", "2024-02-01 00:00:00")
+                .await
+                .unwrap();
+            database
+                .update_search("1", "recognized", Some("[]"))
+                .await
+                .unwrap();
+
+            let updated = database.get("1").await.unwrap().unwrap();
+
+            assert_eq!(updated.create_time, "2024-02-01 00:00:00");
+            assert_eq!(updated.search.as_deref(), Some("recognized"));
+            assert_eq!(updated.codes.as_deref(), Some("[]"));
+
+            database.delete("1").await.unwrap();
+
+            assert!(database.get("1").await.unwrap().is_none());
+        });
+    }
+}
@@ -0,0 +1,269 @@
+use super::{
+    capture::{new_item, text_count},
+    database::{HistoryItem, HistoryQuery},
+    emit_added, format_date, resolve_item,
+    store::Store,
+    Daemon,
+};
+use crate::core::bridge::RECORDING_CHANGED_EVENT;
+use chrono::{Duration as ChronoDuration, Local};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::{
+    sync::atomic::Ordering,
+    thread::{sleep, spawn},
+    time::Duration,
+};
+use tauri::{async_runtime, AppHandle, Emitter, Manager};
+use tauri_plugin_eco_clipboard::ClipboardManager;
+
+fn to_value<T: serde::Serialize>(value: T) -> Result<Value, String> {
+    serde_json::to_value(value).map_err(|err| err.to_string())
+}
+
+fn parse_params<T: DeserializeOwned + Default>(params: &Value) -> Result<T, String> {
+    if params.is_null() {
+        return Ok(T::default());
+    }
+
+    serde_json::from_value(params.clone()).map_err(|err| err.to_string())
+}
+
+fn require_str<'a>(params: &'a Value, key: &str) -> Result<&'a str, String> {
+    params
+        .get(key)
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("Missing param: {}", key))
+}
+
+async fn require_item(daemon: &Daemon, params: &Value) -> Result<HistoryItem, String> {
+    let id = require_str(params, "id")?;
+
+    daemon
+        .database
+        .get(id)
+        .await
+        .map_err(|err| err.to_string())?
+        .ok_or_else(|| format!("No entry with id {}", id))
+}
+
+// 与前端的 `writeClipboard` 相同，纯文本粘贴时只写入搜索内容
+fn write_item(
+    app_handle: &AppHandle,
+    store: &Store,
+    item: &HistoryItem,
+    plain: bool,
+) -> Result<(), String> {
+    let manager = app_handle.state::<ClipboardManager>();
+    let search = item.search.clone().unwrap_or_default();
+    let paste_plain = store.clipboard_store.content.paste_plain;
+
+    let parse_files =
+        || serde_json::from_str::<Vec<String>>(&item.value).map_err(|err| err.to_string());
+
+    if plain {
+        return match item.kind.as_str() {
+            "files" => manager.write_text(parse_files()?.join("\n")),
+            _ => manager.write_text(search),
+        };
+    }
+
+    match item.kind.as_str() {
+        "rtf" if !paste_plain => manager.write_rtf(search, item.value.clone()),
+        "html" if !paste_plain => manager.write_html(search, item.value.clone()),
+        "rtf" | "html" => manager.write_text(search),
+        "image" => manager.write_image(&resolve_item(app_handle, store, item).value),
+        "files" => manager.write_files(parse_files()?),
+        _ => manager.write_text(item.value.clone()),
+    }
+}
+
+// 没有主窗口时直接粘贴到之前的窗口
+async fn paste() -> Result<Value, String> {
+    #[cfg(target_os = "macos")]
+    let outcome = tauri_plugin_eco_paste::paste_frontmost(None, None);
+
+    #[cfg(not(target_os = "macos"))]
+    let outcome = tauri_plugin_eco_paste::paste(None, None).await;
+
+    to_value(outcome.map_err(|err| err.to_string())?)
+}
+
+// 开始或停止监听剪贴板，并通知订阅了记录状态的调用方
+fn set_recording(app_handle: &AppHandle, recording: bool) -> Result<(), String> {
+    let manager = app_handle.state::<ClipboardManager>();
+
+    if recording {
+        manager.start_listen(app_handle.clone())?;
+    } else {
+        manager.stop_listen();
+    }
+
+    app_handle
+        .emit(RECORDING_CHANGED_EVENT, recording)
+        .map_err(|err| err.to_string())
+}
+
+// 暂停记录，指定秒数后自动恢复，期间再次暂停或恢复会取消自动恢复
+fn pause_recording(app_handle: &AppHandle, daemon: &Daemon, seconds: u64) -> Result<Value, String> {
+    let version = daemon.recording_version.fetch_add(1, Ordering::SeqCst) + 1;
+
+    set_recording(app_handle, false)?;
+
+    if seconds == 0 {
+        return Ok(json!({ "recording": false, "resumeAt": null }));
+    }
+
+    let handle = app_handle.clone();
+
+    spawn(move || {
+        sleep(Duration::from_secs(seconds));
+
+        let daemon = handle.state::<Daemon>();
+
+        if daemon.recording_version.load(Ordering::SeqCst) != version {
+            return;
+        }
+
+        if let Err(err) = set_recording(&handle, true) {
+            log::warn!("Failed to resume recording: {}", err);
+        }
+    });
+
+    let resume_at = Local::now() + ChronoDuration::seconds(seconds as i64);
+
+    Ok(json!({ "recording": false, "resumeAt": format_date(resume_at) }))
+}
+
+// 不需要界面就可以执行的方法，显示窗口和搜索需要界面
+const DAEMON_METHODS: &[&str] = &[
+    "history.query",
+    "history.get",
+    "history.copy",
+    "history.paste",
+    "history.add",
+    "clipboard.write",
+    "recording.pause",
+    "recording.resume",
+    "recording.status",
+];
+
+async fn execute(
+    app_handle: &AppHandle,
+    daemon: &Daemon,
+    method: &str,
+    params: &Value,
+) -> Result<Value, String> {
+    let store = Store::load(app_handle);
+
+    match method {
+        "history.query" => {
+            let query = parse_params::<HistoryQuery>(params)?;
+
+            let items = daemon
+                .database
+                .select(&query)
+                .await
+                .map_err(|err| err.to_string())?;
+
+            to_value(
+                items
+                    .iter()
+                    .map(|item| resolve_item(app_handle, &store, item))
+                    .collect::<Vec<_>>(),
+            )
+        }
+        "history.get" => {
+            let item = daemon
+                .database
+                .get(require_str(params, "id")?)
+                .await
+                .map_err(|err| err.to_string())?;
+
+            to_value(item.map(|item| resolve_item(app_handle, &store, &item)))
+        }
+        "history.copy" => {
+            let item = require_item(daemon, params).await?;
+
+            write_item(app_handle, &store, &item, false)?;
+
+            Ok(Value::Null)
+        }
+        "history.paste" => {
+            let item = require_item(daemon, params).await?;
+
+            let plain = params
+                .get("plain")
+                .and_then(Value::as_bool)
+                .unwrap_or_default();
+
+            write_item(app_handle, &store, &item, plain)?;
+
+            paste().await
+        }
+        "history.add" => {
+            let text = require_str(params, "text")?;
+
+            let item = new_item(
+                "text",
+                "text",
+                text.to_string(),
+                text.to_string(),
+                text_count(text),
+            );
+
+            daemon
+                .database
+                .insert(&item)
+                .await
+                .map_err(|err| err.to_string())?;
+
+            emit_added(app_handle, &store, &item);
+
+            to_value(resolve_item(app_handle, &store, &item))
+        }
+        "clipboard.write" => {
+            let text = require_str(params, "text")?;
+
+            app_handle
+                .state::<ClipboardManager>()
+                .write_text(text.to_string())?;
+
+            Ok(Value::Null)
+        }
+        "recording.pause" => {
+            let seconds = params
+                .get("seconds")
+                .and_then(Value::as_u64)
+                .unwrap_or_default();
+
+            pause_recording(app_handle, daemon, seconds)
+        }
+        "recording.resume" => {
+            daemon.recording_version.fetch_add(1, Ordering::SeqCst);
+
+            set_recording(app_handle, true)?;
+
+            Ok(json!({ "recording": true }))
+        }
+        "recording.status" => {
+            let recording = app_handle.state::<ClipboardManager>().is_listening();
+
+            Ok(json!({ "recording": recording }))
+        }
+        _ => Err(format!("unknown method: {}", method)),
+    }
+}
+
+// 守护模式下界面没有加载时由后台执行方法，返回 `None` 时需要界面执行
+pub fn call(app_handle: &AppHandle, method: &str, params: &Value) -> Option<Result<Value, String>> {
+    if !DAEMON_METHODS.contains(&method) {
+        return None;
+    }
+
+    let daemon = app_handle.try_state::<Daemon>()?;
+
+    Some(async_runtime::block_on(execute(
+        app_handle, &daemon, method, params,
+    )))
+}
@@ -0,0 +1,328 @@
+use crate::core::{
+    bridge::{is_bridge_ready, wait_bridge_ready, HISTORY_ADDED_EVENT, READY_TIMEOUT},
+    setup,
+};
+use chrono::{DateTime, Local, NaiveDateTime};
+use database::{Database, HistoryItem, HistoryQuery};
+use serde_json::Value;
+use std::{
+    env, fs,
+    iter::once,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc::channel,
+    },
+    thread::{sleep, spawn},
+    time::Duration,
+};
+use store::Store;
+use tauri::{async_runtime, AppHandle, Emitter, Listener, Manager};
+use tauri_plugin_eco_clipboard::{ClipboardManager, CLIPBOARD_UPDATE_EVENT};
+use tauri_plugin_eco_ocr::{OcrQueue, OCR_RESULT_EVENT};
+use tauri_plugin_eco_window::{
+    show_main_window, show_preference_window, unpin_sticky_window, PREFERENCE_WINDOW_LABEL,
+};
+
+mod capture;
+mod database;
+mod methods;
+mod store;
+
+pub use methods::*;
+
+// 以守护模式启动的参数
+pub const DAEMON_ARG: &str = "--daemon";
+
+// 清理过期历史记录的间隔，与偏好设置窗口相同
+const RETENTION_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
+// 历史记录创建时间的格式，与前端的 `formatDate` 相同
+const DATE_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+// 守护模式的状态，只在守护模式下管理
+pub struct Daemon {
+    database: Database,
+    // 界面是否已经创建
+    ui_spawned: AtomicBool,
+    // 每次暂停或恢复记录时递增，用于取消自动恢复
+    recording_version: AtomicU64,
+}
+
+pub fn is_daemon() -> bool {
+    env::args().skip(1).any(|arg| arg == DAEMON_ARG)
+}
+
+fn format_date(date: DateTime<Local>) -> String {
+    date.format(DATE_FORMAT).to_string()
+}
+
+fn now() -> String {
+    format_date(Local::now())
+}
+
+// 图片的历史记录使用完整的图片路径，与前端的 `resolveHistoryItem` 相同
+fn resolve_item(app_handle: &AppHandle, store: &Store, item: &HistoryItem) -> HistoryItem {
+    let mut item = item.clone();
+
+    if item.kind == "image" {
+        if let Ok(dir) = store.image_dir(app_handle) {
+            item.value = dir.join(&item.value).to_string_lossy().into_owned();
+        }
+    }
+
+    item
+}
+
+// 通知命令行、本地 api 等订阅了新增历史记录的调用方
+fn emit_added(app_handle: &AppHandle, store: &Store, item: &HistoryItem) {
+    let _ = app_handle.emit(HISTORY_ADDED_EVENT, resolve_item(app_handle, store, item));
+}
+
+impl Daemon {
+    // 与前端的 `deleteSQL` 相同，同时关闭钉住的窗口、取消识别和删除图片
+    async fn delete(
+        &self,
+        app_handle: &AppHandle,
+        store: &Store,
+        item: &HistoryItem,
+    ) -> Result<(), String> {
+        self.database
+            .delete(&item.id)
+            .await
+            .map_err(|err| err.to_string())?;
+
+        let _ = unpin_sticky_window(app_handle, &item.id);
+
+        if item.kind != "image" {
+            return Ok(());
+        }
+
+        app_handle.state::<OcrQueue>().cancel(&item.id);
+
+        let path = resolve_item(app_handle, store, item).value;
+
+        if fs::metadata(&path).is_ok() {
+            fs::remove_file(&path).map_err(|err| err.to_string())?;
+        }
+
+        Ok(())
+    }
+}
+
+// 创建界面，已经创建时不会重复创建，非守护模式下界面在启动时已经创建
+pub fn spawn_ui(app_handle: &AppHandle) {
+    let Some(daemon) = app_handle.try_state::<Daemon>() else {
+        return;
+    };
+
+    if daemon.ui_spawned.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let handle = app_handle.clone();
+
+    let result = app_handle.run_on_main_thread(move || match setup::create_windows(&handle) {
+        Ok((main_window, preference_window)) => {
+            setup::default(&handle, main_window, preference_window)
+        }
+        Err(err) => log::error!("Failed to create windows: {}", err),
+    });
+
+    if let Err(err) = result {
+        log::error!("Failed to spawn the ui: {}", err);
+    }
+}
+
+// 显示窗口，界面没有加载时先创建界面，加载完成后再显示
+pub fn show_ui(app_handle: &AppHandle, label: &str) {
+    let show = if label == PREFERENCE_WINDOW_LABEL {
+        show_preference_window
+    } else {
+        show_main_window
+    };
+
+    if is_bridge_ready(app_handle) {
+        return show(app_handle);
+    }
+
+    spawn_ui(app_handle);
+
+    let app_handle = app_handle.clone();
+
+    spawn(move || {
+        if wait_bridge_ready(&app_handle, READY_TIMEOUT) {
+            show(&app_handle);
+        }
+    });
+}
+
+// 界面加载完成后由前端保存历史记录，加载完成前由后台保存
+fn listen_clipboard(app_handle: &AppHandle) -> Result<(), String> {
+    let (sender, receiver) = channel();
+
+    let handle = app_handle.clone();
+
+    app_handle.listen(CLIPBOARD_UPDATE_EVENT, move |_| {
+        if !is_bridge_ready(&handle) {
+            let _ = sender.send(());
+        }
+    });
+
+    let handle = app_handle.clone();
+
+    // 依次读取剪贴板，避免重复保存相同的内容
+    spawn(move || {
+        for _ in receiver {
+            if let Err(err) = async_runtime::block_on(capture::capture(&handle)) {
+                log::warn!("Failed to capture the clipboard: {}", err);
+            }
+        }
+    });
+
+    app_handle
+        .state::<ClipboardManager>()
+        .start_listen(app_handle.clone())
+}
+
+// 与前端相同，二维码和条形码的内容也可以被搜索到
+async fn save_ocr_result(app_handle: &AppHandle, payload: Value) -> Result<(), String> {
+    let Some(id) = payload.get("id").and_then(Value::as_str) else {
+        return Ok(());
+    };
+
+    let text = |value: &Value| {
+        value
+            .get("text")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    let codes = payload
+        .get("codes")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+
+    let mut texts: Vec<String> = Vec::new();
+
+    for item in once(text(&payload["result"])).chain(codes.iter().map(text)) {
+        if !item.is_empty() && !texts.contains(&item) {
+            texts.push(item);
+        }
+    }
+
+    let codes = (!codes.is_empty()).then(|| Value::Array(codes).to_string());
+
+    app_handle
+        .state::<Daemon>()
+        .database
+        .update_search(id, &texts.join("\n"), codes.as_deref())
+        .await
+        .map_err(|err| err.to_string())
+}
+
+// 界面加载完成后由前端写回识别结果
+fn listen_ocr_result(app_handle: &AppHandle) {
+    let handle = app_handle.clone();
+
+    app_handle.listen(OCR_RESULT_EVENT, move |event| {
+        if is_bridge_ready(&handle) {
+            return;
+        }
+
+        let Ok(payload) = serde_json::from_str::<Value>(event.payload()) else {
+            return;
+        };
+
+        let handle = handle.clone();
+
+        async_runtime::spawn(async move {
+            if let Err(err) = save_ocr_result(&handle, payload).await {
+                log::warn!("Failed to save the ocr result: {}", err);
+            }
+        });
+    });
+}
+
+// 与偏好设置窗口相同，删除超过保留天数或条数的未收藏记录
+async fn clean_history(app_handle: &AppHandle) -> Result<(), String> {
+    let store = Store::load(app_handle);
+    let history = &store.clipboard_store.history;
+
+    if history.duration == 0 && history.max_count == 0 {
+        return Ok(());
+    }
+
+    let daemon = app_handle.state::<Daemon>();
+
+    let query = HistoryQuery {
+        favorite: Some(false),
+        ..Default::default()
+    };
+
+    let items = daemon
+        .database
+        .select(&query)
+        .await
+        .map_err(|err| err.to_string())?;
+
+    let now = Local::now().naive_local();
+
+    for (index, item) in items.iter().enumerate() {
+        let expired = history.duration > 0
+            && NaiveDateTime::parse_from_str(&item.create_time, DATE_FORMAT)
+                .is_ok_and(|time| (now - time).num_days() >= history.duration);
+
+        let over_max_count = history.max_count > 0 && index >= history.max_count;
+
+        if !expired && !over_max_count {
+            continue;
+        }
+
+        daemon.delete(app_handle, &store, item).await?;
+    }
+
+    Ok(())
+}
+
+fn clean_history_periodically(app_handle: &AppHandle) {
+    let app_handle = app_handle.clone();
+
+    spawn(move || loop {
+        if let Err(err) = async_runtime::block_on(clean_history(&app_handle)) {
+            log::warn!("Failed to clean the history: {}", err);
+        }
+
+        sleep(RETENTION_INTERVAL);
+    });
+}
+
+// 以守护模式运行：不创建界面，由后台记录剪贴板、保存历史记录、清理过期记录和识别图片
+// 同步由前端的同步插件完成，服务端配置和登录状态保存在界面的本地存储中，创建界面后才开始同步
+pub fn start(app_handle: &AppHandle) -> Result<(), String> {
+    let path = Store::load(app_handle)
+        .database_path(app_handle)
+        .map_err(|err| err.to_string())?;
+
+    let database = async_runtime::block_on(Database::open(&path))
+        .map_err(|err| format!("Failed to open the database {:?}: {}", path, err))?;
+
+    app_handle.manage(Daemon {
+        database,
+        ui_spawned: AtomicBool::default(),
+        recording_version: AtomicU64::default(),
+    });
+
+    // 隐藏 mac 的程序坞图标，与创建界面后相同
+    #[cfg(target_os = "macos")]
+    let _ = app_handle.set_dock_visibility(false);
+
+    log::info!("Running as a daemon, sync starts after the window is created");
+
+    listen_ocr_result(app_handle);
+
+    clean_history_periodically(app_handle);
+
+    listen_clipboard(app_handle)
+}
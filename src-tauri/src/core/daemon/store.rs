@@ -0,0 +1,107 @@
+use serde::Deserialize;
+use std::{fs, path::PathBuf};
+use tauri::{AppHandle, Manager};
+
+// 前端保存的配置项文件，开发模式使用单独的文件
+#[cfg(debug_assertions)]
+const STORE_FILE: &str = ".store.dev.json";
+
+#[cfg(not(debug_assertions))]
+const STORE_FILE: &str = ".store.json";
+
+// 数据库文件的扩展名
+#[cfg(debug_assertions)]
+const DATABASE_EXTNAME: &str = "dev.db";
+
+#[cfg(not(debug_assertions))]
+const DATABASE_EXTNAME: &str = "db";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct Env {
+    save_data_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct GlobalStore {
+    env: Env,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ContentStore {
+    pub ocr: bool,
+    pub copy_plain: bool,
+    pub paste_plain: bool,
+    pub auto_sort: bool,
+}
+
+impl Default for ContentStore {
+    fn default() -> Self {
+        Self {
+            ocr: true,
+            copy_plain: false,
+            paste_plain: false,
+            auto_sort: false,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistoryStore {
+    // 保留的天数，0 为不限制
+    pub duration: i64,
+    // 保留的条数，0 为不限制
+    pub max_count: usize,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ClipboardStore {
+    pub content: ContentStore,
+    pub history: HistoryStore,
+}
+
+// 前端的配置项，只读取守护模式需要的部分
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Store {
+    global_store: GlobalStore,
+    pub clipboard_store: ClipboardStore,
+}
+
+impl Store {
+    // 每次使用时重新读取，界面修改的配置项可以立即生效
+    pub fn load(app_handle: &AppHandle) -> Self {
+        let Ok(dir) = app_handle.path().app_data_dir() else {
+            return Self::default();
+        };
+
+        fs::read_to_string(dir.join(STORE_FILE))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    // 存储数据的目录，未设置时为应用的数据目录
+    pub fn save_data_dir(&self, app_handle: &AppHandle) -> tauri::Result<PathBuf> {
+        match &self.global_store.env.save_data_dir {
+            Some(dir) => Ok(dir.clone()),
+            None => app_handle.path().app_data_dir(),
+        }
+    }
+
+    pub fn database_path(&self, app_handle: &AppHandle) -> tauri::Result<PathBuf> {
+        let name = &app_handle.package_info().name;
+
+        Ok(self
+            .save_data_dir(app_handle)?
+            .join(format!("{}.{}", name, DATABASE_EXTNAME)))
+    }
+
+    pub fn image_dir(&self, app_handle: &AppHandle) -> tauri::Result<PathBuf> {
+        Ok(self.save_data_dir(app_handle)?.join("images"))
+    }
+}
@@ -0,0 +1,167 @@
+use crate::core::bridge::{call, listen_bridge_events, summarize, BridgeEvent};
+use serde_json::{json, Value};
+use tauri::{async_runtime, AppHandle};
+use zbus::{connection, fdo, interface, object_server::SignalContext, Connection};
+
+// 会话总线上的名称和对象路径
+const BUS_NAME: &str = "org.ecopaste.Clipboard";
+const OBJECT_PATH: &str = "/org/ecopaste/Clipboard";
+
+// 历史记录的条目：id、类型、摘要
+type Entry = (String, String, String);
+
+fn to_entry(item: &Value) -> Entry {
+    let field = |key: &str| {
+        item.get(key)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    (field("id"), field("type"), summarize(item))
+}
+
+// 桥接的方法会阻塞到主窗口返回结果，在单独的线程中调用
+async fn call_bridge(
+    app_handle: &AppHandle,
+    method: &'static str,
+    params: Value,
+) -> fdo::Result<Value> {
+    let app_handle = app_handle.clone();
+
+    async_runtime::spawn_blocking(move || call(&app_handle, method, params))
+        .await
+        .map_err(|err| fdo::Error::Failed(err.to_string()))?
+        .map_err(fdo::Error::Failed)
+}
+
+struct Clipboard {
+    app_handle: AppHandle,
+}
+
+impl Clipboard {
+    // `limit` 为 0 时返回全部
+    async fn query(&self, search: Option<&str>, limit: u32) -> fdo::Result<Vec<Entry>> {
+        let params = json!({
+            "search": search,
+            "limit": (limit > 0).then_some(limit),
+        });
+
+        let items = call_bridge(&self.app_handle, "history.query", params).await?;
+
+        Ok(items
+            .as_array()
+            .map(|items| items.iter().map(to_entry).collect())
+            .unwrap_or_default())
+    }
+}
+
+#[interface(name = "org.ecopaste.Clipboard")]
+impl Clipboard {
+    // 显示主窗口
+    async fn show(&self) -> fdo::Result<()> {
+        call_bridge(&self.app_handle, "window.show", Value::Null).await?;
+
+        Ok(())
+    }
+
+    // 最新的历史记录
+    async fn list(&self, limit: u32) -> fdo::Result<Vec<Entry>> {
+        self.query(None, limit).await
+    }
+
+    // 搜索历史记录
+    async fn search(&self, query: &str, limit: u32) -> fdo::Result<Vec<Entry>> {
+        self.query(Some(query), limit).await
+    }
+
+    // 粘贴历史记录到当前聚焦的应用
+    async fn activate(&self, id: &str) -> fdo::Result<()> {
+        call_bridge(&self.app_handle, "history.paste", json!({ "id": id })).await?;
+
+        Ok(())
+    }
+
+    // 把历史记录复制到剪贴板
+    async fn copy(&self, id: &str) -> fdo::Result<()> {
+        call_bridge(&self.app_handle, "history.copy", json!({ "id": id })).await?;
+
+        Ok(())
+    }
+
+    // 是否正在记录
+    async fn is_recording(&self) -> fdo::Result<bool> {
+        let result = call_bridge(&self.app_handle, "recording.status", Value::Null).await?;
+
+        Ok(result
+            .get("recording")
+            .and_then(Value::as_bool)
+            .unwrap_or_default())
+    }
+
+    // 新增了历史记录
+    #[zbus(signal)]
+    async fn history_added(
+        ctxt: &SignalContext<'_>,
+        id: &str,
+        kind: &str,
+        summary: &str,
+    ) -> zbus::Result<()>;
+
+    // 暂停或恢复了记录
+    #[zbus(signal)]
+    async fn recording_changed(ctxt: &SignalContext<'_>, recording: bool) -> zbus::Result<()>;
+}
+
+async fn serve(app_handle: &AppHandle) -> zbus::Result<Connection> {
+    let clipboard = Clipboard {
+        app_handle: app_handle.clone(),
+    };
+
+    connection::Builder::session()?
+        .name(BUS_NAME)?
+        .serve_at(OBJECT_PATH, clipboard)?
+        .build()
+        .await
+}
+
+async fn emit_signal(connection: &Connection, event: BridgeEvent) -> zbus::Result<()> {
+    let ctxt = SignalContext::new(connection, OBJECT_PATH)?;
+
+    match event {
+        BridgeEvent::HistoryAdded(item) => {
+            let (id, kind, summary) = to_entry(&item);
+
+            Clipboard::history_added(&ctxt, &id, &kind, &summary).await
+        }
+        BridgeEvent::RecordingChanged(recording) => {
+            Clipboard::recording_changed(&ctxt, recording).await
+        }
+    }
+}
+
+// 在会话总线上提供 D-Bus 服务，供 GNOME Shell 扩展、KDE Plasma 小部件等桌面集成使用
+pub fn listen_dbus(app_handle: &AppHandle) {
+    let app_handle = app_handle.clone();
+
+    async_runtime::spawn(async move {
+        let connection = match serve(&app_handle).await {
+            Ok(connection) => connection,
+            Err(err) => {
+                log::warn!("Failed to start the dbus service: {}", err);
+
+                return;
+            }
+        };
+
+        listen_bridge_events(&app_handle, move |event| {
+            let connection = connection.clone();
+
+            async_runtime::spawn(async move {
+                if let Err(err) = emit_signal(&connection, event).await {
+                    log::warn!("Failed to emit dbus signal: {}", err);
+                }
+            });
+        });
+    });
+}
@@ -1,2 +1,9 @@
+pub mod bridge;
+pub mod cli;
+pub mod daemon;
+#[cfg(target_os = "linux")]
+pub mod dbus;
 pub mod prevent_default;
+#[cfg(unix)]
+pub mod rpc;
 pub mod setup;
@@ -0,0 +1,357 @@
+use crate::core::bridge::{call, listen_bridge_events, BridgeEvent, BRIDGE_METHODS};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    env,
+    fs::{self, DirBuilder, Permissions},
+    io::{self, BufRead, BufReader, Write},
+    os::unix::{
+        fs::{DirBuilderExt, PermissionsExt},
+        net::{UnixListener, UnixStream},
+    },
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    thread::spawn,
+    time::Duration,
+};
+use tauri::{AppHandle, Manager};
+
+// 套接字文件的名称，开发模式使用单独的名称，避免与安装的应用冲突
+#[cfg(debug_assertions)]
+const SOCKET_NAME: &str = "xeco-paste-dev.sock";
+
+#[cfg(not(debug_assertions))]
+const SOCKET_NAME: &str = "xeco-paste.sock";
+
+// 没有 `$XDG_RUNTIME_DIR` 时，在临时目录中创建的目录
+const FALLBACK_SOCKET_DIR: &str = "xeco-paste";
+
+// 写入响应和通知的超时时间
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+// 可以订阅的事件
+const HISTORY_ADDED: &str = "history.added";
+const RECORDING_CHANGED: &str = "recording.changed";
+const SUBSCRIBE_EVENTS: [&str; 2] = [HISTORY_ADDED, RECORDING_CHANGED];
+
+// JSON-RPC 2.0 的错误码
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+
+#[derive(Debug, Deserialize)]
+struct RpcRequest {
+    jsonrpc: String,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Serialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct RpcResponse {
+    jsonrpc: &'static str,
+    id: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcError>,
+}
+
+impl RpcResponse {
+    fn new(id: Value, result: Result<Value, RpcError>) -> Self {
+        let (result, error) = match result {
+            Ok(result) => (Some(result), None),
+            Err(error) => (None, Some(error)),
+        };
+
+        Self {
+            jsonrpc: "2.0",
+            id,
+            result,
+            error,
+        }
+    }
+}
+
+// 发送到客户端的通知
+#[derive(Debug, Serialize)]
+struct RpcNotification<'a> {
+    jsonrpc: &'static str,
+    method: &'a str,
+    params: Value,
+}
+
+// 同一个连接的响应和通知可能来自不同的线程，写入时需要加锁
+type Writer = Arc<Mutex<UnixStream>>;
+
+fn write_line<T: Serialize>(writer: &Writer, value: &T) -> io::Result<()> {
+    let mut content = serde_json::to_string(value)?;
+    content.push('\n');
+
+    writer.lock().unwrap().write_all(content.as_bytes())
+}
+
+// 订阅了事件的连接
+struct Subscriber {
+    events: Vec<String>,
+    writer: Writer,
+}
+
+#[derive(Default)]
+struct RpcSubscribers {
+    next_id: AtomicU64,
+    subscribers: Mutex<HashMap<u64, Subscriber>>,
+}
+
+impl RpcSubscribers {
+    fn subscribe(&self, connection: u64, events: Vec<String>, writer: Writer) {
+        self.subscribers
+            .lock()
+            .unwrap()
+            .insert(connection, Subscriber { events, writer });
+    }
+
+    fn unsubscribe(&self, connection: u64) {
+        self.subscribers.lock().unwrap().remove(&connection);
+    }
+
+    // 通知订阅了事件的连接，写入失败的连接已经断开，不再通知
+    fn notify(&self, event: &str, params: Value) {
+        let notification = RpcNotification {
+            jsonrpc: "2.0",
+            method: event,
+            params,
+        };
+
+        self.subscribers.lock().unwrap().retain(|_, subscriber| {
+            if !subscriber.events.iter().any(|item| item == event) {
+                return true;
+            }
+
+            write_line(&subscriber.writer, &notification).is_ok()
+        });
+    }
+}
+
+// 解析订阅的事件，未指定时订阅全部事件
+fn parse_events(params: &Value) -> Result<Vec<String>, RpcError> {
+    let Some(events) = params.get("events") else {
+        return Ok(SUBSCRIBE_EVENTS.map(String::from).to_vec());
+    };
+
+    let events: Vec<String> = serde_json::from_value(events.clone())
+        .map_err(|err| RpcError::new(INVALID_PARAMS, err.to_string()))?;
+
+    match events
+        .iter()
+        .find(|event| !SUBSCRIBE_EVENTS.contains(&event.as_str()))
+    {
+        Some(event) => Err(RpcError::new(
+            INVALID_PARAMS,
+            format!("unknown event: {}", event),
+        )),
+        None => Ok(events),
+    }
+}
+
+fn execute(
+    app_handle: &AppHandle,
+    connection: u64,
+    writer: &Writer,
+    request: RpcRequest,
+) -> Result<Value, RpcError> {
+    if request.jsonrpc != "2.0" {
+        return Err(RpcError::new(INVALID_REQUEST, "jsonrpc must be \"2.0\""));
+    }
+
+    if !request.params.is_null() && !request.params.is_object() {
+        return Err(RpcError::new(INVALID_PARAMS, "params must be an object"));
+    }
+
+    let subscribers = app_handle.state::<RpcSubscribers>();
+
+    match request.method.as_str() {
+        "events.subscribe" => {
+            let events = parse_events(&request.params)?;
+
+            subscribers.subscribe(connection, events.clone(), writer.clone());
+
+            Ok(json!({ "events": events }))
+        }
+        "events.unsubscribe" => {
+            subscribers.unsubscribe(connection);
+
+            Ok(Value::Null)
+        }
+        method if BRIDGE_METHODS.contains(&method) => {
+            call(app_handle, method, request.params).map_err(|err| RpcError::new(SERVER_ERROR, err))
+        }
+        method => Err(RpcError::new(
+            METHOD_NOT_FOUND,
+            format!("method not found: {}", method),
+        )),
+    }
+}
+
+// 每行一个请求
+fn serve(
+    app_handle: &AppHandle,
+    connection: u64,
+    stream: UnixStream,
+    writer: &Writer,
+) -> io::Result<()> {
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let request = match serde_json::from_str::<Value>(&line) {
+            Ok(value) if value.is_object() => value,
+            // 不支持批量请求
+            Ok(_) => {
+                let error = RpcError::new(INVALID_REQUEST, "request must be an object");
+
+                write_line(writer, &RpcResponse::new(Value::Null, Err(error)))?;
+
+                continue;
+            }
+            Err(err) => {
+                let error = RpcError::new(PARSE_ERROR, err.to_string());
+
+                write_line(writer, &RpcResponse::new(Value::Null, Err(error)))?;
+
+                continue;
+            }
+        };
+
+        // 没有 id 的请求为通知，不需要返回结果
+        let id = request.get("id").cloned();
+
+        let result = serde_json::from_value::<RpcRequest>(request)
+            .map_err(|err| RpcError::new(INVALID_REQUEST, err.to_string()))
+            .and_then(|request| execute(app_handle, connection, writer, request));
+
+        let Some(id) = id else {
+            continue;
+        };
+
+        write_line(writer, &RpcResponse::new(id, result))?;
+    }
+
+    Ok(())
+}
+
+// 连接断开时取消订阅
+fn handle_stream(app_handle: &AppHandle, stream: UnixStream) -> io::Result<()> {
+    let connection = app_handle
+        .state::<RpcSubscribers>()
+        .next_id
+        .fetch_add(1, Ordering::Relaxed);
+
+    // 客户端不读取时不会一直阻塞其它连接的通知
+    let write_stream = stream.try_clone()?;
+    write_stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+
+    let writer: Writer = Arc::new(Mutex::new(write_stream));
+
+    let result = serve(app_handle, connection, stream, &writer);
+
+    app_handle.state::<RpcSubscribers>().unsubscribe(connection);
+
+    result
+}
+
+// 优先使用 `$XDG_RUNTIME_DIR`，该目录只有当前用户可以访问
+pub(crate) fn socket_dir() -> io::Result<PathBuf> {
+    if let Some(dir) = env::var_os("XDG_RUNTIME_DIR") {
+        return Ok(PathBuf::from(dir));
+    }
+
+    let dir = env::temp_dir().join(FALLBACK_SOCKET_DIR);
+
+    DirBuilder::new().recursive(true).mode(0o700).create(&dir)?;
+
+    // 目录已存在时也限制为只有当前用户可以访问，不属于当前用户时会失败
+    fs::set_permissions(&dir, Permissions::from_mode(0o700))?;
+
+    Ok(dir)
+}
+
+fn bind() -> io::Result<UnixListener> {
+    let path = socket_dir()?.join(SOCKET_NAME);
+
+    // 只有一个实例在运行，残留的套接字文件来自上次异常退出
+    if path.exists() {
+        fs::remove_file(&path)?;
+    }
+
+    let listener = UnixListener::bind(&path)?;
+
+    fs::set_permissions(&path, Permissions::from_mode(0o600))?;
+
+    Ok(listener)
+}
+
+// 在本地套接字上提供 JSON-RPC 2.0 接口，供编辑器插件、脚本等集成使用
+pub fn listen_rpc(app_handle: &AppHandle) {
+    let listener = match bind() {
+        Ok(listener) => listener,
+        Err(err) => {
+            log::warn!("Failed to start the rpc server: {}", err);
+
+            return;
+        }
+    };
+
+    app_handle.manage(RpcSubscribers::default());
+
+    let handle = app_handle.clone();
+
+    listen_bridge_events(app_handle, move |event| {
+        let subscribers = handle.state::<RpcSubscribers>();
+
+        match event {
+            BridgeEvent::HistoryAdded(item) => subscribers.notify(HISTORY_ADDED, item),
+            BridgeEvent::RecordingChanged(recording) => {
+                subscribers.notify(RECORDING_CHANGED, json!({ "recording": recording }))
+            }
+        }
+    });
+
+    let app_handle = app_handle.clone();
+
+    spawn(move || {
+        for stream in listener.incoming().filter_map(Result::ok) {
+            let app_handle = app_handle.clone();
+
+            spawn(move || {
+                if let Err(err) = handle_stream(&app_handle, stream) {
+                    log::warn!("Failed to handle rpc connection: {}", err);
+                }
+            });
+        }
+    });
+}
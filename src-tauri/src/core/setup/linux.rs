@@ -1,8 +1,30 @@
+use gtk::{gdk::WindowTypeHint, prelude::GtkWindowExt};
 use tauri::{AppHandle, WebviewWindow};
 
 pub fn platform(
     _app_handle: &AppHandle,
-    _main_window: WebviewWindow,
+    main_window: WebviewWindow,
     _preference_window: WebviewWindow,
 ) {
+    let Ok(gtk_window) = main_window.gtk_window() else {
+        return;
+    };
+
+    // 以下设置需要在窗口显示前完成，由 gtk 转换为 EWMH 的窗口类型和 _NET_WM_STATE
+
+    // 作为工具窗口，窗口管理器不会把它当作普通的应用窗口
+    gtk_window.set_type_hint(WindowTypeHint::Utility);
+
+    // 不在任务栏和工作区切换器中显示：_NET_WM_STATE_SKIP_TASKBAR、_NET_WM_STATE_SKIP_PAGER
+    gtk_window.set_skip_taskbar_hint(true);
+    gtk_window.set_skip_pager_hint(true);
+
+    // 在其它窗口之上：_NET_WM_STATE_ABOVE
+    gtk_window.set_keep_above(true);
+
+    // 在所有工作区中显示：_NET_WM_STATE_STICKY
+    gtk_window.stick();
+
+    // 不设置 focus_on_map(false)：搜索框需要在显示时获得焦点，
+    // 粘贴目标的记录已经忽略了自身的窗口，不受主窗口获得焦点的影响
 }
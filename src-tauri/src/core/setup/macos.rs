@@ -1,15 +1,11 @@
-use tauri::{AppHandle, Emitter, EventTarget, WebviewWindow};
+use tauri::{AppHandle, WebviewWindow};
 use tauri_nspanel::{cocoa::appkit::NSWindowCollectionBehavior, panel_delegate, WebviewWindowExt};
-use tauri_plugin_eco_window::MAIN_WINDOW_LABEL;
+use tauri_plugin_eco_window::{emit_focus, emit_moved, emit_resized, MAIN_WINDOW_LABEL};
 
 #[allow(non_upper_case_globals)]
 const NSWindowStyleMaskNonActivatingPanel: i32 = 1 << 7;
 #[allow(non_upper_case_globals)]
 const NSResizableWindowMask: i32 = 1 << 3;
-const WINDOW_FOCUS_EVENT: &str = "tauri://focus";
-const WINDOW_BLUR_EVENT: &str = "tauri://blur";
-const WINDOW_MOVED_EVENT: &str = "tauri://move";
-const WINDOW_RESIZED_EVENT: &str = "tauri://resize";
 
 pub fn platform(
     app_handle: &AppHandle,
@@ -46,35 +42,19 @@ pub fn platform(
         window_did_move
     });
 
-    // 为 delegate 设置事件监听器
-    delegate.set_listener(Box::new(move |delegate_name: String| {
-        let target = EventTarget::labeled(MAIN_WINDOW_LABEL);
-
-        let window_move_event = || {
-            if let Ok(position) = main_window.outer_position() {
-                let _ = main_window.emit_to(target.clone(), WINDOW_MOVED_EVENT, position);
-            }
-        };
+    let app_handle = app_handle.clone();
 
+    // 为 delegate 设置事件监听器，与其它平台发送相同的窗口事件
+    delegate.set_listener(Box::new(move |delegate_name: String| {
         match delegate_name.as_str() {
             // 当窗口获得键盘焦点时调用
-            "window_did_become_key" => {
-                let _ = main_window.emit_to(target, WINDOW_FOCUS_EVENT, true);
-            }
+            "window_did_become_key" => emit_focus(&app_handle, MAIN_WINDOW_LABEL, true),
             // 当窗口失去键盘焦点时调用
-            "window_did_resign_key" => {
-                let _ = main_window.emit_to(target, WINDOW_BLUR_EVENT, true);
-            }
+            "window_did_resign_key" => emit_focus(&app_handle, MAIN_WINDOW_LABEL, false),
             // 当窗口大小改变时调用
-            "window_did_resize" => {
-                window_move_event();
-
-                if let Ok(size) = main_window.inner_size() {
-                    let _ = main_window.emit_to(target, WINDOW_RESIZED_EVENT, size);
-                }
-            }
+            "window_did_resize" => emit_resized(&app_handle, MAIN_WINDOW_LABEL),
             // 当窗口位置改变时调用
-            "window_did_move" => window_move_event(),
+            "window_did_move" => emit_moved(&app_handle, MAIN_WINDOW_LABEL),
             _ => (),
         }
     }));
@@ -1,4 +1,5 @@
-use tauri::{AppHandle, WebviewWindow};
+use tauri::{AppHandle, Manager, WebviewWindow, WebviewWindowBuilder};
+use tauri_plugin_eco_window::{MAIN_WINDOW_LABEL, PREFERENCE_WINDOW_LABEL};
 
 #[cfg(target_os = "macos")]
 mod macos;
@@ -29,3 +30,23 @@ pub fn default(
 
     platform(app_handle, main_window.clone(), preference_window.clone());
 }
+
+// 根据配置文件创建主窗口和偏好设置窗口，守护模式下需要界面时才创建
+pub fn create_windows(app_handle: &AppHandle) -> tauri::Result<(WebviewWindow, WebviewWindow)> {
+    let create_window = |label: &str| {
+        let config = app_handle
+            .config()
+            .app
+            .windows
+            .iter()
+            .find(|config| config.label == label)
+            .ok_or(tauri::Error::WindowNotFound)?;
+
+        WebviewWindowBuilder::from_config(app_handle, config)?.build()
+    };
+
+    Ok((
+        create_window(MAIN_WINDOW_LABEL)?,
+        create_window(PREFERENCE_WINDOW_LABEL)?,
+    ))
+}
@@ -1,34 +1,61 @@
 mod core;
 
-use core::{prevent_default, setup};
-use tauri::{generate_context, Builder, Manager, WindowEvent};
+use core::{bridge, cli, daemon, prevent_default, setup};
+use tauri::{generate_context, Builder, WindowEvent};
 use tauri_plugin_autostart::MacosLauncher;
-use tauri_plugin_eco_window::{show_main_window, MAIN_WINDOW_LABEL, PREFERENCE_WINDOW_LABEL};
+use tauri_plugin_eco_window::MAIN_WINDOW_LABEL;
 use tauri_plugin_log::{Target, TargetKind};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    // 命令行参数转发给正在运行的实例执行
+    if let Some(code) = cli::forward_args() {
+        std::process::exit(code);
+    }
+
         let app = Builder::default()
         .setup(|app| {
             let app_handle = app.handle();
 
-            let main_window = app.get_webview_window(MAIN_WINDOW_LABEL).unwrap();
-
-            let preference_window = app.get_webview_window(PREFERENCE_WINDOW_LABEL).unwrap();
+            // 外部调用的方法交给主窗口执行
+            bridge::listen_bridge(app_handle);
 
             // 注册托盘事件处理（不创建托盘图标，由前端管理）
             // 托盘图标由前端 useTray Hook 统一管理
 
             // ... existing code ...
 
-            setup::default(&app_handle, main_window.clone(), preference_window.clone());
+            // 守护模式下不创建界面，需要时再创建
+            if daemon::is_daemon() {
+                daemon::start(app_handle)?;
+            } else {
+                let (main_window, preference_window) = setup::create_windows(app_handle)?;
+
+                setup::default(app_handle, main_window, preference_window);
+            }
+
+            // 接收命令行转发的请求
+            cli::listen_cli(app_handle);
+
+            // 本地的 JSON-RPC 接口
+            #[cfg(unix)]
+            core::rpc::listen_rpc(app_handle);
+
+            // 桌面集成使用的 D-Bus 服务
+            #[cfg(target_os = "linux")]
+            core::dbus::listen_dbus(app_handle);
 
             Ok(())
         })
         // 确保在 windows 和 linux 上只有一个 app 实例在运行：https://github.com/tauri-apps/plugins-workspace/tree/v2/plugins/single-instance
         .plugin(tauri_plugin_single_instance::init(
-            |app_handle, _argv, _cwd| {
-                show_main_window(app_handle);
+            |app_handle, argv, _cwd| {
+                // 已经在运行时再次以守护模式启动不需要显示窗口
+                if argv.iter().any(|arg| arg == daemon::DAEMON_ARG) {
+                    return;
+                }
+
+                daemon::show_ui(app_handle, MAIN_WINDOW_LABEL);
             },
         ))
         // app 自启动：https://github.com/tauri-apps/tauri-plugin-autostart/tree/v2
@@ -106,7 +133,7 @@ pub fn run() {
                 return;
             }
 
-            tauri_plugin_eco_window::show_preference_window(app_handle);
+            daemon::show_ui(app_handle, tauri_plugin_eco_window::PREFERENCE_WINDOW_LABEL);
         }
         _ => {
             let _ = app_handle;
@@ -5,15 +5,20 @@ use clipboard_rs::{
 use std::{
     fs::create_dir_all,
     hash::{DefaultHasher, Hash, Hasher},
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
     thread::spawn,
 };
 use tauri::{command, AppHandle, Emitter, Runtime, State};
 
+// 剪贴板变更时发送的事件
+pub const CLIPBOARD_UPDATE_EVENT: &str = "plugin:eco-clipboard://clipboard_update";
+
 pub struct ClipboardManager {
     context: Arc<Mutex<ClipboardContext>>,
     watcher_shutdown: Arc<Mutex<Option<WatcherShutdown>>>,
+    // 应用自身写入的文本，剪贴板变更为该内容时不会被当作用户复制
+    self_write: Arc<Mutex<Option<String>>>,
 }
 
 struct ClipboardListen<R>
@@ -21,6 +26,8 @@ where
     R: Runtime,
 {
     app_handle: AppHandle<R>,
+    context: Arc<Mutex<ClipboardContext>>,
+    self_write: Arc<Mutex<Option<String>>>,
 }
 
 impl ClipboardManager {
@@ -28,20 +35,259 @@ impl ClipboardManager {
         ClipboardManager {
             context: Arc::new(Mutex::new(ClipboardContext::new().unwrap())),
             watcher_shutdown: Arc::default(),
+            self_write: Arc::default(),
         }
     }
 
     fn has(&self, format: ContentFormat) -> bool {
         self.context.lock().unwrap().has(format)
     }
+
+    // 开始监听剪贴板的变更，变更时发送 `clipboard_update` 事件
+    pub fn start_listen<R: Runtime>(&self, app_handle: AppHandle<R>) -> Result<(), String> {
+        let mut watcher_shutdown_state = self.watcher_shutdown.lock().unwrap();
+
+        if (*watcher_shutdown_state).is_some() {
+            return Ok(());
+        }
+
+        let listener =
+            ClipboardListen::new(app_handle, self.context.clone(), self.self_write.clone());
+
+        let mut watcher: ClipboardWatcherContext<ClipboardListen<R>> =
+            ClipboardWatcherContext::new().map_err(|err| err.to_string())?;
+
+        let watcher_shutdown = watcher.add_handler(listener).get_shutdown_channel();
+
+        *watcher_shutdown_state = Some(watcher_shutdown);
+
+        spawn(move || {
+            watcher.start_watch();
+        });
+
+        Ok(())
+    }
+
+    pub fn stop_listen(&self) {
+        if let Some(watcher_shutdown) = self.watcher_shutdown.lock().unwrap().take() {
+            watcher_shutdown.stop();
+        }
+    }
+
+    // 是否正在监听剪贴板
+    pub fn is_listening(&self) -> bool {
+        self.watcher_shutdown.lock().unwrap().is_some()
+    }
+
+    pub fn has_files(&self) -> bool {
+        self.has(ContentFormat::Files)
+    }
+
+    pub fn has_image(&self) -> bool {
+        self.has(ContentFormat::Image)
+    }
+
+    pub fn has_html(&self) -> bool {
+        self.has(ContentFormat::Html)
+    }
+
+    pub fn has_rtf(&self) -> bool {
+        self.has(ContentFormat::Rtf)
+    }
+
+    pub fn has_text(&self) -> bool {
+        self.has(ContentFormat::Text)
+    }
+
+    pub fn read_files(&self) -> Result<Vec<String>, String> {
+        let mut files = self
+            .context
+            .lock()
+            .map_err(|err| err.to_string())?
+            .get_files()
+            .map_err(|err| err.to_string())?;
+
+        files.iter_mut().for_each(|path| {
+            *path = path.replace("file://", "");
+        });
+
+        Ok(files)
+    }
+
+    // 把剪贴板中的图片保存到指定目录，文件名为缩略图的哈希
+    pub fn read_image(&self, path: &Path) -> Result<ReadImage, String> {
+        create_dir_all(path).map_err(|op| op.to_string())?;
+
+        let image = self
+            .context
+            .lock()
+            .map_err(|err| err.to_string())?
+            .get_image()
+            .map_err(|err| err.to_string())?;
+
+        let (width, height) = image.get_size();
+
+        let thumbnail_image = image
+            .thumbnail(width / 10, height / 10)
+            .map_err(|err| err.to_string())?;
+
+        let bytes = thumbnail_image
+            .to_png()
+            .map_err(|err| err.to_string())?
+            .get_bytes()
+            .to_vec();
+
+        let mut hasher = DefaultHasher::new();
+
+        bytes.hash(&mut hasher);
+
+        let hash = hasher.finish();
+
+        let image_path = path.join(format!("{hash}.png"));
+
+        if let Some(path) = image_path.to_str() {
+            image.save_to_path(path).map_err(|err| err.to_string())?;
+
+            let image = path.to_string();
+
+            return Ok(ReadImage {
+                width,
+                height,
+                image,
+            });
+        }
+
+        Err("read_image execution error".to_string())
+    }
+
+    pub fn read_html(&self) -> Result<String, String> {
+        self.context
+            .lock()
+            .map_err(|err| err.to_string())?
+            .get_html()
+            .map_err(|err| err.to_string())
+    }
+
+    pub fn read_rtf(&self) -> Result<String, String> {
+        self.context
+            .lock()
+            .map_err(|err| err.to_string())?
+            .get_rich_text()
+            .map_err(|err| err.to_string())
+    }
+
+    pub fn read_text(&self) -> Result<String, String> {
+        self.context
+            .lock()
+            .map_err(|err| err.to_string())?
+            .get_text()
+            .map_err(|err| err.to_string())
+    }
+
+    pub fn write_files(&self, value: Vec<String>) -> Result<(), String> {
+        self.context
+            .lock()
+            .map_err(|err| err.to_string())?
+            .set_files(value)
+            .map_err(|err| err.to_string())
+    }
+
+    pub fn write_image(&self, value: &str) -> Result<(), String> {
+        // 尝试从路径创建 RustImageData，如果失败则返回错误信息
+        let image = RustImageData::from_path(value).map_err(|err| err.to_string())?;
+
+        // 尝试获取锁并设置图像，如果失败则返回错误信息
+        self.context
+            .lock()
+            .map_err(|err| err.to_string())?
+            .set_image(image)
+            .map_err(|err| err.to_string())
+    }
+
+    pub fn write_html(&self, text: String, html: String) -> Result<(), String> {
+        let contents = vec![ClipboardContent::Text(text), ClipboardContent::Html(html)];
+
+        self.context
+            .lock()
+            .map_err(|err| err.to_string())?
+            .set(contents)
+            .map_err(|err| err.to_string())
+    }
+
+    pub fn write_rtf(&self, text: String, rtf: String) -> Result<(), String> {
+        let mut contents = vec![ClipboardContent::Rtf(rtf)];
+
+        if cfg!(not(target_os = "macos")) {
+            contents.push(ClipboardContent::Text(text))
+        }
+
+        self.context
+            .lock()
+            .map_err(|err| err.to_string())?
+            .set(contents)
+            .map_err(|err| err.to_string())
+    }
+
+    pub fn write_text(&self, value: String) -> Result<(), String> {
+        self.context
+            .lock()
+            .map_err(|err| err.to_string())?
+            .set_text(value)
+            .map_err(|err| err.to_string())
+    }
+
+    // 由应用自身写入文本，不会触发 `clipboard_update` 事件
+    pub fn write_text_silently(&self, value: String) -> Result<(), String> {
+        let context = self.context.lock().map_err(|err| err.to_string())?;
+
+        // 没有监听时不会收到变更，不记录写入的内容，避免之后用户复制相同的文本时被忽略
+        if self.is_listening() {
+            *self.self_write.lock().unwrap() = Some(value.clone());
+        }
+
+        let result = context.set_text(value).map_err(|err| err.to_string());
+
+        if result.is_err() {
+            *self.self_write.lock().unwrap() = None;
+        }
+
+        result
+    }
+}
+
+impl Default for ClipboardManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<R> ClipboardListen<R>
 where
     R: Runtime,
 {
-    fn new(app_handle: AppHandle<R>) -> Self {
-        Self { app_handle }
+    fn new(
+        app_handle: AppHandle<R>,
+        context: Arc<Mutex<ClipboardContext>>,
+        self_write: Arc<Mutex<Option<String>>>,
+    ) -> Self {
+        Self {
+            app_handle,
+            context,
+            self_write,
+        }
+    }
+
+    // 剪贴板的内容是否为应用自身写入的文本，只匹配一次
+    fn is_self_write(&self) -> bool {
+        let Some(written) = self.self_write.lock().unwrap().take() else {
+            return false;
+        };
+
+        self.context
+            .lock()
+            .ok()
+            .and_then(|context| context.get_text().ok())
+            .is_some_and(|text| text == written)
     }
 }
 
@@ -50,18 +296,22 @@ where
     R: Runtime,
 {
     fn on_clipboard_change(&mut self) {
+        if self.is_self_write() {
+            return;
+        }
+
         let _ = self
             .app_handle
-            .emit("plugin:eco-clipboard://clipboard_update", ())
+            .emit(CLIPBOARD_UPDATE_EVENT, ())
             .map_err(|err| err.to_string());
     }
 }
 
 #[derive(Debug, serde::Serialize)]
 pub struct ReadImage {
-    width: u32,
-    height: u32,
-    image: String,
+    pub width: u32,
+    pub height: u32,
+    pub image: String,
 }
 
 #[command]
@@ -69,80 +319,44 @@ pub async fn start_listen<R: Runtime>(
     app_handle: AppHandle<R>,
     manager: State<'_, ClipboardManager>,
 ) -> Result<(), String> {
-    let listener = ClipboardListen::new(app_handle.clone());
-
-    let mut watcher: ClipboardWatcherContext<ClipboardListen<R>> =
-        ClipboardWatcherContext::new().unwrap();
-
-    let watcher_shutdown = watcher.add_handler(listener).get_shutdown_channel();
-
-    let mut watcher_shutdown_state = manager.watcher_shutdown.lock().unwrap();
-
-    if (*watcher_shutdown_state).is_some() {
-        return Ok(());
-    }
-
-    *watcher_shutdown_state = Some(watcher_shutdown);
-
-    spawn(move || {
-        watcher.start_watch();
-    });
-
-    Ok(())
+    manager.start_listen(app_handle)
 }
 
 #[command]
 pub async fn stop_listen(manager: State<'_, ClipboardManager>) -> Result<(), String> {
-    let mut watcher_shutdown = manager.watcher_shutdown.lock().unwrap();
-
-    if let Some(watcher_shutdown) = (*watcher_shutdown).take() {
-        watcher_shutdown.stop();
-    }
-
-    *watcher_shutdown = None;
+    manager.stop_listen();
 
     Ok(())
 }
 
 #[command]
 pub async fn has_files(manager: State<'_, ClipboardManager>) -> Result<bool, String> {
-    Ok(manager.has(ContentFormat::Files))
+    Ok(manager.has_files())
 }
 
 #[command]
 pub async fn has_image(manager: State<'_, ClipboardManager>) -> Result<bool, String> {
-    Ok(manager.has(ContentFormat::Image))
+    Ok(manager.has_image())
 }
 
 #[command]
 pub async fn has_html(manager: State<'_, ClipboardManager>) -> Result<bool, String> {
-    Ok(manager.has(ContentFormat::Html))
+    Ok(manager.has_html())
 }
 
 #[command]
 pub async fn has_rtf(manager: State<'_, ClipboardManager>) -> Result<bool, String> {
-    Ok(manager.has(ContentFormat::Rtf))
+    Ok(manager.has_rtf())
 }
 
 #[command]
 pub async fn has_text(manager: State<'_, ClipboardManager>) -> Result<bool, String> {
-    Ok(manager.has(ContentFormat::Text))
+    Ok(manager.has_text())
 }
 
 #[command]
 pub async fn read_files(manager: State<'_, ClipboardManager>) -> Result<Vec<String>, String> {
-    let mut files = manager
-        .context
-        .lock()
-        .map_err(|err| err.to_string())?
-        .get_files()
-        .map_err(|err| err.to_string())?;
-
-    files.iter_mut().for_each(|path| {
-        *path = path.replace("file://", "");
-    });
-
-    Ok(files)
+    manager.read_files()
 }
 
 #[command]
@@ -150,78 +364,22 @@ pub async fn read_image(
     manager: State<'_, ClipboardManager>,
     path: PathBuf,
 ) -> Result<ReadImage, String> {
-    create_dir_all(&path).map_err(|op| op.to_string())?;
-
-    let image = manager
-        .context
-        .lock()
-        .map_err(|err| err.to_string())?
-        .get_image()
-        .map_err(|err| err.to_string())?;
-
-    let (width, height) = image.get_size();
-
-    let thumbnail_image = image
-        .thumbnail(width / 10, height / 10)
-        .map_err(|err| err.to_string())?;
-
-    let bytes = thumbnail_image
-        .to_png()
-        .map_err(|err| err.to_string())?
-        .get_bytes()
-        .to_vec();
-
-    let mut hasher = DefaultHasher::new();
-
-    bytes.hash(&mut hasher);
-
-    let hash = hasher.finish();
-
-    let image_path = path.join(format!("{hash}.png"));
-
-    if let Some(path) = image_path.to_str() {
-        image.save_to_path(path).map_err(|err| err.to_string())?;
-
-        let image = path.to_string();
-
-        return Ok(ReadImage {
-            width,
-            height,
-            image,
-        });
-    }
-
-    Err("read_image execution error".to_string())
+    manager.read_image(&path)
 }
 
 #[command]
 pub async fn read_html(manager: State<'_, ClipboardManager>) -> Result<String, String> {
-    manager
-        .context
-        .lock()
-        .map_err(|err| err.to_string())?
-        .get_html()
-        .map_err(|err| err.to_string())
+    manager.read_html()
 }
 
 #[command]
 pub async fn read_rtf(manager: State<'_, ClipboardManager>) -> Result<String, String> {
-    manager
-        .context
-        .lock()
-        .map_err(|err| err.to_string())?
-        .get_rich_text()
-        .map_err(|err| err.to_string())
+    manager.read_rtf()
 }
 
 #[command]
 pub async fn read_text(manager: State<'_, ClipboardManager>) -> Result<String, String> {
-    manager
-        .context
-        .lock()
-        .map_err(|err| err.to_string())?
-        .get_text()
-        .map_err(|err| err.to_string())
+    manager.read_text()
 }
 
 #[command]
@@ -229,12 +387,7 @@ pub async fn write_files(
     manager: State<'_, ClipboardManager>,
     value: Vec<String>,
 ) -> Result<(), String> {
-    manager
-        .context
-        .lock()
-        .map_err(|err| err.to_string())?
-        .set_files(value)
-        .map_err(|err| err.to_string())
+    manager.write_files(value)
 }
 
 #[command]
@@ -242,16 +395,7 @@ pub async fn write_image(
     manager: State<'_, ClipboardManager>,
     value: String,
 ) -> Result<(), String> {
-    // 尝试从路径创建 RustImageData，如果失败则返回错误信息
-    let image = RustImageData::from_path(&value).map_err(|err| err.to_string())?;
-
-    // 尝试获取锁并设置图像，如果失败则返回错误信息
-    manager
-        .context
-        .lock()
-        .map_err(|err| err.to_string())?
-        .set_image(image)
-        .map_err(|err| err.to_string())
+    manager.write_image(&value)
 }
 
 #[command]
@@ -260,14 +404,7 @@ pub async fn write_html(
     text: String,
     html: String,
 ) -> Result<(), String> {
-    let contents = vec![ClipboardContent::Text(text), ClipboardContent::Html(html)];
-
-    manager
-        .context
-        .lock()
-        .map_err(|err| err.to_string())?
-        .set(contents)
-        .map_err(|err| err.to_string())
+    manager.write_html(text, html)
 }
 
 #[command]
@@ -276,26 +413,10 @@ pub async fn write_rtf(
     text: String,
     rtf: String,
 ) -> Result<(), String> {
-    let mut contents = vec![ClipboardContent::Rtf(rtf)];
-
-    if cfg!(not(target_os = "macos")) {
-        contents.push(ClipboardContent::Text(text))
-    }
-
-    manager
-        .context
-        .lock()
-        .map_err(|err| err.to_string())?
-        .set(contents)
-        .map_err(|err| err.to_string())
+    manager.write_rtf(text, rtf)
 }
 
 #[command]
 pub async fn write_text(manager: State<'_, ClipboardManager>, value: String) -> Result<(), String> {
-    manager
-        .context
-        .lock()
-        .map_err(|err| err.to_string())?
-        .set_text(value)
-        .map_err(|err| err.to_string())
+    manager.write_text(value)
 }
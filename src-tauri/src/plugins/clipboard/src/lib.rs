@@ -1,4 +1,3 @@
-use commands::ClipboardManager;
 use tauri::{
     generate_handler,
     plugin::{Builder, TauriPlugin},
@@ -7,6 +6,8 @@ use tauri::{
 
 mod commands;
 
+pub use commands::{ClipboardManager, ReadImage, CLIPBOARD_UPDATE_EVENT};
+
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::new("eco-clipboard")
         .setup(move |app, _api| {
@@ -1,4 +1,14 @@
-const COMMANDS: &[&str] = &["system_ocr"];
+const COMMANDS: &[&str] = &[
+    "system_ocr",
+    "ocr_to_clipboard",
+    "capture_region",
+    "list_ocr_languages",
+    "list_ocr_engines",
+    "set_ocr_engine",
+    "decode_codes",
+    "enqueue_ocr",
+    "cancel_ocr",
+];
 
 fn main() {
     tauri_plugin::Builder::new(COMMANDS).build();
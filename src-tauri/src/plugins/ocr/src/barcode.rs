@@ -0,0 +1,212 @@
+use serde::{Deserialize, Serialize};
+
+// 二维码或条形码内容的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CodeKind {
+    Url,
+    Wifi,
+    // otpauth://totp/... 或 otpauth://hotp/...
+    Otp,
+    Text,
+}
+
+// Wi-Fi 二维码中的网络配置：WIFI:T:WPA;S:name;P:password;H:false;;
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WifiConfig {
+    pub ssid: String,
+    pub security: Option<String>,
+    pub password: Option<String>,
+    pub hidden: bool,
+}
+
+// 识别出的二维码或条形码
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedCode {
+    // 码制，例如：QR_CODE、EAN_13、CODE_128
+    pub format: String,
+    pub text: String,
+    pub kind: CodeKind,
+    pub wifi: Option<WifiConfig>,
+}
+
+// 按未转义的分隔符拆分，并去掉转义符
+fn split_escaped(value: &str, separator: char) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut chars = value.chars();
+
+    while let Some(char) = chars.next() {
+        match char {
+            '\\' => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                }
+            }
+            _ if char == separator => parts.push(std::mem::take(&mut current)),
+            _ => current.push(char),
+        }
+    }
+
+    if !current.is_empty() {
+        parts.push(current);
+    }
+
+    parts
+}
+
+// 解析 Wi-Fi 配置，没有网络名称时无法连接，不当作 Wi-Fi 二维码
+fn parse_wifi(text: &str) -> Option<WifiConfig> {
+    let body = text.strip_prefix("WIFI:")?;
+
+    let mut config = WifiConfig::default();
+
+    for field in split_escaped(body, ';') {
+        let Some((key, value)) = field.split_once(':') else {
+            continue;
+        };
+
+        match key {
+            "S" => config.ssid = value.to_string(),
+            "T" => config.security = Some(value.to_string()).filter(|value| !value.is_empty()),
+            "P" => config.password = Some(value.to_string()).filter(|value| !value.is_empty()),
+            "H" => config.hidden = value.eq_ignore_ascii_case("true"),
+            _ => {}
+        }
+    }
+
+    (!config.ssid.is_empty()).then_some(config)
+}
+
+fn classify(format: String, text: String) -> DecodedCode {
+    let lower = text.to_lowercase();
+
+    let wifi = parse_wifi(&text);
+
+    let kind = if wifi.is_some() {
+        CodeKind::Wifi
+    } else if lower.starts_with("otpauth://") {
+        CodeKind::Otp
+    } else if lower.starts_with("http://") || lower.starts_with("https://") {
+        CodeKind::Url
+    } else {
+        CodeKind::Text
+    };
+
+    DecodedCode {
+        format,
+        text,
+        kind,
+        wifi,
+    }
+}
+
+// 识别灰度图片中的所有二维码和条形码
+pub fn decode_luma(luma: Vec<u8>, width: u32, height: u32) -> Vec<DecodedCode> {
+    // 没有找到时返回 NotFoundException
+    let Ok(results) = rxing::helpers::detect_multiple_in_luma(luma, width, height) else {
+        return Vec::new();
+    };
+
+    let mut codes: Vec<DecodedCode> = Vec::new();
+
+    for result in results {
+        let text = result.getText().to_string();
+
+        if text.is_empty() || codes.iter().any(|code| code.text == text) {
+            continue;
+        }
+
+        codes.push(classify(format!("{:?}", result.getBarcodeFormat()), text));
+    }
+
+    codes
+}
+
+// 识别图片文件中的所有二维码和条形码
+pub fn decode_codes(path: &str) -> Result<Vec<DecodedCode>, String> {
+    let image = image::open(path).map_err(|err| err.to_string())?.to_luma8();
+
+    let (width, height) = image.dimensions();
+
+    Ok(decode_luma(image.into_raw(), width, height))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_on_unescaped_separators() {
+        assert_eq!(
+            split_escaped("S:home;P:secret;;", ';'),
+            ["S:home", "P:secret", ""]
+        );
+        assert_eq!(
+            split_escaped(r"P:a\;b\:c\\;H:true", ';'),
+            [r"P:a;b:c\", "H:true"]
+        );
+    }
+
+    #[test]
+    fn parses_wifi_fields() {
+        let config = parse_wifi("WIFI:T:WPA;S:home;P:secret;H:TRUE;;").unwrap();
+
+        assert_eq!(
+            config,
+            WifiConfig {
+                ssid: "home".to_string(),
+                security: Some("WPA".to_string()),
+                password: Some("secret".to_string()),
+                hidden: true,
+            }
+        );
+    }
+
+    #[test]
+    fn unescapes_wifi_values() {
+        let config = parse_wifi(r"WIFI:S:cafe\;bar;P:pa\:ss\;word;;").unwrap();
+
+        assert_eq!(config.ssid, "cafe;bar");
+        assert_eq!(config.password.as_deref(), Some("pa:ss;word"));
+    }
+
+    // 开放网络没有密码，T 和 P 可以省略或为空
+    #[test]
+    fn leaves_missing_wifi_fields_empty() {
+        let config = parse_wifi("WIFI:S:guest;T:;;").unwrap();
+
+        assert_eq!(config.security, None);
+        assert_eq!(config.password, None);
+        assert!(!config.hidden);
+    }
+
+    #[test]
+    fn rejects_wifi_without_ssid() {
+        assert_eq!(parse_wifi("WIFI:T:WPA;P:secret;;"), None);
+        assert_eq!(parse_wifi("T:WPA;S:home;;"), None);
+    }
+
+    #[test]
+    fn classifies_content() {
+        let kind = |text: &str| classify("QR_CODE".to_string(), text.to_string()).kind;
+
+        assert_eq!(kind("https://example.com"), CodeKind::Url);
+        assert_eq!(kind("HTTP://EXAMPLE.COM"), CodeKind::Url);
+        assert_eq!(kind("WIFI:S:home;P:secret;;"), CodeKind::Wifi);
+        assert_eq!(kind("otpauth://totp/eco?secret=ABC"), CodeKind::Otp);
+        assert_eq!(kind("ftp://example.com"), CodeKind::Text);
+        assert_eq!(kind("WIFI:;;"), CodeKind::Text);
+        assert_eq!(kind("4006152340"), CodeKind::Text);
+    }
+
+    #[test]
+    fn keeps_wifi_config_only_for_wifi_codes() {
+        let code = classify("QR_CODE".to_string(), "WIFI:S:home;;".to_string());
+
+        assert_eq!(code.wifi.map(|wifi| wifi.ssid).as_deref(), Some("home"));
+        assert_eq!(classify("EAN_13".to_string(), "123".to_string()).wifi, None);
+    }
+}
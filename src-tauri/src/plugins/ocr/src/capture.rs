@@ -0,0 +1,469 @@
+use crate::result::OcrBox;
+use std::{
+    fs::create_dir_all,
+    mem::zeroed,
+    os::raw::c_int,
+    path::Path,
+    ptr::{null, null_mut},
+    sync::{Mutex, OnceLock},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use x11::xlib::{
+    self, ButtonPressMask, ButtonReleaseMask, CWBackPixmap, CWEventMask, CWOverrideRedirect,
+    CurrentTime, Display, ExposureMask, GCForeground, GCFunction, GCLineWidth, GrabModeAsync,
+    GrabSuccess, InputOutput, LSBFirst, PointerMotionMask, XAllPlanes, XBlackPixel, XClearWindow,
+    XCloseDisplay, XCreateFontCursor, XCreateGC, XCreatePixmap, XCreateWindow, XDefaultDepth,
+    XDefaultRootWindow, XDefaultScreen, XDestroyImage, XDestroyWindow, XDisplayHeight,
+    XDisplayWidth, XDrawRectangle, XErrorEvent, XEvent, XFlush, XFreeCursor, XFreeGC, XFreePixmap,
+    XGCValues, XGetImage, XGrabKeyboard, XGrabPointer, XImage, XKeysymToKeycode, XMapRaised,
+    XNextEvent, XOpenDisplay, XPutImage, XSetErrorHandler, XSetWindowAttributes, XSync,
+    XUngrabKeyboard, XUngrabPointer, XWhitePixel, ZPixmap, GC,
+};
+
+// 十字光标：X11/cursorfont.h 中的 XC_crosshair
+const XC_CROSSHAIR: u32 = 34;
+
+// 截取的图片
+pub struct CapturedImage {
+    pub width: u32,
+    pub height: u32,
+    // 按行存储的 RGBA 像素
+    pub data: Vec<u8>,
+}
+
+impl CapturedImage {
+    // 转为灰度，用于识别二维码和条形码
+    pub fn to_luma(&self) -> Vec<u8> {
+        self.data
+            .chunks_exact(4)
+            .map(|pixel| {
+                let [r, g, b] = [pixel[0], pixel[1], pixel[2]].map(|value| value as u32);
+
+                ((r * 299 + g * 587 + b * 114) / 1000) as u8
+            })
+            .collect()
+    }
+
+    // 以截取的时间为文件名保存为 png，返回保存的路径
+    pub fn save_to_dir(&self, dir: &Path) -> Result<String, String> {
+        create_dir_all(dir).map_err(|err| err.to_string())?;
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|err| err.to_string())?
+            .as_nanos();
+
+        let path = dir.join(format!("capture-{}.png", timestamp));
+
+        image::save_buffer(
+            &path,
+            &self.data,
+            self.width,
+            self.height,
+            image::ColorType::Rgba8,
+        )
+        .map_err(|err| err.to_string())?;
+
+        Ok(path.to_string_lossy().to_string())
+    }
+}
+
+// 本模块打开的 X 连接，其它连接的错误仍然交给之前的错误处理函数
+static DISPLAYS: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+// XSetErrorHandler 接收和返回的错误处理函数
+type XErrorHandler = Option<unsafe extern "C" fn(*mut Display, *mut XErrorEvent) -> c_int>;
+
+// 安装错误处理函数前的错误处理函数
+static PREVIOUS_ERROR_HANDLER: OnceLock<XErrorHandler> = OnceLock::new();
+
+// xlib 默认的错误处理函数收到错误时会退出进程，本模块的连接上的错误只记录日志
+unsafe extern "C" fn handle_x_error(display: *mut Display, event: *mut XErrorEvent) -> c_int {
+    let owned = DISPLAYS
+        .lock()
+        .is_ok_and(|displays| displays.contains(&(display as usize)));
+
+    if owned {
+        let event = &*event;
+
+        log::warn!(
+            "X error {} on request {} for resource {}",
+            event.error_code,
+            event.request_code,
+            event.resourceid
+        );
+
+        return 0;
+    }
+
+    match PREVIOUS_ERROR_HANDLER.get().copied().flatten() {
+        Some(handler) => handler(display, event),
+        None => 0,
+    }
+}
+
+// 离开作用域时关闭连接，让之前请求的错误在连接关闭前由错误处理函数处理
+struct DisplayGuard(*mut Display);
+
+impl Drop for DisplayGuard {
+    fn drop(&mut self) {
+        unsafe {
+            XSync(self.0, xlib::False);
+            XCloseDisplay(self.0);
+        }
+
+        DISPLAYS
+            .lock()
+            .unwrap()
+            .retain(|owned| *owned != self.0 as usize);
+    }
+}
+
+fn open_display() -> Result<DisplayGuard, String> {
+    PREVIOUS_ERROR_HANDLER.get_or_init(|| unsafe { XSetErrorHandler(Some(handle_x_error)) });
+
+    let display = unsafe { XOpenDisplay(null()) };
+
+    if display.is_null() {
+        return Err("could not open X display".to_string());
+    }
+
+    DISPLAYS.lock().unwrap().push(display as usize);
+
+    Ok(DisplayGuard(display))
+}
+
+// 截取的屏幕图像，离开作用域时释放
+struct ScreenImage(*mut XImage);
+
+impl Drop for ScreenImage {
+    fn drop(&mut self) {
+        unsafe { XDestroyImage(self.0) };
+    }
+}
+
+// 由两个角得到矩形
+fn rect_from_points(start: (i32, i32), end: (i32, i32)) -> OcrBox {
+    OcrBox {
+        x: start.0.min(end.0),
+        y: start.1.min(end.1),
+        width: (start.0 - end.0).abs(),
+        height: (start.1 - end.1).abs(),
+    }
+}
+
+// 重绘遮罩窗口的背景后以异或方式绘制选区，在任何背景上都可见
+unsafe fn draw_rect(display: *mut Display, overlay: u64, gc: GC, rect: &OcrBox) {
+    XClearWindow(display, overlay);
+
+    if rect.width > 0 && rect.height > 0 {
+        XDrawRectangle(
+            display,
+            overlay,
+            gc,
+            rect.x,
+            rect.y,
+            rect.width as u32,
+            rect.height as u32,
+        );
+    }
+
+    XFlush(display);
+}
+
+// 创建覆盖整个屏幕的遮罩窗口，以截取的屏幕图像为背景
+// 混成窗口管理器下直接在根窗口上绘制不可见，override-redirect 的窗口不受窗口管理器管理
+unsafe fn create_overlay(display: *mut Display, screen: &ScreenImage) -> (u64, u64) {
+    let root = XDefaultRootWindow(display);
+    let image = &*screen.0;
+    let (width, height) = (image.width as u32, image.height as u32);
+
+    let pixmap = XCreatePixmap(
+        display,
+        root,
+        width,
+        height,
+        XDefaultDepth(display, XDefaultScreen(display)) as u32,
+    );
+    let gc = XCreateGC(display, pixmap, 0, null_mut());
+    XPutImage(display, pixmap, gc, screen.0, 0, 0, 0, 0, width, height);
+    XFreeGC(display, gc);
+
+    let mut attributes: XSetWindowAttributes = zeroed();
+    attributes.override_redirect = xlib::True;
+    attributes.background_pixmap = pixmap;
+    attributes.event_mask = ExposureMask;
+
+    let overlay = XCreateWindow(
+        display,
+        root,
+        0,
+        0,
+        width,
+        height,
+        0,
+        0,
+        InputOutput as u32,
+        null_mut(),
+        CWOverrideRedirect | CWBackPixmap | CWEventMask,
+        &mut attributes,
+    );
+
+    XMapRaised(display, overlay);
+    XFlush(display);
+
+    (overlay, pixmap)
+}
+
+// 在截取的屏幕图像上由用户拖动鼠标选择区域，按下 Esc 或鼠标右键取消
+unsafe fn select_region(
+    display: *mut Display,
+    screen_image: &ScreenImage,
+) -> Result<Option<OcrBox>, String> {
+    let root = XDefaultRootWindow(display);
+    let screen = XDefaultScreen(display);
+    let cursor = XCreateFontCursor(display, XC_CROSSHAIR);
+
+    let mask = (ButtonPressMask | ButtonReleaseMask | PointerMotionMask) as u32;
+
+    let grabbed = XGrabPointer(
+        display,
+        root,
+        xlib::False,
+        mask,
+        GrabModeAsync,
+        GrabModeAsync,
+        0,
+        cursor,
+        CurrentTime,
+    );
+
+    if grabbed != GrabSuccess {
+        XFreeCursor(display, cursor);
+        return Err("could not grab the pointer".to_string());
+    }
+
+    // 没有抓取键盘时无法用 Esc 取消，按键也会发送给其它窗口
+    let grabbed = XGrabKeyboard(
+        display,
+        root,
+        xlib::False,
+        GrabModeAsync,
+        GrabModeAsync,
+        CurrentTime,
+    );
+
+    if grabbed != GrabSuccess {
+        XUngrabPointer(display, CurrentTime);
+        XFreeCursor(display, cursor);
+        XFlush(display);
+        return Err("could not grab the keyboard".to_string());
+    }
+
+    let (overlay, pixmap) = create_overlay(display, screen_image);
+
+    let mut values: XGCValues = zeroed();
+    values.function = xlib::GXxor;
+    values.foreground = XWhitePixel(display, screen) ^ XBlackPixel(display, screen);
+    values.line_width = 1;
+
+    let gc = XCreateGC(
+        display,
+        overlay,
+        (GCFunction | GCForeground | GCLineWidth) as u64,
+        &mut values,
+    );
+
+    let escape = XKeysymToKeycode(display, x11::keysym::XK_Escape as u64) as u32;
+
+    let mut start: Option<(i32, i32)> = None;
+    let mut current = OcrBox::default();
+    let mut event: XEvent = zeroed();
+
+    // 遮罩窗口位于屏幕原点，根窗口坐标即为遮罩窗口中的坐标
+    let selection = loop {
+        XNextEvent(display, &mut event);
+
+        match event.get_type() {
+            xlib::Expose if event.expose.count == 0 => {
+                draw_rect(display, overlay, gc, &current);
+            }
+            xlib::ButtonPress => {
+                if event.button.button != xlib::Button1 {
+                    break None;
+                }
+
+                start = Some((event.button.x_root, event.button.y_root));
+            }
+            xlib::MotionNotify => {
+                if let Some(start) = start {
+                    current = rect_from_points(start, (event.motion.x_root, event.motion.y_root));
+
+                    draw_rect(display, overlay, gc, &current);
+                }
+            }
+            xlib::ButtonRelease => {
+                if let Some(start) = start {
+                    let rect = rect_from_points(start, (event.button.x_root, event.button.y_root));
+
+                    // 只点击没有拖动时视为取消
+                    break (rect.width > 0 && rect.height > 0).then_some(rect);
+                }
+            }
+            xlib::KeyPress if event.key.keycode == escape => break None,
+            _ => {}
+        }
+    };
+
+    XUngrabPointer(display, CurrentTime);
+    XUngrabKeyboard(display, CurrentTime);
+    XFreeGC(display, gc);
+    XDestroyWindow(display, overlay);
+    XFreePixmap(display, pixmap);
+    XFreeCursor(display, cursor);
+    XFlush(display);
+
+    Ok(selection)
+}
+
+// 将掩码对应的颜色分量转为 8 位
+fn channel(pixel: u32, mask: u64) -> u8 {
+    let mask = mask as u32;
+
+    if mask == 0 {
+        return 0;
+    }
+
+    let value = (pixel & mask) >> mask.trailing_zeros();
+    let max = mask >> mask.trailing_zeros();
+
+    (value * 255 / max) as u8
+}
+
+// 按字节序把 2 ~ 4 个字节组合为像素值
+fn read_pixel(bytes: &[u8], lsb_first: bool) -> u32 {
+    let fold = |pixel: u32, byte: &u8| (pixel << 8) | *byte as u32;
+
+    if lsb_first {
+        bytes.iter().rev().fold(0, fold)
+    } else {
+        bytes.iter().fold(0, fold)
+    }
+}
+
+// 把区域限制在屏幕范围内
+unsafe fn clamp_to_screen(display: *mut Display, rect: &OcrBox) -> Result<OcrBox, String> {
+    let screen = XDefaultScreen(display);
+    let screen_width = XDisplayWidth(display, screen);
+    let screen_height = XDisplayHeight(display, screen);
+
+    let left = rect.x.clamp(0, screen_width);
+    let top = rect.y.clamp(0, screen_height);
+    let right = rect.x.saturating_add(rect.width).clamp(0, screen_width);
+    let bottom = rect.y.saturating_add(rect.height).clamp(0, screen_height);
+
+    if right <= left || bottom <= top {
+        return Err("capture region is outside of the screen".to_string());
+    }
+
+    Ok(OcrBox {
+        x: left,
+        y: top,
+        width: right - left,
+        height: bottom - top,
+    })
+}
+
+// 截取根窗口的指定区域，区域需要在屏幕范围内
+unsafe fn get_image(display: *mut Display, rect: &OcrBox) -> Result<ScreenImage, String> {
+    let image = XGetImage(
+        display,
+        XDefaultRootWindow(display),
+        rect.x,
+        rect.y,
+        rect.width as u32,
+        rect.height as u32,
+        XAllPlanes(),
+        ZPixmap,
+    );
+
+    if image.is_null() {
+        return Err("could not read the screen image".to_string());
+    }
+
+    Ok(ScreenImage(image))
+}
+
+// 把屏幕图像中的指定区域转为 RGBA 像素，区域需要在图像范围内
+unsafe fn to_captured(image: &ScreenImage, rect: &OcrBox) -> Result<CapturedImage, String> {
+    let ximage = &*image.0;
+    let bits_per_pixel = ximage.bits_per_pixel;
+
+    // 真彩色的像素以 32、24 或 16 位存储，颜色分量的位置由掩码给出
+    let bytes_per_pixel = match bits_per_pixel {
+        32 | 24 | 16 => bits_per_pixel as usize / 8,
+        _ => return Err(format!("unsupported bits per pixel: {}", bits_per_pixel)),
+    };
+
+    let (left, top) = (rect.x as usize, rect.y as usize);
+    let (width, height) = (rect.width as u32, rect.height as u32);
+
+    let mut data = Vec::with_capacity((width * height * 4) as usize);
+
+    for y in top..top + height as usize {
+        let row = ximage.data.add(y * ximage.bytes_per_line as usize) as *const u8;
+
+        for x in left..left + width as usize {
+            let bytes = std::slice::from_raw_parts(row.add(x * bytes_per_pixel), bytes_per_pixel);
+
+            let pixel = read_pixel(bytes, ximage.byte_order == LSBFirst);
+
+            data.extend_from_slice(&[
+                channel(pixel, ximage.red_mask),
+                channel(pixel, ximage.green_mask),
+                channel(pixel, ximage.blue_mask),
+                255,
+            ]);
+        }
+    }
+
+    Ok(CapturedImage {
+        width,
+        height,
+        data,
+    })
+}
+
+// 截取屏幕区域，未指定区域时由用户选择，用户取消时返回空
+// 由用户选择时先截取整个屏幕，选择期间显示的是静止的画面，最后从中裁剪选择的区域
+pub fn capture(region: Option<OcrBox>) -> Result<Option<CapturedImage>, String> {
+    let display = open_display()?;
+
+    unsafe {
+        if let Some(region) = region {
+            let rect = clamp_to_screen(display.0, &region)?;
+            let image = get_image(display.0, &rect)?;
+
+            let whole = OcrBox { x: 0, y: 0, ..rect };
+
+            return to_captured(&image, &whole).map(Some);
+        }
+
+        let screen = XDefaultScreen(display.0);
+        let full = OcrBox {
+            x: 0,
+            y: 0,
+            width: XDisplayWidth(display.0, screen),
+            height: XDisplayHeight(display.0, screen),
+        };
+        let image = get_image(display.0, &full)?;
+
+        let Some(region) = select_region(display.0, &image)? else {
+            return Ok(None);
+        };
+
+        let rect = clamp_to_screen(display.0, &region)?;
+
+        to_captured(&image, &rect).map(Some)
+    }
+}
@@ -1,65 +1,222 @@
-use tauri::{command, AppHandle, Runtime};
+use crate::{
+    barcode::{self, DecodedCode},
+    engine::{recognize, OcrEngineConfig, OcrEngineInfo, OcrEngines},
+    error::OcrError,
+    options::OcrOptions,
+    preprocess::crop,
+    process::{CancelToken, OcrJobs},
+    queue::{OcrJob, OcrQueue},
+    result::{OcrBox, OcrResult},
+};
+use serde::Serialize;
+use std::path::PathBuf;
+use tauri::{async_runtime, command, State};
+use tauri_plugin_eco_clipboard::ClipboardManager;
 
+// 在阻塞线程中识别，指定任务 id 时可以通过 `cancel_ocr` 取消
+async fn run_job<T, F>(jobs: &OcrJobs, job_id: Option<String>, task: F) -> Result<T, OcrError>
+where
+    T: Send + 'static,
+    F: FnOnce(CancelToken) -> Result<T, OcrError> + Send + 'static,
+{
+    let cancel = match &job_id {
+        Some(id) => jobs.start(id),
+        None => CancelToken::default(),
+    };
+
+    let result = async_runtime::spawn_blocking(move || task(cancel))
+        .await
+        .map_err(|err| OcrError::from(err.to_string()));
+
+    if let Some(id) = &job_id {
+        jobs.finish(id);
+    }
+
+    result?
+}
+
+// 使用当前选择的引擎识别图片
 #[command]
-#[cfg(not(target_os = "linux"))]
-pub async fn system_ocr<R: Runtime>(
-    app_handle: AppHandle<R>,
-    path: &str,
-) -> Result<String, String> {
-    use tauri_plugin_shell::{process::CommandEvent, ShellExt};
-
-    // https://tauri.app/develop/sidecar/#running-it-from-rust
-    let (mut rx, _child) = app_handle
-        .shell()
-        .sidecar("ocr")
-        .map_err(|err| err.to_string())?
-        .args(&[path])
-        .spawn()
-        .map_err(|err| err.to_string())?;
+pub async fn system_ocr(
+    engines: State<'_, OcrEngines>,
+    jobs: State<'_, OcrJobs>,
+    path: String,
+    options: Option<OcrOptions>,
+    job_id: Option<String>,
+) -> Result<OcrResult, OcrError> {
+    let engine = engines.current_async().await?;
+    let options = options.unwrap_or_default();
 
-    loop {
-        let event = rx.recv().await;
+    run_job(&jobs, job_id, move |cancel| {
+        recognize(engine.as_ref(), &path, &options, cancel)
+    })
+    .await
+}
+
+// 识别图片的指定区域，并将识别出的文字写入剪贴板，写入不会被当作用户复制
+#[command]
+pub async fn ocr_to_clipboard(
+    engines: State<'_, OcrEngines>,
+    jobs: State<'_, OcrJobs>,
+    clipboard: State<'_, ClipboardManager>,
+    path: String,
+    region: Option<OcrBox>,
+    options: Option<OcrOptions>,
+    job_id: Option<String>,
+) -> Result<OcrResult, OcrError> {
+    let engine = engines.current_async().await?;
+    let options = options.unwrap_or_default();
 
-        match event {
-            Some(CommandEvent::Stdout(line_bytes)) => {
-                let line = String::from_utf8_lossy(&line_bytes);
+    let result = run_job(&jobs, job_id, move |cancel| {
+        let temp = match region {
+            Some(region) => Some(crop(&path, &region)?),
+            None => None,
+        };
 
-                return Ok(line.to_string());
-            }
-            _ => return Ok("".to_string()),
+        match &temp {
+            Some(temp) => recognize(
+                engine.as_ref(),
+                &temp.path().to_string_lossy(),
+                &options,
+                cancel,
+            ),
+            None => recognize(engine.as_ref(), &path, &options, cancel),
         }
+    })
+    .await?;
+
+    if !result.text.is_empty() {
+        clipboard.write_text_silently(result.text.clone())?;
     }
+
+    Ok(result)
+}
+
+// 截取屏幕区域并识别的结果
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureResult {
+    // 保存的图片路径
+    pub image: String,
+    pub width: u32,
+    pub height: u32,
+    pub result: OcrResult,
+    pub codes: Vec<DecodedCode>,
 }
 
+// 截取屏幕区域并识别，图片保存到 `path` 目录，未指定区域时由用户拖动选择，用户取消时返回空
 #[command]
 #[cfg(target_os = "linux")]
-pub async fn system_ocr<R: Runtime>(
-    _app_handle: AppHandle<R>,
-    path: &str,
-) -> tauri::Result<String> {
-    let output = match std::process::Command::new("tesseract")
-        .arg(path)
-        .arg("stdout")
-        .args(["-l", "eng+chi_sim+jpn"])
-        .output()
-    {
-        Ok(output) => output,
-        Err(e) => {
-            log::error!("Failed to exec tesseract: {:?}", e);
-            return Ok(String::default());
-        }
-    };
+pub async fn capture_region(
+    engines: State<'_, OcrEngines>,
+    path: PathBuf,
+    region: Option<OcrBox>,
+    options: Option<OcrOptions>,
+) -> Result<Option<CaptureResult>, OcrError> {
+    let engine = engines.current_async().await?;
+    let options = options.unwrap_or_default();
 
-    let text = if output.status.success() {
-        String::from_utf8(output.stdout)
-            .unwrap_or_default()
-            .split_whitespace()
-            .collect()
-    } else {
-        let content = String::from_utf8(output.stderr).unwrap_or_default();
-        log::error!("Error while executing tesseract: {:?}", content);
-        String::default()
-    };
+    async_runtime::spawn_blocking(move || -> Result<_, OcrError> {
+        let Some(captured) = crate::capture::capture(region)? else {
+            return Ok(None);
+        };
+
+        let image = captured.save_to_dir(&path)?;
+
+        // 识别失败时删除已保存的截图，由前端处理错误
+        let result = recognize(engine.as_ref(), &image, &options, CancelToken::default())
+            .inspect_err(|_| {
+                let _ = std::fs::remove_file(&image);
+            })?;
+
+        let codes = barcode::decode_luma(captured.to_luma(), captured.width, captured.height);
+
+        Ok(Some(CaptureResult {
+            image,
+            width: captured.width,
+            height: captured.height,
+            result,
+            codes,
+        }))
+    })
+    .await
+    .map_err(|err| OcrError::from(err.to_string()))?
+}
 
-    Ok(text)
+// 目前只支持 X11
+#[command]
+#[cfg(not(target_os = "linux"))]
+pub async fn capture_region(
+    _path: PathBuf,
+    _region: Option<OcrBox>,
+    _options: Option<OcrOptions>,
+) -> Result<Option<CaptureResult>, OcrError> {
+    Err(OcrError::Unsupported {
+        message: "screen capture is only supported on X11".to_string(),
+    })
+}
+
+// 获取当前引擎可用的识别语言，系统自带的 ocr 程序会自动选择语言
+#[command]
+pub async fn list_ocr_languages(engines: State<'_, OcrEngines>) -> Result<Vec<String>, String> {
+    let engine = engines
+        .current_async()
+        .await
+        .map_err(|err| err.to_string())?;
+
+    async_runtime::spawn_blocking(move || engine.languages())
+        .await
+        .map_err(|err| err.to_string())?
+}
+
+// 获取所有识别引擎及其可用状态
+#[command]
+pub async fn list_ocr_engines(
+    engines: State<'_, OcrEngines>,
+) -> Result<Vec<OcrEngineInfo>, String> {
+    engines.list().await
+}
+
+// 设置使用的识别引擎
+#[command]
+pub async fn set_ocr_engine(
+    engines: State<'_, OcrEngines>,
+    config: OcrEngineConfig,
+) -> Result<(), String> {
+    engines.set_config(config);
+
+    Ok(())
+}
+
+// 识别图片中的二维码和条形码
+#[command]
+pub async fn decode_codes(path: String) -> Result<Vec<DecodedCode>, String> {
+    async_runtime::spawn_blocking(move || barcode::decode_codes(&path))
+        .await
+        .map_err(|err| err.to_string())?
+}
+
+// 将图片加入后台识别队列，识别完成后通过 `plugin:eco-ocr://ocr_result` 事件通知
+#[command]
+pub async fn enqueue_ocr(
+    queue: State<'_, OcrQueue>,
+    id: String,
+    path: PathBuf,
+    options: Option<OcrOptions>,
+) -> Result<(), String> {
+    queue.push(OcrJob {
+        id,
+        path,
+        options: options.unwrap_or_default(),
+    })
+}
+
+// 取消识别任务，包括队列中的任务和指定了任务 id 的识别，正在运行的识别进程会被结束
+#[command]
+pub async fn cancel_ocr(
+    queue: State<'_, OcrQueue>,
+    jobs: State<'_, OcrJobs>,
+    id: String,
+) -> Result<bool, String> {
+    Ok(queue.cancel(&id) | jobs.cancel(&id))
 }
@@ -0,0 +1,113 @@
+use super::{OcrEngine, OcrEngineKind};
+use crate::{error::OcrError, options::OcrOptions, process::OcrControl, result::OcrResult};
+use serde::{Deserialize, Serialize};
+use std::{
+    env,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+// 参数中的图片路径占位符，没有占位符时将路径追加到参数末尾
+const PATH_PLACEHOLDER: &str = "{path}";
+
+// 用户配置的外部识别命令，例如 PaddleOCR 的命令行
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalCommand {
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+// 在 PATH 中查找程序
+fn find_program(program: &str) -> Option<PathBuf> {
+    let path = Path::new(program);
+
+    if path.components().count() > 1 {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+
+    let paths = env::var_os("PATH")?;
+
+    env::split_paths(&paths).find_map(|dir| {
+        let candidate = dir.join(program);
+
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+
+        if cfg!(target_os = "windows") {
+            let candidate = candidate.with_extension("exe");
+
+            return candidate.is_file().then_some(candidate);
+        }
+
+        None
+    })
+}
+
+// 外部命令引擎，输出可以是纯文本，也可以是与 `OcrResult` 结构相同的 json
+pub struct CommandEngine {
+    command: Option<ExternalCommand>,
+}
+
+impl CommandEngine {
+    pub fn new(command: Option<ExternalCommand>) -> Self {
+        Self { command }
+    }
+
+    fn command(&self) -> Result<&ExternalCommand, String> {
+        self.command
+            .as_ref()
+            .filter(|command| !command.program.trim().is_empty())
+            .ok_or_else(|| "no external ocr command configured".to_string())
+    }
+}
+
+impl OcrEngine for CommandEngine {
+    fn kind(&self) -> OcrEngineKind {
+        OcrEngineKind::Command
+    }
+
+    fn availability(&self) -> Result<(), String> {
+        let command = self.command()?;
+
+        find_program(&command.program)
+            .map(|_| ())
+            .ok_or_else(|| format!("program not found: {}", command.program))
+    }
+
+    fn recognize(
+        &self,
+        path: &str,
+        _options: &OcrOptions,
+        control: &OcrControl,
+    ) -> Result<OcrResult, OcrError> {
+        let command = self.command()?;
+
+        let mut args: Vec<String> = command
+            .args
+            .iter()
+            .map(|arg| arg.replace(PATH_PLACEHOLDER, path))
+            .collect();
+
+        if !command
+            .args
+            .iter()
+            .any(|arg| arg.contains(PATH_PLACEHOLDER))
+        {
+            args.push(path.to_string());
+        }
+
+        let stdout = control.run(Command::new(&command.program).args(args))?;
+        let stdout = stdout.trim();
+
+        if stdout.starts_with('{') {
+            if let Ok(result) = serde_json::from_str::<OcrResult>(stdout) {
+                return Ok(result);
+            }
+        }
+
+        Ok(OcrResult::from_text(stdout))
+    }
+}
@@ -0,0 +1,95 @@
+use super::{OcrEngine, OcrEngineKind};
+use crate::{
+    error::OcrError, options::OcrOptions, preprocess::PreprocessOptions, process::OcrControl,
+    result::OcrResult,
+};
+
+// 进程内调用 libtesseract，避免每次识别都启动新的进程
+pub struct LibtesseractEngine;
+
+#[cfg(feature = "libtesseract")]
+impl LibtesseractEngine {
+    // 未指定语言时使用默认语言
+    fn language(options: &OcrOptions) -> String {
+        match &options.languages {
+            Some(languages) if !languages.is_empty() => languages.join("+"),
+            _ => super::tesseract::DEFAULT_LANGUAGES[0].to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "libtesseract")]
+impl OcrEngine for LibtesseractEngine {
+    fn kind(&self) -> OcrEngineKind {
+        OcrEngineKind::Libtesseract
+    }
+
+    fn availability(&self) -> Result<(), String> {
+        tesseract::Tesseract::new(None, None)
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+
+    // 进程内识别无法中途结束，只在识别前后检查是否已超时或取消
+    fn recognize(
+        &self,
+        path: &str,
+        options: &OcrOptions,
+        control: &OcrControl,
+    ) -> Result<OcrResult, OcrError> {
+        control.check("libtesseract")?;
+
+        let language = Self::language(options);
+
+        let mut api =
+            tesseract::Tesseract::new(None, Some(&language)).map_err(|err| err.to_string())?;
+
+        if let Some(psm) = options.psm {
+            api = api
+                .set_variable("tessedit_pageseg_mode", &psm.to_string())
+                .map_err(|err| err.to_string())?;
+        }
+
+        let mut api = api
+            .set_image(path)
+            .map_err(|err| err.to_string())?
+            .recognize()
+            .map_err(|err| err.to_string())?;
+
+        let tsv = api.get_tsv_text(0).map_err(|err| err.to_string())?;
+
+        control.check("libtesseract")?;
+
+        Ok(crate::result::parse_tesseract_tsv(&tsv))
+    }
+
+    fn default_preprocess(&self) -> Option<PreprocessOptions> {
+        Some(PreprocessOptions::default())
+    }
+}
+
+#[cfg(not(feature = "libtesseract"))]
+impl OcrEngine for LibtesseractEngine {
+    fn kind(&self) -> OcrEngineKind {
+        OcrEngineKind::Libtesseract
+    }
+
+    fn availability(&self) -> Result<(), String> {
+        Err("built without the `libtesseract` feature".to_string())
+    }
+
+    fn recognize(
+        &self,
+        _path: &str,
+        _options: &OcrOptions,
+        _control: &OcrControl,
+    ) -> Result<OcrResult, OcrError> {
+        self.availability()?;
+
+        Ok(OcrResult::default())
+    }
+
+    fn default_preprocess(&self) -> Option<PreprocessOptions> {
+        Some(PreprocessOptions::default())
+    }
+}
@@ -0,0 +1,254 @@
+use crate::{
+    error::OcrError,
+    options::OcrOptions,
+    preprocess::{preprocess, PreprocessOptions},
+    process::{CancelToken, OcrControl, DEFAULT_OCR_TIMEOUT},
+    result::OcrResult,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+use tauri::async_runtime;
+
+mod command;
+mod libtesseract;
+mod sidecar;
+mod tesseract;
+
+pub use command::*;
+pub use libtesseract::*;
+pub use sidecar::*;
+pub use tesseract::*;
+
+// 识别引擎的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OcrEngineKind {
+    // tesseract 命令行
+    Tesseract,
+    // 进程内调用 libtesseract，需要启用 `libtesseract` 特性
+    Libtesseract,
+    // 随应用打包的系统 ocr 程序
+    Sidecar,
+    // 用户配置的外部命令
+    Command,
+}
+
+// 识别引擎的设置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrEngineConfig {
+    // 未指定时按平台的默认顺序选择第一个可用的引擎
+    pub kind: Option<OcrEngineKind>,
+    pub command: Option<ExternalCommand>,
+}
+
+// 引擎的可用状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrEngineInfo {
+    pub kind: OcrEngineKind,
+    pub available: bool,
+    // 不可用的原因
+    pub reason: Option<String>,
+}
+
+pub trait OcrEngine: Send + Sync {
+    fn kind(&self) -> OcrEngineKind;
+
+    // 检查引擎是否可用，不可用时返回原因
+    fn availability(&self) -> Result<(), String>;
+
+    // 启动的进程需要通过 `control` 运行，以便超时或取消时结束进程
+    fn recognize(
+        &self,
+        path: &str,
+        options: &OcrOptions,
+        control: &OcrControl,
+    ) -> Result<OcrResult, OcrError>;
+
+    // 可用的识别语言，不支持指定语言的引擎返回空
+    fn languages(&self) -> Result<Vec<String>, String> {
+        Ok(Vec::new())
+    }
+
+    // 未指定预处理选项时使用的预处理步骤
+    fn default_preprocess(&self) -> Option<PreprocessOptions> {
+        None
+    }
+}
+
+// 未指定引擎时依次尝试的顺序
+#[cfg(target_os = "linux")]
+const DEFAULT_ENGINES: [OcrEngineKind; 2] = [OcrEngineKind::Tesseract, OcrEngineKind::Libtesseract];
+
+#[cfg(not(target_os = "linux"))]
+const DEFAULT_ENGINES: [OcrEngineKind; 2] = [OcrEngineKind::Sidecar, OcrEngineKind::Tesseract];
+
+// 所有引擎，用于展示可用状态
+const ALL_ENGINES: [OcrEngineKind; 4] = [
+    OcrEngineKind::Tesseract,
+    OcrEngineKind::Libtesseract,
+    OcrEngineKind::Sidecar,
+    OcrEngineKind::Command,
+];
+
+fn create_engine(kind: OcrEngineKind, config: &OcrEngineConfig) -> Box<dyn OcrEngine> {
+    match kind {
+        OcrEngineKind::Tesseract => Box::new(TesseractEngine),
+        OcrEngineKind::Libtesseract => Box::new(LibtesseractEngine),
+        OcrEngineKind::Sidecar => Box::new(SidecarEngine),
+        OcrEngineKind::Command => Box::new(CommandEngine::new(config.command.clone())),
+    }
+}
+
+// 依次检查默认的引擎，返回第一个可用的引擎，都不可用时返回第一个引擎
+// 检查可用性需要启动进程，不能在持有锁或异步任务中调用
+fn detect_engine(config: &OcrEngineConfig) -> (Box<dyn OcrEngine>, bool) {
+    let mut engines = DEFAULT_ENGINES
+        .iter()
+        .map(|&kind| create_engine(kind, config))
+        .collect::<Vec<_>>();
+
+    let index = engines
+        .iter()
+        .position(|engine| engine.availability().is_ok());
+
+    (
+        engines.swap_remove(index.unwrap_or_default()),
+        index.is_some(),
+    )
+}
+
+// 检查所有引擎的可用状态
+fn list_engines(config: &OcrEngineConfig) -> Vec<OcrEngineInfo> {
+    ALL_ENGINES
+        .iter()
+        .map(|&kind| {
+            let availability = create_engine(kind, config).availability();
+
+            OcrEngineInfo {
+                kind,
+                available: availability.is_ok(),
+                reason: availability.err(),
+            }
+        })
+        .collect()
+}
+
+// 当前选择的识别引擎
+#[derive(Default)]
+pub struct OcrEngines {
+    config: Mutex<OcrEngineConfig>,
+    // 未指定引擎时检测到的可用引擎，检查可用性需要启动进程，设置变化时重新检测
+    detected: Mutex<Option<OcrEngineKind>>,
+    // 设置的版本，检测期间设置发生变化时不记录检测结果
+    version: AtomicUsize,
+}
+
+impl OcrEngines {
+    pub fn set_config(&self, config: OcrEngineConfig) {
+        *self.config.lock().unwrap() = config;
+
+        let mut detected = self.detected.lock().unwrap();
+
+        self.version.fetch_add(1, Ordering::SeqCst);
+        *detected = None;
+    }
+
+    // 已指定或已检测到引擎时直接返回，否则返回需要检测的设置以及设置的版本
+    fn selected(&self) -> Result<Box<dyn OcrEngine>, (OcrEngineConfig, usize)> {
+        let version = self.version.load(Ordering::SeqCst);
+        let config = self.config.lock().unwrap().clone();
+
+        match config.kind.or(*self.detected.lock().unwrap()) {
+            Some(kind) => Ok(create_engine(kind, &config)),
+            None => Err((config, version)),
+        }
+    }
+
+    // 记录检测到的可用引擎，都不可用时不记录，以便安装后重新检测
+    fn remember(&self, version: usize, engine: &dyn OcrEngine, available: bool) {
+        let mut detected = self.detected.lock().unwrap();
+
+        if available && self.version.load(Ordering::SeqCst) == version {
+            *detected = Some(engine.kind());
+        }
+    }
+
+    // 在阻塞线程中获取当前的引擎，例如后台识别队列
+    pub fn current(&self) -> Box<dyn OcrEngine> {
+        self.selected().unwrap_or_else(|(config, version)| {
+            let (engine, available) = detect_engine(&config);
+
+            self.remember(version, engine.as_ref(), available);
+
+            engine
+        })
+    }
+
+    // 在异步命令中获取当前的引擎，检测在阻塞线程中进行
+    pub async fn current_async(&self) -> Result<Box<dyn OcrEngine>, OcrError> {
+        let (config, version) = match self.selected() {
+            Ok(engine) => return Ok(engine),
+            Err(pending) => pending,
+        };
+
+        let (engine, available) = async_runtime::spawn_blocking(move || detect_engine(&config))
+            .await
+            .map_err(|err| OcrError::from(err.to_string()))?;
+
+        self.remember(version, engine.as_ref(), available);
+
+        Ok(engine)
+    }
+
+    // 在阻塞线程中检查所有引擎的可用状态
+    pub async fn list(&self) -> Result<Vec<OcrEngineInfo>, String> {
+        let config = self.config.lock().unwrap().clone();
+
+        async_runtime::spawn_blocking(move || list_engines(&config))
+            .await
+            .map_err(|err| err.to_string())
+    }
+}
+
+// 预处理后使用指定的引擎识别，预处理失败时使用原图
+pub fn recognize(
+    engine: &dyn OcrEngine,
+    path: &str,
+    options: &OcrOptions,
+    cancel: CancelToken,
+) -> Result<OcrResult, OcrError> {
+    let timeout = Duration::from_secs(options.timeout.unwrap_or(DEFAULT_OCR_TIMEOUT));
+    let control = OcrControl::new(timeout, cancel);
+
+    let preprocessed = match options
+        .preprocess
+        .clone()
+        .or_else(|| engine.default_preprocess())
+    {
+        Some(preprocess_options) => preprocess(path, &preprocess_options).unwrap_or_else(|err| {
+            log::warn!("Failed to preprocess image for ocr: {}", err);
+            None
+        }),
+        None => None,
+    };
+
+    let Some(preprocessed) = preprocessed else {
+        return engine.recognize(path, options, &control);
+    };
+
+    let image = preprocessed.image.path().to_string_lossy();
+    let mut result = engine.recognize(&image, options, &control)?;
+
+    // 识别的是预处理后的图片，位置需要还原到原图
+    preprocessed.transform.restore(&mut result);
+
+    Ok(result)
+}
@@ -0,0 +1,75 @@
+use super::{OcrEngine, OcrEngineKind};
+use crate::{error::OcrError, options::OcrOptions, process::OcrControl, result::OcrResult};
+use serde::Deserialize;
+use std::{env, path::PathBuf, process::Command};
+
+// 打包时 sidecar 会被复制到可执行文件所在的目录，并去掉平台后缀
+#[cfg(target_os = "windows")]
+const SIDECAR_NAME: &str = "ocr.exe";
+
+#[cfg(not(target_os = "windows"))]
+const SIDECAR_NAME: &str = "ocr";
+
+fn sidecar_path() -> Result<PathBuf, String> {
+    let exe = env::current_exe().map_err(|err| err.to_string())?;
+
+    let dir = exe
+        .parent()
+        .ok_or_else(|| "executable has no parent directory".to_string())?;
+
+    Ok(dir.join(SIDECAR_NAME))
+}
+
+// 随应用打包的系统 ocr 程序：Windows 使用 Windows.Media.Ocr，macOS 使用 Vision
+pub struct SidecarEngine;
+
+// Windows 的 ocr 程序输出 json，二维码由 `barcode` 模块单独识别
+#[derive(Deserialize)]
+struct WindowsOcrOutput {
+    content: String,
+}
+
+// 解析 sidecar 的输出：Windows 为 json，macOS 为纯文本
+fn parse_output(stdout: &str) -> OcrResult {
+    // 读取全部输出，而不是只读取第一行
+    let text = stdout
+        .lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    if cfg!(target_os = "windows") {
+        if let Ok(output) = serde_json::from_str::<WindowsOcrOutput>(&text) {
+            return OcrResult::from_text(output.content.trim_end());
+        }
+    }
+
+    OcrResult::from_text(text.trim_end())
+}
+
+impl OcrEngine for SidecarEngine {
+    fn kind(&self) -> OcrEngineKind {
+        OcrEngineKind::Sidecar
+    }
+
+    fn availability(&self) -> Result<(), String> {
+        let path = sidecar_path()?;
+
+        if !path.is_file() {
+            return Err(format!("sidecar not found: {}", path.display()));
+        }
+
+        Ok(())
+    }
+
+    fn recognize(
+        &self,
+        path: &str,
+        _options: &OcrOptions,
+        control: &OcrControl,
+    ) -> Result<OcrResult, OcrError> {
+        let stdout = control.run(Command::new(sidecar_path()?).arg(path))?;
+
+        Ok(parse_output(&stdout))
+    }
+}
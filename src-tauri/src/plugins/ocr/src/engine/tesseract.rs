@@ -0,0 +1,214 @@
+use super::{OcrEngine, OcrEngineKind};
+use crate::{
+    error::OcrError,
+    options::OcrOptions,
+    preprocess::PreprocessOptions,
+    process::{OcrControl, PROBE_TIMEOUT},
+    result::{parse_tesseract_tsv, OcrResult},
+};
+use std::{process::Command, sync::Mutex};
+
+// 未指定语言时优先使用的语言
+pub(crate) const DEFAULT_LANGUAGES: [&str; 3] = ["eng", "chi_sim", "jpn"];
+
+// 文字方向和书写系统检测使用的模型，不能用于识别文字
+const OSD_LANGUAGE: &str = "osd";
+
+// 已安装语言的缓存，识别时复用，获取语言列表时刷新
+static LANGUAGES: Mutex<Option<Vec<String>>> = Mutex::new(None);
+
+// 获取已安装的语言
+fn list_languages(control: &OcrControl) -> Result<Vec<String>, OcrError> {
+    let stdout = control.run(Command::new("tesseract").arg("--list-langs"))?;
+
+    // 第一行为 `List of available languages in "..." (n):`
+    let languages: Vec<String> = stdout
+        .lines()
+        .skip(1)
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty())
+        .collect();
+
+    *LANGUAGES.lock().unwrap() = Some(languages.clone());
+
+    Ok(languages)
+}
+
+// 识别时使用缓存的语言，避免每次识别都多启动一个进程
+fn installed_languages(control: &OcrControl) -> Result<Vec<String>, OcrError> {
+    if let Some(languages) = LANGUAGES.lock().unwrap().clone() {
+        return Ok(languages);
+    }
+
+    list_languages(control)
+}
+
+// 书写系统对应的候选语言
+fn script_languages(script: &str) -> &'static [&'static str] {
+    match script {
+        "Latin" => &[
+            "eng", "deu", "fra", "spa", "ita", "por", "nld", "pol", "ces", "swe", "tur", "vie",
+        ],
+        "Han" | "HanS" => &["chi_sim", "chi_tra"],
+        "HanT" => &["chi_tra", "chi_sim"],
+        "Japanese" | "Katakana" | "Hiragana" => &["jpn"],
+        "Hangul" | "Korean" => &["kor"],
+        "Cyrillic" => &["rus", "ukr", "bel", "bul", "srp"],
+        "Arabic" => &["ara", "fas", "urd"],
+        "Greek" => &["ell"],
+        "Hebrew" => &["heb"],
+        "Devanagari" => &["hin", "mar", "nep"],
+        "Thai" => &["tha"],
+        _ => &[],
+    }
+}
+
+// 通过 `--psm 0` 检测图片的书写系统，检测失败时返回空，超时或取消时返回错误
+fn detect_script(path: &str, control: &OcrControl) -> Result<Option<String>, OcrError> {
+    let stdout = match control.run(Command::new("tesseract").arg(path).arg("stdout").args([
+        "--psm",
+        "0",
+        "-l",
+        OSD_LANGUAGE,
+    ])) {
+        Ok(stdout) => stdout,
+        Err(err @ (OcrError::Timeout { .. } | OcrError::Cancelled)) => return Err(err),
+        Err(err) => {
+            log::warn!("Failed to detect script: {}", err);
+            return Ok(None);
+        }
+    };
+
+    // 输出中包含 `Script: Latin` 这样的行
+    Ok(stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("Script:"))
+        .map(|script| script.trim().to_string()))
+}
+
+// 根据请求的语言、检测到的书写系统以及已安装的语言，决定最终使用的语言
+fn resolve_languages(
+    path: &str,
+    options: &OcrOptions,
+    installed: &[String],
+    control: &OcrControl,
+) -> Result<Vec<String>, OcrError> {
+    let is_installed = |language: &str| installed.iter().any(|item| item == language);
+
+    if options.detect_script {
+        if let Some(script) = detect_script(path, control)? {
+            let languages: Vec<String> = script_languages(&script)
+                .iter()
+                .filter(|language| is_installed(language))
+                .map(|language| language.to_string())
+                .collect();
+
+            if !languages.is_empty() {
+                return Ok(languages);
+            }
+
+            log::warn!("No installed language for detected script: {}", script);
+        }
+    }
+
+    if let Some(requested) = &options.languages {
+        let languages: Vec<String> = requested
+            .iter()
+            .filter(|language| {
+                let installed = is_installed(language);
+
+                if !installed {
+                    log::warn!("Tesseract language is not installed: {}", language);
+                }
+
+                installed
+            })
+            .cloned()
+            .collect();
+
+        if !languages.is_empty() {
+            return Ok(languages);
+        }
+    }
+
+    let defaults: Vec<String> = DEFAULT_LANGUAGES
+        .iter()
+        .filter(|language| is_installed(language))
+        .map(|language| language.to_string())
+        .collect();
+
+    if !defaults.is_empty() {
+        return Ok(defaults);
+    }
+
+    // 默认语言都没有安装时，使用所有已安装的语言
+    Ok(installed
+        .iter()
+        .filter(|language| language.as_str() != OSD_LANGUAGE)
+        .cloned()
+        .collect())
+}
+
+// 使用 tesseract 识别图片
+fn recognize(
+    path: &str,
+    options: &OcrOptions,
+    control: &OcrControl,
+) -> Result<OcrResult, OcrError> {
+    let installed = installed_languages(control)?;
+
+    let languages = resolve_languages(path, options, &installed, control)?;
+
+    if languages.is_empty() {
+        return Err("no tesseract language data installed".to_string().into());
+    }
+
+    let mut command = Command::new("tesseract");
+
+    command
+        .arg(path)
+        .arg("stdout")
+        .args(["-l", &languages.join("+")]);
+
+    if let Some(psm) = options.psm {
+        command.args(["--psm", &psm.to_string()]);
+    }
+
+    // 使用 tsv 格式输出，包含每个单词的位置、置信度以及所在的段落和行
+    let stdout = control.run(command.arg("tsv"))?;
+
+    Ok(parse_tesseract_tsv(&stdout))
+}
+
+// tesseract 命令行
+pub struct TesseractEngine;
+
+impl OcrEngine for TesseractEngine {
+    fn kind(&self) -> OcrEngineKind {
+        OcrEngineKind::Tesseract
+    }
+
+    fn availability(&self) -> Result<(), String> {
+        OcrControl::with_timeout(PROBE_TIMEOUT)
+            .run(Command::new("tesseract").arg("--version"))
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+
+    fn recognize(
+        &self,
+        path: &str,
+        options: &OcrOptions,
+        control: &OcrControl,
+    ) -> Result<OcrResult, OcrError> {
+        recognize(path, options, control)
+    }
+
+    fn languages(&self) -> Result<Vec<String>, String> {
+        list_languages(&OcrControl::with_timeout(PROBE_TIMEOUT)).map_err(|err| err.to_string())
+    }
+
+    fn default_preprocess(&self) -> Option<PreprocessOptions> {
+        Some(PreprocessOptions::default())
+    }
+}
@@ -0,0 +1,78 @@
+use serde::Serialize;
+use std::fmt;
+
+// 识别失败的原因，序列化后由前端根据 `kind` 区分
+#[derive(Debug, Clone, Serialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum OcrError {
+    // 无法启动识别程序
+    Spawn {
+        program: String,
+        message: String,
+    },
+    // 识别超时，进程已被结束
+    Timeout {
+        program: String,
+        seconds: u64,
+    },
+    // 识别被取消，进程已被结束
+    Cancelled,
+    // 识别程序异常退出，被信号结束时没有退出码
+    Exit {
+        program: String,
+        code: Option<i32>,
+        stderr: String,
+    },
+    // 当前平台或会话不支持，例如非 X11 下截取屏幕
+    Unsupported {
+        message: String,
+    },
+    // 其它错误，例如图片无法读取
+    Other {
+        message: String,
+    },
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Spawn { program, message } => {
+                write!(f, "failed to start {}: {}", program, message)
+            }
+            Self::Timeout { program, seconds } => {
+                write!(f, "{} timed out after {}s", program, seconds)
+            }
+            Self::Cancelled => write!(f, "ocr was cancelled"),
+            Self::Exit {
+                program,
+                code,
+                stderr,
+            } => {
+                match code {
+                    Some(code) => write!(f, "{} exited with code {}", program, code)?,
+                    None => write!(f, "{} was terminated by a signal", program)?,
+                }
+
+                if !stderr.is_empty() {
+                    write!(f, ": {}", stderr)?;
+                }
+
+                Ok(())
+            }
+            Self::Unsupported { message } => write!(f, "{}", message),
+            Self::Other { message } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for OcrError {}
+
+impl From<String> for OcrError {
+    fn from(message: String) -> Self {
+        Self::Other { message }
+    }
+}
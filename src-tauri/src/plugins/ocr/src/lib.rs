@@ -1,13 +1,50 @@
 use tauri::{
     generate_handler,
     plugin::{Builder, TauriPlugin},
-    Runtime,
+    Manager, Runtime,
 };
 
+mod barcode;
 mod commands;
+mod engine;
+mod error;
+mod options;
+mod preprocess;
+mod process;
+mod queue;
+mod result;
+
+#[cfg(target_os = "linux")]
+mod capture;
+
+pub use barcode::*;
+pub use engine::*;
+pub use error::*;
+pub use options::*;
+pub use preprocess::*;
+pub use process::*;
+pub use queue::*;
+pub use result::*;
 
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::new("eco-ocr")
-        .invoke_handler(generate_handler![commands::system_ocr])
+        .invoke_handler(generate_handler![
+            commands::system_ocr,
+            commands::ocr_to_clipboard,
+            commands::capture_region,
+            commands::list_ocr_languages,
+            commands::list_ocr_engines,
+            commands::set_ocr_engine,
+            commands::decode_codes,
+            commands::enqueue_ocr,
+            commands::cancel_ocr
+        ])
+        .setup(|app, _api| {
+            app.manage(OcrEngines::default());
+            app.manage(OcrJobs::default());
+            app.manage(OcrQueue::new(app.clone()));
+
+            Ok(())
+        })
         .build()
 }
@@ -0,0 +1,19 @@
+use crate::preprocess::PreprocessOptions;
+use serde::{Deserialize, Serialize};
+
+// 识别选项
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrOptions {
+    // tesseract 的语言，例如：eng、deu、rus、kor
+    pub languages: Option<Vec<String>>,
+    // 页面分割模式：https://tesseract-ocr.github.io/tessdoc/ImproveQuality.html#page-segmentation-method
+    pub psm: Option<u8>,
+    // 是否先检测书写系统（--psm 0），再根据结果选择语言
+    #[serde(default)]
+    pub detect_script: bool,
+    // 识别前的图片预处理，未指定时使用引擎的默认步骤
+    pub preprocess: Option<PreprocessOptions>,
+    // 超时时间（秒），超时后结束识别进程，未指定时使用 `DEFAULT_OCR_TIMEOUT`
+    pub timeout: Option<u64>,
+}
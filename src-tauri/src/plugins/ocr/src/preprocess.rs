@@ -0,0 +1,665 @@
+use crate::result::{OcrBox, OcrResult};
+use serde::{Deserialize, Serialize};
+use std::{io::BufWriter, path::Path};
+use tempfile::NamedTempFile;
+
+// 图片的短边小于该值时放大，小字号的界面截图放大后识别率更高
+const UPSCALE_MIN_SIZE: usize = 1000;
+
+// 最大放大倍数
+const UPSCALE_MAX_FACTOR: usize = 3;
+
+// 放大后和二值化的最大像素数，二值化的积分图每个像素需要 16 个字节
+const MAX_PIXELS: usize = 4_000_000;
+
+// 平均亮度低于该值时认为是深色背景
+const DARK_MEAN_THRESHOLD: f64 = 128.0;
+
+// Sauvola 二值化的窗口半径和参数
+const BINARIZE_RADIUS: usize = 15;
+const BINARIZE_K: f64 = 0.2;
+const BINARIZE_R: f64 = 128.0;
+
+// 纠偏检测的角度范围和步长，单位为度
+const DESKEW_MAX_ANGLE: f64 = 5.0;
+const DESKEW_STEP: f64 = 0.25;
+
+// 纠偏检测时缩小到的宽度
+const DESKEW_SAMPLE_WIDTH: usize = 800;
+
+// 最佳角度的得分需要比不旋转时高出的比例，避免对没有倾斜的截图做无意义的旋转
+const DESKEW_MIN_GAIN: f64 = 1.05;
+
+// 识别前的图片预处理步骤
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PreprocessOptions {
+    // 转为灰度图，反色、二值化和纠偏都依赖灰度图
+    pub grayscale: bool,
+    // 检测到深色背景时自动反色
+    pub invert: bool,
+    // 放大尺寸较小的图片
+    pub upscale: bool,
+    // 自适应二值化
+    pub binarize: bool,
+    // 纠正轻微倾斜的文字
+    pub deskew: bool,
+}
+
+impl Default for PreprocessOptions {
+    fn default() -> Self {
+        Self {
+            grayscale: true,
+            invert: true,
+            upscale: true,
+            binarize: true,
+            deskew: true,
+        }
+    }
+}
+
+impl PreprocessOptions {
+    fn is_enabled(&self) -> bool {
+        self.grayscale || self.upscale
+    }
+}
+
+// 预处理对坐标的变换，用于把识别结果的位置还原到原图
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PreprocessTransform {
+    // 放大倍数
+    pub scale: f64,
+    // 纠偏旋转的角度，单位为度，以预处理后图片的中心旋转
+    pub angle: f64,
+    // 原图的尺寸
+    pub width: usize,
+    pub height: usize,
+}
+
+impl PreprocessTransform {
+    fn identity(width: usize, height: usize) -> Self {
+        Self {
+            scale: 1.0,
+            angle: 0.0,
+            width,
+            height,
+        }
+    }
+
+    // 把预处理后图片中的点还原到原图，与 `rotate` 的采样方式相同
+    fn restore_point(&self, x: f64, y: f64) -> (f64, f64) {
+        let cx = self.width as f64 * self.scale / 2.0;
+        let cy = self.height as f64 * self.scale / 2.0;
+        let (sin, cos) = self.angle.to_radians().sin_cos();
+
+        let u = x - cx;
+        let v = y - cy;
+
+        let x = u * cos - v * sin + cx;
+        let y = u * sin + v * cos + cy;
+
+        (x / self.scale, y / self.scale)
+    }
+
+    // 还原区域的四个角，取外接矩形并限制在原图范围内
+    pub fn restore_box(&self, bbox: &OcrBox) -> OcrBox {
+        let left = bbox.x as f64;
+        let top = bbox.y as f64;
+        let right = left + bbox.width as f64;
+        let bottom = top + bbox.height as f64;
+
+        let points = [
+            self.restore_point(left, top),
+            self.restore_point(right, top),
+            self.restore_point(left, bottom),
+            self.restore_point(right, bottom),
+        ];
+
+        let min = |values: [f64; 4]| values.into_iter().fold(f64::INFINITY, f64::min);
+        let max = |values: [f64; 4]| values.into_iter().fold(f64::NEG_INFINITY, f64::max);
+
+        let xs = points.map(|(x, _)| x);
+        let ys = points.map(|(_, y)| y);
+
+        let clamp_x = |value: f64| value.round().clamp(0.0, self.width as f64) as i32;
+        let clamp_y = |value: f64| value.round().clamp(0.0, self.height as f64) as i32;
+
+        let x = clamp_x(min(xs));
+        let y = clamp_y(min(ys));
+
+        OcrBox {
+            x,
+            y,
+            width: clamp_x(max(xs)) - x,
+            height: clamp_y(max(ys)) - y,
+        }
+    }
+
+    // 把识别结果中行和单词的位置还原到原图
+    pub fn restore(&self, result: &mut OcrResult) {
+        if *self == Self::identity(self.width, self.height) {
+            return;
+        }
+
+        for line in result.lines.iter_mut() {
+            line.bbox = self.restore_box(&line.bbox);
+
+            for word in line.words.iter_mut() {
+                word.bbox = self.restore_box(&word.bbox);
+            }
+        }
+    }
+}
+
+// 预处理的结果
+pub struct Preprocessed {
+    pub image: TempImage,
+    pub transform: PreprocessTransform,
+}
+
+// 预处理后的临时图片，离开作用域时删除
+pub struct TempImage(NamedTempFile);
+
+impl TempImage {
+    // 以独占方式创建随机文件名的临时文件，不会跟随已存在的同名文件或符号链接
+    fn new() -> Result<Self, String> {
+        tempfile::Builder::new()
+            .prefix("eco-ocr-")
+            .suffix(".png")
+            .tempfile()
+            .map(Self)
+            .map_err(|err| err.to_string())
+    }
+
+    pub fn path(&self) -> &Path {
+        self.0.path()
+    }
+
+    // 通过创建时打开的文件写入，不再按路径重新打开
+    fn writer(&mut self) -> BufWriter<&mut NamedTempFile> {
+        BufWriter::new(&mut self.0)
+    }
+}
+
+// 按行存储的像素数据，每个像素 `channels` 个字节
+struct Pixels {
+    width: usize,
+    height: usize,
+    channels: usize,
+    data: Vec<u8>,
+}
+
+impl Pixels {
+    // 双线性插值取值，超出范围的位置使用 `fill`
+    fn sample(&self, x: f64, y: f64, channel: usize, fill: u8) -> f64 {
+        let x0 = x.floor();
+        let y0 = y.floor();
+        let fx = x - x0;
+        let fy = y - y0;
+
+        let get = |x: f64, y: f64| -> f64 {
+            if x < 0.0 || y < 0.0 || x >= self.width as f64 || y >= self.height as f64 {
+                return fill as f64;
+            }
+
+            let index = (y as usize * self.width + x as usize) * self.channels + channel;
+
+            self.data[index] as f64
+        };
+
+        let top = get(x0, y0) * (1.0 - fx) + get(x0 + 1.0, y0) * fx;
+        let bottom = get(x0, y0 + 1.0) * (1.0 - fx) + get(x0 + 1.0, y0 + 1.0) * fx;
+
+        top * (1.0 - fy) + bottom * fy
+    }
+}
+
+// 平均亮度低于阈值时反色，使文字变为白底黑字
+fn invert_if_dark(pixels: &mut Pixels) {
+    if pixels.data.is_empty() {
+        return;
+    }
+
+    let sum: u64 = pixels.data.iter().map(|&value| value as u64).sum();
+    let mean = sum as f64 / pixels.data.len() as f64;
+
+    if mean >= DARK_MEAN_THRESHOLD {
+        return;
+    }
+
+    for value in pixels.data.iter_mut() {
+        *value = 255 - *value;
+    }
+}
+
+// 放大倍数，只放大短边较小的图片，并限制放大后的像素数
+fn upscale_factor(width: usize, height: usize) -> usize {
+    let size = width.min(height).max(1);
+
+    if size >= UPSCALE_MIN_SIZE {
+        return 1;
+    }
+
+    let pixels = width * height;
+    let mut factor = UPSCALE_MIN_SIZE.div_ceil(size).min(UPSCALE_MAX_FACTOR);
+
+    while factor > 1 && pixels * factor * factor > MAX_PIXELS {
+        factor -= 1;
+    }
+
+    factor
+}
+
+// 使用双线性插值放大
+fn upscale(pixels: Pixels, factor: usize) -> Pixels {
+    if factor == 1 {
+        return pixels;
+    }
+
+    let width = pixels.width * factor;
+    let height = pixels.height * factor;
+    let channels = pixels.channels;
+    let scale = 1.0 / factor as f64;
+    let mut data = vec![0; width * height * channels];
+
+    for y in 0..height {
+        // 对齐像素中心，并限制在原图范围内
+        let sy = ((y as f64 + 0.5) * scale - 0.5).clamp(0.0, (pixels.height - 1) as f64);
+
+        for x in 0..width {
+            let sx = ((x as f64 + 0.5) * scale - 0.5).clamp(0.0, (pixels.width - 1) as f64);
+
+            for channel in 0..channels {
+                let value = pixels.sample(sx, sy, channel, 255);
+
+                data[(y * width + x) * channels + channel] = value.round() as u8;
+            }
+        }
+    }
+
+    Pixels {
+        width,
+        height,
+        channels,
+        data,
+    }
+}
+
+// Sauvola 自适应二值化，使用积分图计算窗口内的均值和标准差
+fn binarize(pixels: &mut Pixels) {
+    let (width, height) = (pixels.width, pixels.height);
+    let stride = width + 1;
+    let mut sum = vec![0u64; stride * (height + 1)];
+    let mut square = vec![0u64; stride * (height + 1)];
+
+    for y in 0..height {
+        let mut row_sum = 0u64;
+        let mut row_square = 0u64;
+
+        for x in 0..width {
+            let value = pixels.data[y * width + x] as u64;
+
+            row_sum += value;
+            row_square += value * value;
+
+            sum[(y + 1) * stride + x + 1] = sum[y * stride + x + 1] + row_sum;
+            square[(y + 1) * stride + x + 1] = square[y * stride + x + 1] + row_square;
+        }
+    }
+
+    let area = |table: &[u64], x0: usize, y0: usize, x1: usize, y1: usize| -> f64 {
+        (table[y1 * stride + x1] + table[y0 * stride + x0]
+            - table[y0 * stride + x1]
+            - table[y1 * stride + x0]) as f64
+    };
+
+    for y in 0..height {
+        let y0 = y.saturating_sub(BINARIZE_RADIUS);
+        let y1 = (y + BINARIZE_RADIUS + 1).min(height);
+
+        for x in 0..width {
+            let x0 = x.saturating_sub(BINARIZE_RADIUS);
+            let x1 = (x + BINARIZE_RADIUS + 1).min(width);
+            let count = ((x1 - x0) * (y1 - y0)) as f64;
+
+            let mean = area(&sum, x0, y0, x1, y1) / count;
+            let variance = (area(&square, x0, y0, x1, y1) / count - mean * mean).max(0.0);
+            let threshold = mean * (1.0 + BINARIZE_K * (variance.sqrt() / BINARIZE_R - 1.0));
+
+            let index = y * width + x;
+
+            pixels.data[index] = if pixels.data[index] as f64 > threshold {
+                255
+            } else {
+                0
+            };
+        }
+    }
+}
+
+// 通过水平投影检测文字的倾斜角度，文字行与投影方向一致时投影的起伏最大
+fn detect_skew(pixels: &Pixels) -> f64 {
+    let step = pixels.width.div_ceil(DESKEW_SAMPLE_WIDTH).max(1);
+
+    let mut points = Vec::new();
+
+    for y in (0..pixels.height).step_by(step) {
+        for x in (0..pixels.width).step_by(step) {
+            if pixels.data[y * pixels.width + x] < 128 {
+                points.push((x as f64, y as f64));
+            }
+        }
+    }
+
+    if points.is_empty() {
+        return 0.0;
+    }
+
+    let diagonal = (pixels.width as f64).hypot(pixels.height as f64);
+    let bins = (diagonal / step as f64).ceil() as usize * 2 + 1;
+    let offset = diagonal;
+
+    let score = |angle: f64| -> f64 {
+        let (sin, cos) = angle.to_radians().sin_cos();
+        let mut histogram = vec![0u32; bins];
+
+        for &(x, y) in &points {
+            let projected = y * cos - x * sin + offset;
+            let index = (projected / step as f64) as usize;
+
+            if let Some(count) = histogram.get_mut(index) {
+                *count += 1;
+            }
+        }
+
+        histogram
+            .windows(2)
+            .map(|pair| (pair[1] as f64 - pair[0] as f64).powi(2))
+            .sum()
+    };
+
+    let baseline = score(0.0);
+    let steps = (DESKEW_MAX_ANGLE / DESKEW_STEP) as i32;
+
+    let (angle, best) = (-steps..=steps)
+        .map(|index| index as f64 * DESKEW_STEP)
+        .map(|angle| (angle, score(angle)))
+        .fold((0.0, baseline), |best, current| {
+            if current.1 > best.1 {
+                current
+            } else {
+                best
+            }
+        });
+
+    if best > baseline * DESKEW_MIN_GAIN {
+        angle
+    } else {
+        0.0
+    }
+}
+
+// 以图片中心旋转，空白部分填充为白色
+fn rotate(pixels: &Pixels, angle: f64) -> Pixels {
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let cx = pixels.width as f64 / 2.0;
+    let cy = pixels.height as f64 / 2.0;
+    let mut data = vec![255; pixels.data.len()];
+
+    for y in 0..pixels.height {
+        for x in 0..pixels.width {
+            let u = x as f64 - cx;
+            let v = y as f64 - cy;
+
+            let sx = u * cos - v * sin + cx;
+            let sy = u * sin + v * cos + cy;
+
+            data[y * pixels.width + x] = pixels.sample(sx, sy, 0, 255).round() as u8;
+        }
+    }
+
+    Pixels {
+        width: pixels.width,
+        height: pixels.height,
+        channels: 1,
+        data,
+    }
+}
+
+// 按顺序执行预处理：灰度、反色、放大、纠偏、二值化，同时返回对坐标的变换
+fn process(pixels: Pixels, options: &PreprocessOptions) -> (Pixels, PreprocessTransform) {
+    let mut pixels = pixels;
+    let mut transform = PreprocessTransform::identity(pixels.width, pixels.height);
+
+    if options.grayscale && options.invert {
+        invert_if_dark(&mut pixels);
+    }
+
+    if options.upscale {
+        let factor = upscale_factor(pixels.width, pixels.height);
+
+        pixels = upscale(pixels, factor);
+        transform.scale = factor as f64;
+    }
+
+    if options.grayscale && options.deskew {
+        let angle = detect_skew(&pixels);
+
+        if angle != 0.0 {
+            pixels = rotate(&pixels, angle);
+            transform.angle = angle;
+        }
+    }
+
+    // 全屏截图等大图不做二值化，避免积分图占用过多内存，交给识别引擎自带的二值化处理
+    if options.grayscale && options.binarize && pixels.width * pixels.height <= MAX_PIXELS {
+        binarize(&mut pixels);
+    }
+
+    (pixels, transform)
+}
+
+// 预处理图片并写入临时文件，没有启用任何步骤时返回空
+pub fn preprocess(path: &str, options: &PreprocessOptions) -> Result<Option<Preprocessed>, String> {
+    if !options.is_enabled() {
+        return Ok(None);
+    }
+
+    let image = image::open(path).map_err(|err| err.to_string())?;
+
+    let (width, height) = (image.width() as usize, image.height() as usize);
+
+    if width == 0 || height == 0 {
+        return Ok(None);
+    }
+
+    let pixels = if options.grayscale {
+        Pixels {
+            width,
+            height,
+            channels: 1,
+            data: image.to_luma8().into_raw(),
+        }
+    } else {
+        Pixels {
+            width,
+            height,
+            channels: 4,
+            data: image.to_rgba8().into_raw(),
+        }
+    };
+
+    let (pixels, transform) = process(pixels, options);
+
+    let color = match pixels.channels {
+        1 => image::ColorType::L8,
+        _ => image::ColorType::Rgba8,
+    };
+
+    let mut temp = TempImage::new()?;
+
+    image::write_buffer_with_format(
+        &mut temp.writer(),
+        &pixels.data,
+        pixels.width as u32,
+        pixels.height as u32,
+        color,
+        image::ImageFormat::Png,
+    )
+    .map_err(|err| err.to_string())?;
+
+    Ok(Some(Preprocessed {
+        image: temp,
+        transform,
+    }))
+}
+
+// 裁剪图片的指定区域并写入临时文件，区域会被限制在图片范围内
+pub fn crop(path: &str, region: &OcrBox) -> Result<TempImage, String> {
+    let image = image::open(path).map_err(|err| err.to_string())?;
+
+    let clamp = |value: i32, max: u32| value.clamp(0, max as i32) as u32;
+
+    let left = clamp(region.x, image.width());
+    let top = clamp(region.y, image.height());
+    let right = clamp(region.x.saturating_add(region.width), image.width());
+    let bottom = clamp(region.y.saturating_add(region.height), image.height());
+
+    if right <= left || bottom <= top {
+        return Err("crop region is outside of the image".to_string());
+    }
+
+    let mut temp = TempImage::new()?;
+
+    image
+        .crop_imm(left, top, right - left, bottom - top)
+        .write_to(&mut temp.writer(), image::ImageFormat::Png)
+        .map_err(|err| err.to_string())?;
+
+    Ok(temp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 白底上每隔 20 行画一条按指定角度倾斜的黑线
+    fn lines(width: usize, height: usize, angle: f64) -> Pixels {
+        let mut data = vec![255; width * height];
+        let slope = angle.to_radians().tan();
+
+        for start in (20..height).step_by(20) {
+            for x in 0..width {
+                let y = (start as f64 + x as f64 * slope).round() as usize;
+
+                if y < height {
+                    data[y * width + x] = 0;
+                }
+            }
+        }
+
+        Pixels {
+            width,
+            height,
+            channels: 1,
+            data,
+        }
+    }
+
+    #[test]
+    fn restores_scaled_boxes() {
+        let transform = PreprocessTransform {
+            scale: 2.0,
+            ..PreprocessTransform::identity(100, 50)
+        };
+
+        let bbox = OcrBox {
+            x: 20,
+            y: 10,
+            width: 40,
+            height: 20,
+        };
+
+        assert_eq!(
+            transform.restore_box(&bbox),
+            OcrBox {
+                x: 10,
+                y: 5,
+                width: 20,
+                height: 10,
+            }
+        );
+    }
+
+    #[test]
+    fn restores_rotated_boxes() {
+        let transform = PreprocessTransform {
+            angle: 90.0,
+            ..PreprocessTransform::identity(100, 100)
+        };
+
+        let bbox = OcrBox {
+            x: 10,
+            y: 20,
+            width: 30,
+            height: 10,
+        };
+
+        assert_eq!(
+            transform.restore_box(&bbox),
+            OcrBox {
+                x: 70,
+                y: 10,
+                width: 10,
+                height: 30,
+            }
+        );
+    }
+
+    #[test]
+    fn clamps_restored_boxes_to_the_image() {
+        let transform = PreprocessTransform::identity(100, 50);
+
+        let bbox = OcrBox {
+            x: -5,
+            y: 40,
+            width: 30,
+            height: 30,
+        };
+
+        assert_eq!(
+            transform.restore_box(&bbox),
+            OcrBox {
+                x: 0,
+                y: 40,
+                width: 25,
+                height: 10,
+            }
+        );
+    }
+
+    #[test]
+    fn upscales_only_small_images() {
+        assert_eq!(upscale_factor(1920, 1080), 1);
+        assert_eq!(upscale_factor(1500, 600), 2);
+        assert_eq!(upscale_factor(400, 300), UPSCALE_MAX_FACTOR);
+    }
+
+    #[test]
+    fn limits_upscaled_pixels() {
+        assert_eq!(upscale_factor(3000, 500), 1);
+        assert_eq!(upscale_factor(2000, 400), 2);
+    }
+
+    #[test]
+    fn detects_skewed_lines() {
+        assert_eq!(detect_skew(&lines(400, 200, 2.0)), 2.0);
+        assert_eq!(detect_skew(&lines(400, 200, -1.5)), -1.5);
+    }
+
+    #[test]
+    fn ignores_straight_and_blank_images() {
+        assert_eq!(detect_skew(&lines(400, 200, 0.0)), 0.0);
+        assert_eq!(detect_skew(&lines(400, 10, 0.0)), 0.0);
+    }
+}
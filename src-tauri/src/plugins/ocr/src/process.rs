@@ -0,0 +1,196 @@
+use crate::error::OcrError;
+use std::{
+    collections::HashMap,
+    io::Read,
+    process::{Child, Command, Output, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+// 未指定超时时间时，一次识别最多等待的秒数
+pub const DEFAULT_OCR_TIMEOUT: u64 = 30;
+
+// 检查引擎是否可用时最多等待的时间
+pub(crate) const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+// 检查进程是否退出的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// 取消标记，多个线程共享
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+// 正在识别的任务，按任务 id 取消
+#[derive(Default)]
+pub struct OcrJobs {
+    tokens: Mutex<HashMap<String, CancelToken>>,
+}
+
+impl OcrJobs {
+    // 登记任务，返回任务的取消标记
+    pub fn start(&self, id: &str) -> CancelToken {
+        let token = CancelToken::default();
+
+        self.tokens
+            .lock()
+            .unwrap()
+            .insert(id.to_string(), token.clone());
+
+        token
+    }
+
+    pub fn finish(&self, id: &str) {
+        self.tokens.lock().unwrap().remove(id);
+    }
+
+    // 取消任务，正在运行的识别进程会被结束
+    pub fn cancel(&self, id: &str) -> bool {
+        match self.tokens.lock().unwrap().remove(id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+// 一次识别的超时时间和取消标记，识别过程中启动的所有进程共享同一个截止时间
+pub struct OcrControl {
+    timeout: Duration,
+    deadline: Instant,
+    cancel: CancelToken,
+}
+
+impl OcrControl {
+    pub fn new(timeout: Duration, cancel: CancelToken) -> Self {
+        Self {
+            timeout,
+            deadline: Instant::now() + timeout,
+            cancel,
+        }
+    }
+
+    // 只有超时时间，不能取消
+    pub fn with_timeout(timeout: Duration) -> Self {
+        Self::new(timeout, CancelToken::default())
+    }
+
+    // 已取消或已超时时返回错误
+    pub fn check(&self, program: &str) -> Result<(), OcrError> {
+        if self.cancel.is_cancelled() {
+            return Err(OcrError::Cancelled);
+        }
+
+        if Instant::now() >= self.deadline {
+            return Err(OcrError::Timeout {
+                program: program.to_string(),
+                seconds: self.timeout.as_secs(),
+            });
+        }
+
+        Ok(())
+    }
+
+    // 运行进程并等待退出，超时或取消时结束进程
+    pub fn output(&self, command: &mut Command) -> Result<Output, OcrError> {
+        let program = command.get_program().to_string_lossy().to_string();
+
+        self.check(&program)?;
+
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| OcrError::Spawn {
+                program: program.clone(),
+                message: err.to_string(),
+            })?;
+
+        // 在单独的线程中读取输出，避免输出过多时填满管道导致进程阻塞
+        let stdout = read_pipe(child.stdout.take());
+        let stderr = read_pipe(child.stderr.take());
+
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) => {}
+                Err(err) => {
+                    kill(&mut child);
+
+                    return Err(OcrError::Other {
+                        message: err.to_string(),
+                    });
+                }
+            }
+
+            if let Err(err) = self.check(&program) {
+                kill(&mut child);
+
+                return Err(err);
+            }
+
+            thread::sleep(POLL_INTERVAL);
+        };
+
+        Ok(Output {
+            status,
+            stdout: join_pipe(stdout),
+            stderr: join_pipe(stderr),
+        })
+    }
+
+    // 运行进程，非零退出码作为错误返回，成功时返回标准输出
+    pub fn run(&self, command: &mut Command) -> Result<String, OcrError> {
+        let program = command.get_program().to_string_lossy().to_string();
+
+        let output = self.output(command)?;
+
+        if !output.status.success() {
+            return Err(OcrError::Exit {
+                program,
+                code: output.status.code(),
+                stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            });
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+}
+
+fn read_pipe<T: Read + Send + 'static>(pipe: Option<T>) -> Option<JoinHandle<Vec<u8>>> {
+    pipe.map(|mut pipe| {
+        thread::spawn(move || {
+            let mut buffer = Vec::new();
+            let _ = pipe.read_to_end(&mut buffer);
+            buffer
+        })
+    })
+}
+
+fn join_pipe(handle: Option<JoinHandle<Vec<u8>>>) -> Vec<u8> {
+    handle
+        .and_then(|handle| handle.join().ok())
+        .unwrap_or_default()
+}
+
+// 结束进程并回收，读取输出的线程会在管道关闭后自行退出
+fn kill(child: &mut Child) {
+    let _ = child.kill();
+    let _ = child.wait();
+}
@@ -0,0 +1,493 @@
+use crate::{
+    barcode::{decode_codes, DecodedCode},
+    engine::{recognize, OcrEngine, OcrEngines},
+    error::OcrError,
+    options::OcrOptions,
+    process::OcrJobs,
+    result::OcrResult,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+
+// 同时进行识别的任务数量
+const OCR_CONCURRENCY: usize = 2;
+
+// 缓存的识别结果数量上限，超出后移除最早的结果
+const OCR_CACHE_LIMIT: usize = 1000;
+
+// 缓存文本的总字节数上限，缓存文件每次变化都会整体重写，需要限制大小
+const OCR_CACHE_MAX_BYTES: usize = 2 * 1024 * 1024;
+
+// 缓存文件名，保存在应用的缓存目录中
+const OCR_CACHE_FILE: &str = "ocr-cache.json";
+
+// 缓存变化后延迟写入文件的时间
+const OCR_CACHE_SAVE_DELAY: Duration = Duration::from_secs(5);
+
+// 识别完成后发送的事件
+pub const OCR_RESULT_EVENT: &str = "plugin:eco-ocr://ocr_result";
+
+// 识别任务
+pub struct OcrJob {
+    // 历史记录的 id
+    pub id: String,
+    pub path: PathBuf,
+    pub options: OcrOptions,
+}
+
+// 识别完成后发送给前端的数据
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrJobResult {
+    pub id: String,
+    pub hash: String,
+    pub cached: bool,
+    pub result: OcrResult,
+    // 图片中的二维码和条形码
+    pub codes: Vec<DecodedCode>,
+}
+
+// 只缓存识别出的文本，前端只使用文本建立搜索索引，单词的位置没有必要写入文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct OcrCacheEntry {
+    key: String,
+    text: String,
+}
+
+// 按加入顺序保存的识别文本，超出数量或大小上限时移除最早的结果
+#[derive(Default)]
+struct OcrCacheEntries {
+    texts: HashMap<String, String>,
+    order: VecDeque<String>,
+    // 所有文本的字节数
+    bytes: usize,
+}
+
+impl OcrCacheEntries {
+    fn insert(&mut self, key: String, text: String) {
+        self.bytes += text.len();
+
+        if let Some(previous) = self.texts.insert(key.clone(), text) {
+            self.bytes -= previous.len();
+            self.order.retain(|item| item != &key);
+        }
+
+        self.order.push_back(key);
+
+        while self.order.len() > OCR_CACHE_LIMIT || self.bytes > OCR_CACHE_MAX_BYTES {
+            let Some(key) = self.order.pop_front() else {
+                break;
+            };
+
+            if let Some(text) = self.texts.remove(&key) {
+                self.bytes -= text.len();
+            }
+        }
+    }
+
+    fn to_vec(&self) -> Vec<OcrCacheEntry> {
+        self.order
+            .iter()
+            .filter_map(|key| {
+                self.texts.get(key).map(|text| OcrCacheEntry {
+                    key: key.clone(),
+                    text: text.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+// 以图片内容和识别选项为键的识别文本缓存
+struct OcrCache {
+    entries: Arc<Mutex<OcrCacheEntries>>,
+    // 通知保存线程写入缓存文件
+    save: Option<Mutex<Sender<()>>>,
+}
+
+impl OcrCache {
+    fn load(path: Option<PathBuf>) -> Self {
+        let mut entries = OcrCacheEntries::default();
+
+        // 旧版本的缓存文件无法解析，会被丢弃
+        let saved: Vec<OcrCacheEntry> = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        for entry in saved {
+            entries.insert(entry.key, entry.text);
+        }
+
+        let entries = Arc::new(Mutex::new(entries));
+
+        let save = path.map(|path| {
+            let (sender, receiver) = channel();
+            let entries = entries.clone();
+
+            thread::spawn(move || save_cache(path, entries, receiver));
+
+            Mutex::new(sender)
+        });
+
+        Self { entries, save }
+    }
+
+    // 缓存的结果只有文本
+    fn get(&self, key: &str) -> Option<OcrResult> {
+        self.entries
+            .lock()
+            .unwrap()
+            .texts
+            .get(key)
+            .map(OcrResult::from_text)
+    }
+
+    fn insert(&self, key: String, result: &OcrResult) {
+        self.entries
+            .lock()
+            .unwrap()
+            .insert(key, result.text.clone());
+
+        if let Some(save) = &self.save {
+            let _ = save.lock().unwrap().send(());
+        }
+    }
+}
+
+// 缓存变化后延迟写入文件，期间的多次变化只写入一次
+fn save_cache(path: PathBuf, entries: Arc<Mutex<OcrCacheEntries>>, receiver: Receiver<()>) {
+    while receiver.recv().is_ok() {
+        thread::sleep(OCR_CACHE_SAVE_DELAY);
+
+        receiver.try_iter().for_each(drop);
+
+        let entries = entries.lock().unwrap().to_vec();
+
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+
+        match serde_json::to_string(&entries) {
+            Ok(content) => {
+                if let Err(err) = fs::write(&path, content) {
+                    log::warn!("Failed to save ocr cache: {}", err);
+                }
+            }
+            Err(err) => log::warn!("Failed to serialize ocr cache: {}", err),
+        }
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+// 计算图片内容的哈希
+fn hash_file(path: &Path) -> Result<String, String> {
+    let bytes = fs::read(path).map_err(|err| err.to_string())?;
+
+    Ok(to_hex(&Sha256::digest(bytes)))
+}
+
+// 缓存的键，相同的图片使用不同的引擎、语言、分割模式或预处理时结果不同
+fn cache_key(hash: &str, engine: &dyn OcrEngine, options: &OcrOptions) -> String {
+    let preprocess = options
+        .preprocess
+        .clone()
+        .or_else(|| engine.default_preprocess());
+
+    let settings = json!({
+        "engine": engine.kind(),
+        "languages": options.languages,
+        "psm": options.psm,
+        "detectScript": options.detect_script,
+        "preprocess": preprocess,
+    });
+
+    let digest = Sha256::digest(settings.to_string());
+
+    format!("{}-{}", hash, to_hex(&digest[..8]))
+}
+
+// 后台识别队列，新的图片记录加入队列后由工作线程依次识别
+pub struct OcrQueue {
+    sender: Mutex<Sender<OcrJob>>,
+    // 等待识别或正在识别的记录，取消时从中移除
+    pending: Arc<Mutex<HashSet<String>>>,
+}
+
+impl OcrQueue {
+    pub fn new<R: Runtime>(app_handle: AppHandle<R>) -> Self {
+        let (sender, receiver) = channel::<OcrJob>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let pending = Arc::new(Mutex::new(HashSet::new()));
+
+        let cache_path = app_handle
+            .path()
+            .app_cache_dir()
+            .ok()
+            .map(|dir| dir.join(OCR_CACHE_FILE));
+        let cache = Arc::new(OcrCache::load(cache_path));
+
+        for _ in 0..OCR_CONCURRENCY {
+            let app_handle = app_handle.clone();
+            let receiver = receiver.clone();
+            let pending = pending.clone();
+            let cache = cache.clone();
+
+            thread::spawn(move || worker(app_handle, receiver, pending, cache));
+        }
+
+        Self {
+            sender: Mutex::new(sender),
+            pending,
+        }
+    }
+
+    // 加入队列
+    pub fn push(&self, job: OcrJob) -> Result<(), String> {
+        self.pending.lock().unwrap().insert(job.id.clone());
+
+        self.sender
+            .lock()
+            .unwrap()
+            .send(job)
+            .map_err(|err| err.to_string())
+    }
+
+    // 取消识别，正在运行的识别进程由 `OcrJobs` 结束，之后也不会再发送结果
+    pub fn cancel(&self, id: &str) -> bool {
+        self.pending.lock().unwrap().remove(id)
+    }
+}
+
+fn worker<R: Runtime>(
+    app_handle: AppHandle<R>,
+    receiver: Arc<Mutex<Receiver<OcrJob>>>,
+    pending: Arc<Mutex<HashSet<String>>>,
+    cache: Arc<OcrCache>,
+) {
+    let is_pending = |id: &str| pending.lock().unwrap().contains(id);
+
+    loop {
+        // 只在取任务时持有锁，识别时其它工作线程可以继续取任务
+        let job = match receiver.lock().unwrap().recv() {
+            Ok(job) => job,
+            Err(_) => break,
+        };
+
+        if !is_pending(&job.id) {
+            continue;
+        }
+
+        let hash = match hash_file(&job.path) {
+            Ok(hash) => hash,
+            Err(err) => {
+                log::warn!("Failed to read image for ocr {:?}: {}", job.path, err);
+                pending.lock().unwrap().remove(&job.id);
+                continue;
+            }
+        };
+
+        let engine = app_handle.state::<OcrEngines>().current();
+        let key = cache_key(&hash, engine.as_ref(), &job.options);
+
+        let (result, cached) = match cache.get(&key) {
+            Some(result) => (result, true),
+            None => {
+                let path = job.path.to_string_lossy();
+                let jobs = app_handle.state::<OcrJobs>();
+
+                let cancel = jobs.start(&job.id);
+                let recognized = recognize(engine.as_ref(), &path, &job.options, cancel);
+                jobs.finish(&job.id);
+
+                match recognized {
+                    Ok(result) => {
+                        cache.insert(key, &result);
+
+                        (result, false)
+                    }
+                    Err(OcrError::Cancelled) => continue,
+                    // 识别失败时仍然尝试识别二维码和条形码
+                    Err(err) => {
+                        log::error!("Error while recognizing {:?}: {}", job.path, err);
+
+                        (OcrResult::default(), false)
+                    }
+                }
+            }
+        };
+
+        let codes = decode_codes(&job.path.to_string_lossy()).unwrap_or_else(|err| {
+            log::warn!("Failed to decode codes in {:?}: {}", job.path, err);
+            Vec::new()
+        });
+
+        if !pending.lock().unwrap().remove(&job.id) {
+            continue;
+        }
+
+        let payload = OcrJobResult {
+            id: job.id,
+            hash,
+            cached,
+            result,
+            codes,
+        };
+
+        let _ = app_handle.emit(OCR_RESULT_EVENT, payload);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        engine::{SidecarEngine, TesseractEngine},
+        preprocess::PreprocessOptions,
+    };
+
+    fn job(id: &str) -> OcrJob {
+        OcrJob {
+            id: id.to_string(),
+            path: PathBuf::from(format!("{id}.png")),
+            options: OcrOptions::default(),
+        }
+    }
+
+    #[test]
+    fn cache_key_depends_on_image_and_settings() {
+        let options = OcrOptions::default();
+        let key = cache_key("abc", &TesseractEngine, &options);
+
+        assert!(key.starts_with("abc-"));
+        assert_eq!(key, cache_key("abc", &TesseractEngine, &options));
+        assert_ne!(key, cache_key("abd", &TesseractEngine, &options));
+        assert_ne!(key, cache_key("abc", &SidecarEngine, &options));
+
+        let languages = OcrOptions {
+            languages: Some(vec!["deu".to_string()]),
+            ..Default::default()
+        };
+        let psm = OcrOptions {
+            psm: Some(6),
+            ..Default::default()
+        };
+
+        assert_ne!(key, cache_key("abc", &TesseractEngine, &languages));
+        assert_ne!(key, cache_key("abc", &TesseractEngine, &psm));
+    }
+
+    // 未指定预处理时使用引擎的默认步骤，超时时间不影响识别结果
+    #[test]
+    fn cache_key_ignores_equivalent_options() {
+        let key = cache_key("abc", &TesseractEngine, &OcrOptions::default());
+
+        let preprocess = OcrOptions {
+            preprocess: Some(PreprocessOptions::default()),
+            timeout: Some(60),
+            ..Default::default()
+        };
+        let disabled = OcrOptions {
+            preprocess: Some(PreprocessOptions {
+                binarize: false,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        assert_eq!(key, cache_key("abc", &TesseractEngine, &preprocess));
+        assert_ne!(key, cache_key("abc", &TesseractEngine, &disabled));
+    }
+
+    #[test]
+    fn evicts_oldest_texts() {
+        let mut entries = OcrCacheEntries::default();
+
+        for index in 0..=OCR_CACHE_LIMIT {
+            entries.insert(index.to_string(), "text".to_string());
+        }
+
+        assert_eq!(entries.order.len(), OCR_CACHE_LIMIT);
+        assert!(!entries.texts.contains_key("0"));
+
+        entries.insert("large".to_string(), "a".repeat(OCR_CACHE_MAX_BYTES - 4));
+
+        assert_eq!(entries.order, ["1000", "large"]);
+        assert_eq!(entries.bytes, OCR_CACHE_MAX_BYTES);
+    }
+
+    #[test]
+    fn replaces_cached_texts() {
+        let mut entries = OcrCacheEntries::default();
+
+        entries.insert("a".to_string(), "first".to_string());
+        entries.insert("b".to_string(), "second".to_string());
+        entries.insert("a".to_string(), "third!".to_string());
+
+        assert_eq!(entries.order, ["b", "a"]);
+        assert_eq!(entries.bytes, 12);
+        assert_eq!(
+            entries
+                .to_vec()
+                .into_iter()
+                .map(|entry| entry.text)
+                .collect::<Vec<_>>(),
+            ["second", "third!"]
+        );
+    }
+
+    #[test]
+    fn loads_saved_texts() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+
+        fs::write(file.path(), r#"[{"key":"a","text":"hello"}]"#).unwrap();
+
+        let cache = OcrCache::load(Some(file.path().to_path_buf()));
+
+        assert_eq!(
+            cache.get("a").map(|result| result.text).as_deref(),
+            Some("hello")
+        );
+        assert!(cache.get("b").is_none());
+    }
+
+    // 已经取出的任务在识别前后都会检查是否仍在等待，取消后不会再发送结果
+    #[test]
+    fn cancels_pending_jobs() {
+        let (sender, receiver) = channel();
+        let queue = OcrQueue {
+            sender: Mutex::new(sender),
+            pending: Default::default(),
+        };
+
+        queue.push(job("a")).unwrap();
+        queue.push(job("b")).unwrap();
+
+        assert!(queue.cancel("a"));
+        assert!(!queue.cancel("a"));
+        assert!(!queue.cancel("c"));
+
+        let queued: Vec<String> = receiver.try_iter().map(|job| job.id).collect();
+
+        assert_eq!(queued, ["a", "b"]);
+        assert!(!queue.pending.lock().unwrap().contains("a"));
+        assert!(queue.pending.lock().unwrap().contains("b"));
+    }
+}
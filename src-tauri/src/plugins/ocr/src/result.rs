@@ -0,0 +1,265 @@
+use serde::{Deserialize, Serialize};
+
+// 识别区域，单位为像素
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrBox {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+// 识别出的单词
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrWord {
+    pub text: String,
+    pub bbox: OcrBox,
+    // 置信度：0 ~ 100
+    pub confidence: f32,
+}
+
+// 识别出的一行文本
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OcrLine {
+    pub text: String,
+    pub bbox: OcrBox,
+    // 行内单词的平均置信度
+    pub confidence: f32,
+    // 所属段落的序号，从 0 开始
+    pub paragraph: usize,
+    pub words: Vec<OcrWord>,
+}
+
+// 图片识别的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct OcrResult {
+    // 完整文本，行之间以换行分隔，段落之间以空行分隔
+    pub text: String,
+    pub lines: Vec<OcrLine>,
+    // 所有单词的平均置信度，没有位置信息的识别结果为空
+    pub confidence: Option<f32>,
+}
+
+impl OcrResult {
+    // 只有文本的识别结果，例如系统自带的 ocr 程序
+    pub fn from_text(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Default::default()
+        }
+    }
+}
+
+// tesseract tsv 输出中的层级
+const LEVEL_LINE: u32 = 4;
+const LEVEL_WORD: u32 = 5;
+
+// 是否为中日文字符，这类文字的单词之间不需要空格
+fn is_cjk(char: char) -> bool {
+    matches!(char,
+        '\u{3000}'..='\u{30ff}'
+        | '\u{3400}'..='\u{4dbf}'
+        | '\u{4e00}'..='\u{9fff}'
+        | '\u{f900}'..='\u{faff}'
+        | '\u{ff00}'..='\u{ffef}')
+}
+
+// 拼接同一行的单词
+fn join_words(words: &[OcrWord]) -> String {
+    let mut text = String::new();
+
+    for word in words {
+        let needs_space = match (text.chars().last(), word.text.chars().next()) {
+            (Some(prev), Some(next)) => !(is_cjk(prev) && is_cjk(next)),
+            _ => false,
+        };
+
+        if needs_space {
+            text.push(' ');
+        }
+
+        text.push_str(&word.text);
+    }
+
+    text
+}
+
+// 计算平均置信度
+fn average(values: impl Iterator<Item = f32>) -> Option<f32> {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), value| (sum + value, count + 1));
+
+    (count > 0).then(|| sum / count as f32)
+}
+
+// 解析 tesseract 的 tsv 输出：level page_num block_num par_num line_num word_num left top width height conf text
+pub fn parse_tesseract_tsv(tsv: &str) -> OcrResult {
+    let mut lines: Vec<OcrLine> = Vec::new();
+    let mut current_paragraph: Option<(u32, u32)> = None;
+    let mut paragraph = 0;
+
+    // 表头行的层级无法解析，会被忽略，libtesseract 的输出没有表头
+    for row in tsv.lines() {
+        let columns: Vec<&str> = row.splitn(12, '\t').collect();
+
+        if columns.len() < 11 {
+            continue;
+        }
+
+        let number = |index: usize| columns[index].trim().parse::<i32>().unwrap_or_default();
+
+        let level = number(0) as u32;
+        let key = (number(2) as u32, number(3) as u32);
+        let bbox = OcrBox {
+            x: number(6),
+            y: number(7),
+            width: number(8),
+            height: number(9),
+        };
+
+        match level {
+            LEVEL_LINE => {
+                if current_paragraph.is_some_and(|current| current != key) {
+                    paragraph += 1;
+                }
+                current_paragraph = Some(key);
+
+                lines.push(OcrLine {
+                    bbox,
+                    paragraph,
+                    ..Default::default()
+                });
+            }
+            LEVEL_WORD => {
+                let text = columns.get(11).map(|text| text.trim()).unwrap_or_default();
+
+                if text.is_empty() {
+                    continue;
+                }
+
+                let confidence = columns[10].trim().parse::<f32>().unwrap_or_default();
+
+                if let Some(line) = lines.last_mut() {
+                    line.words.push(OcrWord {
+                        text: text.to_string(),
+                        bbox,
+                        confidence: confidence.max(0.0),
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    lines.retain(|line| !line.words.is_empty());
+
+    for line in lines.iter_mut() {
+        line.text = join_words(&line.words);
+        line.confidence =
+            average(line.words.iter().map(|word| word.confidence)).unwrap_or_default();
+    }
+
+    let mut text = String::new();
+
+    for (index, line) in lines.iter().enumerate() {
+        if index > 0 {
+            let new_paragraph = lines[index - 1].paragraph != line.paragraph;
+
+            text.push_str(if new_paragraph { "\n\n" } else { "\n" });
+        }
+
+        text.push_str(&line.text);
+    }
+
+    let confidence = average(
+        lines
+            .iter()
+            .flat_map(|line| line.words.iter())
+            .map(|word| word.confidence),
+    );
+
+    OcrResult {
+        text,
+        lines,
+        confidence,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `tesseract image stdout tsv` 的输出
+    const TSV: &str = "\
+level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext
+1\t1\t0\t0\t0\t0\t0\t0\t640\t480\t-1\t
+2\t1\t1\t0\t0\t0\t10\t10\t300\t90\t-1\t
+3\t1\t1\t1\t0\t0\t10\t10\t300\t50\t-1\t
+4\t1\t1\t1\t1\t0\t10\t10\t300\t20\t-1\t
+5\t1\t1\t1\t1\t1\t10\t10\t100\t20\t96.5\tHello
+5\t1\t1\t1\t1\t2\t120\t10\t190\t20\t91.5\tworld
+4\t1\t1\t1\t2\t0\t10\t40\t200\t20\t-1\t
+5\t1\t1\t1\t2\t1\t10\t40\t200\t20\t88\tagain
+3\t1\t1\t2\t0\t0\t10\t80\t300\t20\t-1\t
+4\t1\t1\t2\t1\t0\t10\t80\t300\t20\t-1\t
+5\t1\t1\t2\t1\t1\t10\t80\t300\t20\t80\t你好
+5\t1\t1\t2\t1\t2\t120\t80\t190\t20\t80\t世界
+";
+
+    #[test]
+    fn joins_lines_and_paragraphs() {
+        let result = parse_tesseract_tsv(TSV);
+
+        assert_eq!(result.text, "Hello world\nagain\n\n你好世界");
+        assert_eq!(result.lines.len(), 3);
+        assert_eq!(result.lines[1].paragraph, 0);
+        assert_eq!(result.lines[2].paragraph, 1);
+    }
+
+    #[test]
+    fn keeps_boxes_and_confidence() {
+        let result = parse_tesseract_tsv(TSV);
+        let line = &result.lines[0];
+
+        assert_eq!(
+            line.bbox,
+            OcrBox {
+                x: 10,
+                y: 10,
+                width: 300,
+                height: 20
+            }
+        );
+        assert_eq!(line.words[1].bbox.x, 120);
+        assert_eq!(line.confidence, 94.0);
+        assert_eq!(result.confidence, Some(87.2));
+    }
+
+    // 空白单词和没有单词的行不会出现在结果中，负的置信度按 0 计算
+    #[test]
+    fn skips_empty_words() {
+        let tsv = "\
+4\t1\t1\t1\t1\t0\t0\t0\t100\t20\t-1\t
+5\t1\t1\t1\t1\t1\t0\t0\t10\t20\t-1\t \n\
+4\t1\t1\t1\t2\t0\t0\t20\t100\t20\t-1\t
+5\t1\t1\t1\t2\t1\t0\t20\t50\t20\t-1\ttext";
+
+        let result = parse_tesseract_tsv(tsv);
+
+        assert_eq!(result.text, "text");
+        assert_eq!(result.lines.len(), 1);
+        assert_eq!(result.confidence, Some(0.0));
+    }
+
+    #[test]
+    fn ignores_malformed_rows() {
+        let result = parse_tesseract_tsv("garbage\n4\t1\t1\n");
+
+        assert_eq!(result.text, "");
+        assert!(result.lines.is_empty());
+        assert_eq!(result.confidence, None);
+    }
+}
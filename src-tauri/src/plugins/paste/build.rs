@@ -1,4 +1,10 @@
-const COMMANDS: &[&str] = &["paste"];
+const COMMANDS: &[&str] = &[
+    "paste",
+    "get_focus_history",
+    "get_target_window",
+    "paste_into",
+    "list_windows",
+];
 
 fn main() {
     tauri_plugin::Builder::new(COMMANDS).build();
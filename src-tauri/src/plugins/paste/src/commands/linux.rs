@@ -1,15 +1,58 @@
-use super::wait;
+use super::{
+    parse_key_sequence, wait,
+    wayland::{self, is_wayland_session},
+    PasteError, PasteKey, PasteOutcome, DEFAULT_KEY_DELAY, PASTE_INTO_INTERVAL,
+};
+use crate::focus::{focus_history, now_millis, push_focus_history, target_window, WindowInfo};
 use rdev::{simulate, EventType, Key};
-use std::sync::Mutex;
+use std::{
+    ffi::CStr,
+    os::raw::c_int,
+    sync::{Mutex, OnceLock},
+};
 use tauri::command;
 use tauri_plugin_eco_window::MAIN_WINDOW_TITLE;
 use x11::xlib::{
-    self, Atom, Display, XCloseDisplay, XDefaultRootWindow, XFree, XGetInputFocus,
-    XGetWindowProperty, XInternAtom, XNextEvent, XOpenDisplay, XRaiseWindow, XSelectInput,
-    XSetInputFocus,
+    self, Atom, Display, XChangeProperty, XClassHint, XClientMessageEvent, XCloseDisplay,
+    XCreateSimpleWindow, XDefaultRootWindow, XDestroyWindow, XErrorEvent, XEvent, XFlush, XFree,
+    XGetClassHint, XGetInputFocus, XGetWindowProperty, XInternAtom, XNextEvent, XOpenDisplay,
+    XRaiseWindow, XSelectInput, XSendEvent, XSetErrorHandler, XSetInputFocus, XSync, XWindowEvent,
+    XA_ATOM, XA_CARDINAL, XA_STRING, XA_WINDOW,
 };
 
-static PREVIOUS_WINDOW: Mutex<Option<u64>> = Mutex::new(None);
+// 本插件打开的 X 连接，其它连接（例如 gtk）的错误仍然交给之前的错误处理函数
+static DISPLAYS: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+// XSetErrorHandler 接收和返回的错误处理函数
+type XErrorHandler = Option<unsafe extern "C" fn(*mut Display, *mut XErrorEvent) -> c_int>;
+
+// 安装错误处理函数前的错误处理函数
+static PREVIOUS_ERROR_HANDLER: OnceLock<XErrorHandler> = OnceLock::new();
+
+// 查询或激活的窗口可能已经被销毁，xlib 默认的错误处理函数收到 BadWindow 时会退出进程
+unsafe extern "C" fn handle_x_error(display: *mut Display, event: *mut XErrorEvent) -> c_int {
+    let owned = DISPLAYS
+        .lock()
+        .is_ok_and(|displays| displays.contains(&(display as usize)));
+
+    if owned {
+        let event = &*event;
+
+        log::warn!(
+            "X error {} on request {} for resource {}",
+            event.error_code,
+            event.request_code,
+            event.resourceid
+        );
+
+        return 0;
+    }
+
+    match PREVIOUS_ERROR_HANDLER.get().copied().flatten() {
+        Some(handler) => handler(display, event),
+        None => 0,
+    }
+}
 
 // 获取窗口标题
 fn get_net_wm_name(display: *mut Display, window: u64) -> std::result::Result<String, String> {
@@ -18,8 +61,7 @@ fn get_net_wm_name(display: *mut Display, window: u64) -> std::result::Result<St
     let mut nitems: u64 = 0;
     let mut bytes_after: u64 = 0;
     let mut prop: *mut u8 = std::ptr::null_mut();
-    let net_wm_name_atom =
-        unsafe { XInternAtom(display, b"_NET_WM_NAME\0".as_ptr() as _, xlib::False) };
+    let net_wm_name_atom = unsafe { XInternAtom(display, c"_NET_WM_NAME".as_ptr(), xlib::False) };
     let result = unsafe {
         XGetWindowProperty(
             display,
@@ -49,14 +91,164 @@ fn get_net_wm_name(display: *mut Display, window: u64) -> std::result::Result<St
     }
 }
 
+// 获取指定名称的 atom
+fn intern_atom(display: *mut Display, name: &[u8]) -> Atom {
+    unsafe { XInternAtom(display, name.as_ptr() as _, xlib::False) }
+}
+
+// 读取格式为 32 的窗口属性，例如 CARDINAL、WINDOW、ATOM 类型的列表
+fn get_window_property(
+    display: *mut Display,
+    window: u64,
+    name: &[u8],
+    property_type: Atom,
+    length: i64,
+) -> Vec<u64> {
+    let mut actual_type: Atom = 0;
+    let mut actual_format: i32 = 0;
+    let mut nitems: u64 = 0;
+    let mut bytes_after: u64 = 0;
+    let mut prop: *mut u8 = std::ptr::null_mut();
+    let result = unsafe {
+        XGetWindowProperty(
+            display,
+            window,
+            intern_atom(display, name),
+            0,
+            length,
+            xlib::False,
+            property_type,
+            &mut actual_type,
+            &mut actual_format,
+            &mut nitems,
+            &mut bytes_after,
+            &mut prop,
+        )
+    };
+    if result != xlib::Success as i32 || prop.is_null() {
+        return Vec::new();
+    }
+
+    // 格式为 32 的属性在 xlib 中以 long 存储
+    let values = if actual_format == 32 {
+        unsafe { std::slice::from_raw_parts(prop as *const std::os::raw::c_ulong, nitems as usize) }
+            .to_vec()
+    } else {
+        Vec::new()
+    };
+    unsafe { XFree(prop as *mut _) };
+
+    values
+}
+
+// 获取窗口所属的进程 id
+fn get_net_wm_pid(display: *mut Display, window: u64) -> Option<u32> {
+    get_window_property(display, window, b"_NET_WM_PID\0", XA_CARDINAL, 1)
+        .first()
+        .map(|pid| *pid as u32)
+}
+
+// 获取当前激活的窗口（_NET_ACTIVE_WINDOW）
+fn get_net_active_window(display: *mut Display) -> Option<u64> {
+    let root_window = unsafe { XDefaultRootWindow(display) };
+
+    get_window_property(display, root_window, b"_NET_ACTIVE_WINDOW\0", XA_WINDOW, 1)
+        .first()
+        .copied()
+        .filter(|window| *window != 0)
+}
+
+// 窗口管理器是否支持 _NET_ACTIVE_WINDOW
+fn supports_net_active_window(display: *mut Display) -> bool {
+    let root_window = unsafe { XDefaultRootWindow(display) };
+    let net_active_window_atom = intern_atom(display, b"_NET_ACTIVE_WINDOW\0");
+
+    get_window_property(display, root_window, b"_NET_SUPPORTED\0", XA_ATOM, 1024)
+        .contains(&net_active_window_atom)
+}
+
+// 获取窗口的类名（WM_CLASS）
+fn get_wm_class(display: *mut Display, window: u64) -> String {
+    unsafe {
+        let mut hint: XClassHint = std::mem::zeroed();
+
+        if XGetClassHint(display, window, &mut hint) == 0 {
+            return String::new();
+        }
+
+        let class = if hint.res_class.is_null() {
+            String::new()
+        } else {
+            CStr::from_ptr(hint.res_class)
+                .to_string_lossy()
+                .into_owned()
+        };
+
+        if !hint.res_name.is_null() {
+            XFree(hint.res_name as *mut _);
+        }
+        if !hint.res_class.is_null() {
+            XFree(hint.res_class as *mut _);
+        }
+
+        class
+    }
+}
+
+// 根据进程 id 获取应用名称
+fn get_process_name(pid: u32) -> Option<String> {
+    std::fs::read_to_string(format!("/proc/{pid}/comm"))
+        .ok()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+// 收集窗口信息
+fn get_window_info(display: *mut Display, window: u64, title: String) -> WindowInfo {
+    let pid = get_net_wm_pid(display, window);
+    let class = get_wm_class(display, window);
+    let app_name = pid
+        .and_then(get_process_name)
+        .unwrap_or_else(|| class.clone());
+
+    WindowInfo {
+        id: window,
+        pid,
+        app_name,
+        title,
+        class,
+        timestamp: now_millis(),
+    }
+}
+
+// 获取当前获得焦点的窗口
+fn get_focused_window(display: *mut Display, use_ewmh: bool) -> Option<u64> {
+    if use_ewmh {
+        return get_net_active_window(display);
+    }
+
+    let mut window: u64 = 0;
+    let mut revert_to_return: i32 = 0;
+    unsafe { XGetInputFocus(display, &mut window, &mut revert_to_return) };
+
+    // 0 为 None，1 为 PointerRoot
+    (window > 1).then_some(window)
+}
+
 // 监听窗口切换
 pub fn observe_app() {
     std::thread::spawn(|| unsafe {
-        let display = XOpenDisplay(std::ptr::null_mut());
-        if display.is_null() {
-            log::error!("Could not open display");
-            return;
-        }
+        let display = match open_display() {
+            Ok(display) => display,
+            Err(err) => {
+                log::error!("Could not open display: {}", err);
+                return;
+            }
+        };
+
+        // 支持 EWMH 的窗口管理器会在根窗口的 _NET_ACTIVE_WINDOW 属性上记录激活的窗口
+        let use_ewmh = supports_net_active_window(display);
+        let net_active_window_atom = intern_atom(display, b"_NET_ACTIVE_WINDOW\0");
 
         let root_window = XDefaultRootWindow(display);
         XSelectInput(
@@ -66,71 +258,395 @@ pub fn observe_app() {
         );
 
         loop {
-            let mut event = std::mem::zeroed();
+            let mut event: XEvent = std::mem::zeroed();
             XNextEvent(display, &mut event);
 
-            let mut window: u64 = 0;
-            let mut revert_to_return: i32 = 0;
-            XGetInputFocus(display, &mut window, &mut revert_to_return);
-
-            if window == 1 {
+            if use_ewmh
+                && (event.get_type() != xlib::PropertyNotify
+                    || event.property.atom != net_active_window_atom)
+            {
                 continue;
             }
 
+            let Some(window) = get_focused_window(display, use_ewmh) else {
+                continue;
+            };
+
             let wm_name = get_net_wm_name(display, window).unwrap_or_default();
 
             if wm_name.is_empty() || wm_name.eq(MAIN_WINDOW_TITLE) {
                 continue;
             }
 
-            let mut previous_window = PREVIOUS_WINDOW.lock().unwrap();
-            let _ = previous_window.insert(window);
+            // 同一个窗口的属性变化不需要重复记录
+            if let Some(target) = target_window() {
+                if target.id == window && target.title == wm_name {
+                    continue;
+                }
+            }
+
+            let info = get_window_info(display, window, wm_name);
+
+            // 忽略自身的窗口
+            if info.pid == Some(std::process::id()) {
+                continue;
+            }
+
+            push_focus_history(info);
         }
     });
 }
 
 // 获取上一个窗口
 pub fn get_previous_window() -> Option<u64> {
-    return PREVIOUS_WINDOW.lock().unwrap().clone();
+    target_window().map(|window| window.id)
+}
+
+// 打开与 X 服务的连接，连接上的错误只记录日志，不会退出进程
+fn open_display() -> Result<*mut Display, PasteError> {
+    // 在 gtk 初始化之后安装，保留 gtk 的错误处理函数
+    PREVIOUS_ERROR_HANDLER.get_or_init(|| unsafe { XSetErrorHandler(Some(handle_x_error)) });
+
+    let display = unsafe { XOpenDisplay(std::ptr::null_mut()) };
+
+    if display.is_null() {
+        return Err(PasteError::DisplayUnavailable(
+            "could not open display".to_string(),
+        ));
+    }
+
+    DISPLAYS.lock().unwrap().push(display as usize);
+
+    Ok(display)
 }
 
-// 聚焦上一个窗口
-fn focus_previous_window() {
+// 关闭连接，等待之前的请求处理完成，让错误在连接关闭前由错误处理函数处理
+fn close_display(display: *mut Display) {
     unsafe {
-        let display = XOpenDisplay(std::ptr::null_mut());
-        if display.is_null() {
-            log::error!("Could not open display");
-            return;
+        XSync(display, xlib::False);
+        XCloseDisplay(display);
+    }
+
+    DISPLAYS
+        .lock()
+        .unwrap()
+        .retain(|owned| *owned != display as usize);
+}
+
+// 获取 X 服务的当前时间戳：修改一个临时窗口的属性，从 PropertyNotify 事件中读取时间
+fn get_server_time(display: *mut Display) -> xlib::Time {
+    unsafe {
+        let root_window = XDefaultRootWindow(display);
+        let window = XCreateSimpleWindow(display, root_window, 0, 0, 1, 1, 0, 0, 0);
+        XSelectInput(display, window, xlib::PropertyChangeMask);
+
+        let timestamp_atom = intern_atom(display, b"_ECO_PASTE_TIMESTAMP\0");
+        XChangeProperty(
+            display,
+            window,
+            timestamp_atom,
+            XA_STRING,
+            8,
+            xlib::PropModeAppend,
+            [0u8].as_ptr(),
+            0,
+        );
+
+        let mut event: XEvent = std::mem::zeroed();
+        XWindowEvent(display, window, xlib::PropertyChangeMask, &mut event);
+        XDestroyWindow(display, window);
+
+        event.property.time
+    }
+}
+
+// 通过 _NET_ACTIVE_WINDOW 客户端消息请求窗口管理器激活窗口
+fn activate_window(display: *mut Display, window: u64) {
+    unsafe {
+        let mut message: XClientMessageEvent = std::mem::zeroed();
+        message.type_ = xlib::ClientMessage;
+        message.display = display;
+        message.window = window;
+        message.message_type = intern_atom(display, b"_NET_ACTIVE_WINDOW\0");
+        message.format = 32;
+        // 来源为 2（pager），窗口管理器不会将其视为抢占焦点
+        message.data.set_long(0, 2);
+        message.data.set_long(1, get_server_time(display) as _);
+        message.data.set_long(2, 0);
+
+        let mut event = XEvent::from(message);
+        XSendEvent(
+            display,
+            XDefaultRootWindow(display),
+            xlib::False,
+            xlib::SubstructureRedirectMask | xlib::SubstructureNotifyMask,
+            &mut event,
+        );
+        XFlush(display);
+    }
+}
+
+// 聚焦指定窗口，返回是否确认已获得焦点
+fn focus_window(window: u64) -> Result<bool, PasteError> {
+    let display = open_display()?;
+
+    let use_ewmh = supports_net_active_window(display);
+
+    if use_ewmh {
+        activate_window(display, window);
+    } else {
+        unsafe {
+            XRaiseWindow(display, window);
+            XSetInputFocus(display, window, xlib::RevertToNone, xlib::CurrentTime);
+            XSync(display, xlib::False);
         }
-        let window = match get_previous_window() {
-            Some(window) => window,
-            None => {
-                log::error!("Could not get active window");
-                return;
+    }
+
+    // 窗口管理器异步处理激活请求，等待焦点切换完成
+    let mut focused = false;
+
+    for _ in 0..10 {
+        if get_focused_window(display, use_ewmh) == Some(window) {
+            focused = true;
+            break;
+        }
+
+        wait(20);
+    }
+
+    close_display(display);
+
+    Ok(focused)
+}
+
+// 获取窗口管理器管理的所有顶层窗口（_NET_CLIENT_LIST）
+fn get_net_client_list(display: *mut Display) -> Vec<u64> {
+    let root_window = unsafe { XDefaultRootWindow(display) };
+
+    get_window_property(display, root_window, b"_NET_CLIENT_LIST\0", XA_WINDOW, 1024)
+}
+
+// 获取可以粘贴的窗口列表
+pub fn get_window_list() -> Result<Vec<WindowInfo>, PasteError> {
+    let display = open_display()?;
+
+    let history = focus_history();
+
+    let windows = get_net_client_list(display)
+        .into_iter()
+        .filter_map(|window| {
+            let title = get_net_wm_name(display, window).unwrap_or_default();
+
+            if title.is_empty() || title.eq(MAIN_WINDOW_TITLE) {
+                return None;
             }
-        };
 
-        XRaiseWindow(display, window);
-        XSetInputFocus(display, window, xlib::RevertToNone, xlib::CurrentTime);
-        XCloseDisplay(display);
+            let mut info = get_window_info(display, window, title);
+
+            // 忽略自身的窗口
+            if info.pid == Some(std::process::id()) {
+                return None;
+            }
+
+            // 没有获得过焦点的窗口时间戳为 0
+            info.timestamp = history
+                .iter()
+                .find(|item| item.id == window)
+                .map(|item| item.timestamp)
+                .unwrap_or_default();
+
+            Some(info)
+        })
+        .collect();
+
+    close_display(display);
+
+    Ok(windows)
+}
+
+// 聚焦上一个窗口，返回窗口以及是否确认已获得焦点
+// 没有上一个窗口或聚焦失败时不发送按键，避免粘贴到当前获得焦点的其它窗口
+fn focus_previous_window() -> Result<(u64, bool), PasteError> {
+    let window = get_previous_window().ok_or(PasteError::NoTargetWindow)?;
+
+    Ok((window, focus_window(window)?))
+}
+
+// 是否通过 wayland 的后端发送按键
+// wayland 下焦点历史只能记录到 XWayland 窗口，目标为原生窗口时记录的上一个窗口已经过期，
+// 因此不激活 X11 窗口，隐藏主窗口后由合成器将焦点交还给上一个窗口
+fn uses_wayland_keyboard() -> bool {
+    is_wayland_session() && wayland::detect_backend().is_some()
+}
+
+// 模拟按键事件
+fn dispatch(event_type: &EventType) -> Result<(), PasteError> {
+    wait(20);
+
+    simulate(event_type).map_err(|err| PasteError::KeystrokeFailed(format!("{:?}", err)))
+}
+
+// 按下并松开一个按键
+fn click(key: Key) -> Result<(), PasteError> {
+    dispatch(&EventType::KeyPress(key))?;
+    dispatch(&EventType::KeyRelease(key))
+}
+
+// 转换为 rdev 的按键
+fn to_rdev_key(key: PasteKey) -> Key {
+    match key {
+        PasteKey::Enter => Key::Return,
+        PasteKey::Tab => Key::Tab,
+        PasteKey::Escape => Key::Escape,
+        PasteKey::Space => Key::Space,
+        PasteKey::Backspace => Key::Backspace,
+        PasteKey::Up => Key::UpArrow,
+        PasteKey::Down => Key::DownArrow,
+        PasteKey::Left => Key::LeftArrow,
+        PasteKey::Right => Key::RightArrow,
+    }
+}
+
+// 粘贴后依次发送按键
+fn send_keys(keys: &[PasteKey], delay: u64) -> Result<(), PasteError> {
+    for key in keys {
+        wait(delay);
+
+        click(to_rdev_key(*key))?;
+    }
+
+    Ok(())
+}
+
+// 模拟按键使用的后端
+fn keyboard_backend() -> &'static str {
+    if is_wayland_session() {
+        if let Some(backend) = wayland::detect_backend() {
+            return backend.name();
+        }
     }
+
+    "rdev"
+}
+
+// 发送粘贴快捷键以及追加的按键，返回使用的后端
+fn send_paste(keys: &[PasteKey], delay: u64) -> Result<&'static str, PasteError> {
+    // wayland 原生窗口收不到 rdev 通过 X11 模拟的按键，运行时选择 wayland 的后端
+    if is_wayland_session() {
+        if let Some(backend) = wayland::detect_backend() {
+            wayland::send_paste(backend, keys, delay)?;
+
+            return Ok(backend.name());
+        }
+    }
+
+    // 无论是否成功都要松开 shift，避免按键一直处于按下状态
+    dispatch(&EventType::KeyPress(Key::ShiftLeft))?;
+    let result = click(Key::Insert);
+    let released = dispatch(&EventType::KeyRelease(Key::ShiftLeft));
+
+    result.and(released)?;
+
+    send_keys(keys, delay)?;
+
+    Ok("rdev")
 }
 
 // 粘贴
 #[command]
-pub async fn paste() {
-    fn dispatch(event_type: &EventType) {
-        wait(20);
+pub async fn paste(keys: Option<String>, delay: Option<u64>) -> Result<PasteOutcome, PasteError> {
+    let keys = parse_key_sequence(keys.as_deref())?;
 
-        simulate(event_type).unwrap();
-    }
+    let (target, focused) = if uses_wayland_keyboard() {
+        (None, false)
+    } else {
+        let (window, focused) = focus_previous_window()?;
 
-    focus_previous_window();
+        (Some(window), focused)
+    };
 
     wait(100);
 
-    dispatch(&EventType::KeyPress(Key::ShiftLeft));
-    dispatch(&EventType::KeyPress(Key::Insert));
-    dispatch(&EventType::KeyRelease(Key::Insert));
-    dispatch(&EventType::KeyRelease(Key::ShiftLeft));
+    let backend = send_paste(&keys, delay.unwrap_or(DEFAULT_KEY_DELAY))?;
+
+    Ok(PasteOutcome {
+        target,
+        focused,
+        keystroke: "Shift+Insert".to_string(),
+        backend: backend.to_string(),
+        keys,
+        error: None,
+    })
+}
+
+// 依次粘贴到指定的窗口
+// 窗口 id 为 X11 窗口，wayland 下只能粘贴到 XWayland 窗口，原生窗口不会出现在窗口列表中
+#[command]
+pub async fn paste_into(
+    window_ids: Vec<u64>,
+    keys: Option<String>,
+    delay: Option<u64>,
+) -> Result<Vec<PasteOutcome>, PasteError> {
+    let keys = parse_key_sequence(keys.as_deref())?;
+
+    let mut outcomes = Vec::with_capacity(window_ids.len());
+
+    for (index, window) in window_ids.into_iter().enumerate() {
+        if index > 0 {
+            wait(PASTE_INTO_INTERVAL);
+        }
+
+        let outcome = focus_window(window).and_then(|focused| {
+            wait(100);
+
+            let backend = send_paste(&keys, delay.unwrap_or(DEFAULT_KEY_DELAY))?;
+
+            Ok(PasteOutcome {
+                target: Some(window),
+                focused,
+                keystroke: "Shift+Insert".to_string(),
+                backend: backend.to_string(),
+                keys: keys.clone(),
+                error: None,
+            })
+        });
+
+        // 某个窗口粘贴失败时记录原因，继续粘贴到其余的窗口
+        outcomes.push(outcome.unwrap_or_else(|err| {
+            PasteOutcome::failed(window, "Shift+Insert", keyboard_backend(), &keys, err)
+        }));
+    }
+
+    Ok(outcomes)
+}
+
+// 获取可以粘贴的窗口列表
+#[command]
+pub async fn list_windows() -> Result<Vec<WindowInfo>, PasteError> {
+    get_window_list()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 不存在的窗口
+    const MISSING_WINDOW: u64 = 0x3ff_fffe;
+
+    // 需要 X 服务，由 verify-x11-paste.sh 在 Xvfb 中运行
+    #[test]
+    #[ignore]
+    fn survives_bad_window() {
+        let display = open_display().unwrap();
+
+        assert!(get_net_wm_name(display, MISSING_WINDOW).is_err());
+        assert_eq!(get_net_wm_pid(display, MISSING_WINDOW), None);
+        assert_eq!(get_wm_class(display, MISSING_WINDOW), "");
+
+        activate_window(display, MISSING_WINDOW);
+        close_display(display);
+
+        assert!(!focus_window(MISSING_WINDOW).unwrap());
+        assert!(get_window_list().is_ok());
+    }
 }
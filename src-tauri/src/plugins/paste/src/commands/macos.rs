@@ -1,16 +1,60 @@
-use cocoa::base::{id, nil};
+use super::{
+    parse_key_sequence, wait, PasteError, PasteKey, PasteOutcome, DEFAULT_KEY_DELAY,
+    PASTE_INTO_INTERVAL,
+};
+use crate::focus::{focus_history, now_millis, push_focus_history, target_window, WindowInfo};
+use cocoa::base::{id, nil, BOOL, NO};
 use cocoa::foundation::{NSAutoreleasePool, NSString};
 use objc::declare::ClassDecl;
 use objc::runtime::{Class, Object, Sel};
 use objc::{msg_send, sel, sel_impl};
 use std::ffi::CStr;
 use std::process::Command;
-use std::sync::Mutex;
 use std::thread;
 use tauri::{command, AppHandle, Runtime, WebviewWindow};
 use tauri_plugin_eco_window::{set_macos_panel, MacOSPanelStatus, MAIN_WINDOW_TITLE};
 
-static PREVIOUS_WINDOW: Mutex<Option<i32>> = Mutex::new(None);
+// NSRunningApplication 的 activationPolicy：出现在程序坞中的普通应用
+const NS_APPLICATION_ACTIVATION_POLICY_REGULAR: i64 = 0;
+// NSApplicationActivateIgnoringOtherApps
+const NS_APPLICATION_ACTIVATE_IGNORING_OTHER_APPS: u64 = 1 << 1;
+
+// 将 NSString 转换为 String
+unsafe fn ns_string_to_string(value: id) -> String {
+    if value == nil {
+        return String::new();
+    }
+
+    let value_str: *const i8 = msg_send![value, UTF8String];
+
+    if value_str.is_null() {
+        return String::new();
+    }
+
+    CStr::from_ptr(value_str).to_string_lossy().into_owned()
+}
+
+// 收集应用信息，macos 以应用为单位切换焦点，没有辅助功能权限时无法获取窗口标题
+unsafe fn get_application_info(app: id) -> Option<WindowInfo> {
+    let localized_name: id = msg_send![app, localizedName];
+    let name = ns_string_to_string(localized_name);
+
+    if name == MAIN_WINDOW_TITLE {
+        return None;
+    }
+
+    let process_id: i32 = msg_send![app, processIdentifier];
+    let bundle_identifier: id = msg_send![app, bundleIdentifier];
+
+    Some(WindowInfo {
+        id: process_id as u64,
+        pid: Some(process_id as u32),
+        app_name: name,
+        title: String::new(),
+        class: ns_string_to_string(bundle_identifier),
+        timestamp: now_millis(),
+    })
+}
 
 extern "C" fn application_did_activate(_self: &Object, _cmd: Sel, notification: id) {
     unsafe {
@@ -26,19 +70,9 @@ extern "C" fn application_did_activate(_self: &Object, _cmd: Sel, notification:
             return;
         }
 
-        let localized_name: id = msg_send![app, localizedName];
-        let name_str: *const i8 = msg_send![localized_name, UTF8String];
-        let name_cstr = CStr::from_ptr(name_str);
-        let name = name_cstr.to_str().unwrap_or("Unknown").to_string();
-
-        if name == MAIN_WINDOW_TITLE {
-            return;
+        if let Some(info) = get_application_info(app) {
+            push_focus_history(info);
         }
-
-        let process_id: i32 = msg_send![app, processIdentifier];
-
-        let mut previous_window = PREVIOUS_WINDOW.lock().unwrap();
-        let _ = previous_window.insert(process_id);
     }
 }
 
@@ -75,18 +109,234 @@ pub fn observe_app() {
 
 // 获取前一个窗口
 pub fn get_previous_window() -> Option<i32> {
-    return PREVIOUS_WINDOW.lock().unwrap().clone();
+    target_window().map(|window| window.id as i32)
+}
+
+// 激活指定进程的应用，返回是否确认已成为前台应用
+fn focus_application(process_id: i32) -> Result<bool, PasteError> {
+    unsafe {
+        let running_application = Class::get("NSRunningApplication")
+            .ok_or_else(|| PasteError::FocusFailed("NSRunningApplication unavailable".into()))?;
+
+        let app: id = msg_send![
+            running_application,
+            runningApplicationWithProcessIdentifier: process_id
+        ];
+        if app == nil {
+            return Err(PasteError::FocusFailed(format!(
+                "application {process_id} is not running"
+            )));
+        }
+
+        let activated: BOOL = msg_send![
+            app,
+            activateWithOptions: NS_APPLICATION_ACTIVATE_IGNORING_OTHER_APPS
+        ];
+        if activated == NO {
+            return Err(PasteError::FocusFailed(format!(
+                "failed to activate application {process_id}"
+            )));
+        }
+    }
+
+    wait(100);
+
+    Ok(get_frontmost_process_id() == Some(process_id))
+}
+
+// 获取可以粘贴的应用列表
+pub fn get_window_list() -> Result<Vec<WindowInfo>, PasteError> {
+    let history = focus_history();
+
+    let mut windows = Vec::new();
+
+    unsafe {
+        let workspace: id = msg_send![Class::get("NSWorkspace").unwrap(), sharedWorkspace];
+        let apps: id = msg_send![workspace, runningApplications];
+        let count: usize = msg_send![apps, count];
+
+        for index in 0..count {
+            let app: id = msg_send![apps, objectAtIndex: index];
+            let activation_policy: i64 = msg_send![app, activationPolicy];
+
+            if activation_policy != NS_APPLICATION_ACTIVATION_POLICY_REGULAR {
+                continue;
+            }
+
+            if let Some(mut info) = get_application_info(app) {
+                // 没有获得过焦点的应用时间戳为 0
+                info.timestamp = history
+                    .iter()
+                    .find(|item| item.id == info.id)
+                    .map(|item| item.timestamp)
+                    .unwrap_or_default();
+
+                windows.push(info);
+            }
+        }
+    }
+
+    Ok(windows)
+}
+
+// 转换为 macos 的虚拟键码：https://eastmanreference.com/complete-list-of-applescript-key-codes
+fn to_key_code(key: PasteKey) -> u16 {
+    match key {
+        PasteKey::Enter => 36,
+        PasteKey::Tab => 48,
+        PasteKey::Escape => 53,
+        PasteKey::Space => 49,
+        PasteKey::Backspace => 51,
+        PasteKey::Up => 126,
+        PasteKey::Down => 125,
+        PasteKey::Left => 123,
+        PasteKey::Right => 124,
+    }
+}
+
+// 通过 osascript 执行 System Events 脚本
+fn run_script(script: &str) -> Result<(), PasteError> {
+    let output = Command::new("osascript")
+        .args(["-e", script])
+        .output()
+        .map_err(|err| PasteError::KeystrokeFailed(err.to_string()))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        return Err(PasteError::KeystrokeFailed(stderr.trim().to_string()));
+    }
+
+    Ok(())
+}
+
+// 获取当前前台应用的进程 id
+fn get_frontmost_process_id() -> Option<i32> {
+    unsafe {
+        let workspace: id = msg_send![Class::get("NSWorkspace")?, sharedWorkspace];
+        let app: id = msg_send![workspace, frontmostApplication];
+
+        if app == nil {
+            return None;
+        }
+
+        let process_id: i32 = msg_send![app, processIdentifier];
+
+        Some(process_id)
+    }
+}
+
+// 发送粘贴快捷键以及追加的按键
+fn send_paste(keys: &[PasteKey], delay: u64) -> Result<(), PasteError> {
+    let script = r#"tell application "System Events" to keystroke "v" using command down"#;
+
+    run_script(script)?;
+
+    send_keys(keys, delay)
+}
+
+// 粘贴后依次发送按键
+fn send_keys(keys: &[PasteKey], delay: u64) -> Result<(), PasteError> {
+    for key in keys {
+        wait(delay);
+
+        let script = format!(
+            r#"tell application "System Events" to key code {}"#,
+            to_key_code(*key)
+        );
+
+        run_script(&script)?;
+    }
+
+    Ok(())
 }
 
 // 粘贴
 #[command]
-pub async fn paste<R: Runtime>(app_handle: AppHandle<R>, window: WebviewWindow<R>) {
+pub async fn paste<R: Runtime>(
+    app_handle: AppHandle<R>,
+    window: WebviewWindow<R>,
+    keys: Option<String>,
+    delay: Option<u64>,
+) -> Result<PasteOutcome, PasteError> {
     set_macos_panel(&app_handle, &window, MacOSPanelStatus::Resign);
 
-    let script = r#"tell application "System Events" to keystroke "v" using command down"#;
+    paste_frontmost(keys, delay)
+}
 
-    Command::new("osascript")
-        .args(["-e", script])
-        .output()
-        .expect("failed to execute process");
+// 粘贴到前台应用，没有主窗口的守护模式也使用该方法
+pub fn paste_frontmost(
+    keys: Option<String>,
+    delay: Option<u64>,
+) -> Result<PasteOutcome, PasteError> {
+    let keys = parse_key_sequence(keys.as_deref())?;
+
+    // 主窗口是不抢占焦点的面板，前台应用应该仍然是上一个应用，不是时先激活上一个应用
+    // 没有上一个应用或激活失败时不发送按键，避免粘贴到当前的前台应用
+    let target = get_previous_window().ok_or(PasteError::NoTargetWindow)?;
+
+    let focused = if get_frontmost_process_id() == Some(target) {
+        true
+    } else {
+        focus_application(target)?
+    };
+
+    send_paste(&keys, delay.unwrap_or(DEFAULT_KEY_DELAY))?;
+
+    Ok(PasteOutcome {
+        target: Some(target as u64),
+        focused,
+        keystroke: "Cmd+V".to_string(),
+        backend: "osascript".to_string(),
+        keys,
+        error: None,
+    })
+}
+
+// 依次粘贴到指定的应用
+#[command]
+pub async fn paste_into<R: Runtime>(
+    app_handle: AppHandle<R>,
+    window: WebviewWindow<R>,
+    window_ids: Vec<u64>,
+    keys: Option<String>,
+    delay: Option<u64>,
+) -> Result<Vec<PasteOutcome>, PasteError> {
+    let keys = parse_key_sequence(keys.as_deref())?;
+
+    set_macos_panel(&app_handle, &window, MacOSPanelStatus::Resign);
+
+    let mut outcomes = Vec::with_capacity(window_ids.len());
+
+    for (index, process_id) in window_ids.into_iter().enumerate() {
+        if index > 0 {
+            wait(PASTE_INTO_INTERVAL);
+        }
+
+        let outcome = focus_application(process_id as i32).and_then(|focused| {
+            send_paste(&keys, delay.unwrap_or(DEFAULT_KEY_DELAY))?;
+
+            Ok(PasteOutcome {
+                target: Some(process_id),
+                focused,
+                keystroke: "Cmd+V".to_string(),
+                backend: "osascript".to_string(),
+                keys: keys.clone(),
+                error: None,
+            })
+        });
+
+        // 某个应用粘贴失败时记录原因，继续粘贴到其余的应用
+        outcomes.push(outcome.unwrap_or_else(|err| {
+            PasteOutcome::failed(process_id, "Cmd+V", "osascript", &keys, err)
+        }));
+    }
+
+    Ok(outcomes)
+}
+
+// 获取可以粘贴的应用列表
+#[command]
+pub async fn list_windows() -> Result<Vec<WindowInfo>, PasteError> {
+    get_window_list()
 }
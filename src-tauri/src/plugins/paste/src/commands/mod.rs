@@ -7,6 +7,9 @@ mod windows;
 #[cfg(target_os = "linux")]
 mod linux;
 
+#[cfg(target_os = "linux")]
+mod wayland;
+
 #[cfg(target_os = "macos")]
 pub use macos::*;
 
@@ -16,9 +19,191 @@ pub use windows::*;
 #[cfg(target_os = "linux")]
 pub use linux::*;
 
-#[cfg(not(target_os = "macos"))]
+use crate::focus::{focus_history, target_window, WindowInfo};
+use serde::Serialize;
+use std::{fmt, str::FromStr};
+use tauri::command;
+
+// 一次粘贴的结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasteOutcome {
+    // 粘贴的目标窗口：linux 为 X11 窗口 id，windows 为 HWND，macos 为进程 id
+    pub target: Option<u64>,
+    // 发送按键前目标窗口是否确认已获得焦点
+    pub focused: bool,
+    // 发送的粘贴快捷键
+    pub keystroke: String,
+    // 模拟按键使用的后端，例如：rdev、enigo、osascript、virtual-keyboard、uinput
+    pub backend: String,
+    // 粘贴后追加发送的按键
+    pub keys: Vec<PasteKey>,
+    // 依次粘贴到多个窗口时，该窗口粘贴失败的原因
+    pub error: Option<PasteError>,
+}
+
+impl PasteOutcome {
+    // 粘贴到指定窗口失败时的结果
+    pub fn failed(
+        target: u64,
+        keystroke: &str,
+        backend: &str,
+        keys: &[PasteKey],
+        error: PasteError,
+    ) -> Self {
+        Self {
+            target: Some(target),
+            focused: false,
+            keystroke: keystroke.to_string(),
+            backend: backend.to_string(),
+            keys: keys.to_vec(),
+            error: Some(error),
+        }
+    }
+}
+
+// 粘贴失败的原因
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", content = "message", rename_all = "camelCase")]
+pub enum PasteError {
+    // 按键序列中存在不支持的按键
+    InvalidKey(String),
+    // 没有可以粘贴的目标窗口
+    NoTargetWindow,
+    // 无法连接到显示服务
+    DisplayUnavailable(String),
+    // 切换到目标窗口失败
+    FocusFailed(String),
+    // 模拟按键失败
+    KeystrokeFailed(String),
+}
+
+impl fmt::Display for PasteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PasteError::InvalidKey(key) => write!(f, "unsupported key: {key}"),
+            PasteError::NoTargetWindow => write!(f, "no target window to paste into"),
+            PasteError::DisplayUnavailable(message) => {
+                write!(f, "display unavailable: {message}")
+            }
+            PasteError::FocusFailed(message) => write!(f, "failed to focus window: {message}"),
+            PasteError::KeystrokeFailed(message) => {
+                write!(f, "failed to send keystroke: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PasteError {}
+
+// 依次粘贴到多个窗口时，每个窗口之间的间隔（毫秒）
+pub const PASTE_INTO_INTERVAL: u64 = 200;
+
+// 粘贴后追加按键之间的默认间隔（毫秒）
+pub const DEFAULT_KEY_DELAY: u64 = 50;
+
+// 粘贴后可以追加发送的按键
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum PasteKey {
+    Enter,
+    Tab,
+    Escape,
+    Space,
+    Backspace,
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl FromStr for PasteKey {
+    type Err = PasteError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().as_str() {
+            "enter" | "return" => Ok(PasteKey::Enter),
+            "tab" => Ok(PasteKey::Tab),
+            "esc" | "escape" => Ok(PasteKey::Escape),
+            "space" => Ok(PasteKey::Space),
+            "backspace" => Ok(PasteKey::Backspace),
+            "up" => Ok(PasteKey::Up),
+            "down" => Ok(PasteKey::Down),
+            "left" => Ok(PasteKey::Left),
+            "right" => Ok(PasteKey::Right),
+            _ => Err(PasteError::InvalidKey(value.trim().to_string())),
+        }
+    }
+}
+
+// 解析按键序列，例如：`Enter`、`Tab,Tab,Enter`
+pub fn parse_key_sequence(sequence: Option<&str>) -> Result<Vec<PasteKey>, PasteError> {
+    let Some(sequence) = sequence else {
+        return Ok(Vec::new());
+    };
+
+    sequence
+        .split(',')
+        .filter(|key| !key.trim().is_empty())
+        .map(PasteKey::from_str)
+        .collect()
+}
+
 pub fn wait(millis: u64) {
     use std::{thread, time};
 
     thread::sleep(time::Duration::from_millis(millis));
 }
+
+// 获取最近获得过焦点的窗口，最近的排在最前面
+#[command]
+pub async fn get_focus_history() -> Vec<WindowInfo> {
+    focus_history()
+}
+
+// 获取粘贴的目标窗口
+#[command]
+pub async fn get_target_window() -> Option<WindowInfo> {
+    target_window()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_comma_separated_keys() {
+        let keys = parse_key_sequence(Some("Tab,Tab,Enter")).unwrap();
+
+        assert_eq!(keys, [PasteKey::Tab, PasteKey::Tab, PasteKey::Enter]);
+    }
+
+    #[test]
+    fn parses_aliases_case_and_whitespace() {
+        let keys = parse_key_sequence(Some(" esc , SPACE ,return,backspace")).unwrap();
+
+        assert_eq!(
+            keys,
+            [
+                PasteKey::Escape,
+                PasteKey::Space,
+                PasteKey::Enter,
+                PasteKey::Backspace
+            ]
+        );
+    }
+
+    // 没有按键序列或者只有分隔符时不追加按键
+    #[test]
+    fn skips_empty_sequences() {
+        assert!(parse_key_sequence(None).unwrap().is_empty());
+        assert!(parse_key_sequence(Some("")).unwrap().is_empty());
+        assert!(parse_key_sequence(Some(" , ,")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_unsupported_keys() {
+        let err = parse_key_sequence(Some("Tab, Ctrl+V")).unwrap_err();
+
+        assert!(matches!(err, PasteError::InvalidKey(key) if key == "Ctrl+V"));
+    }
+}
@@ -0,0 +1,473 @@
+use super::{wait, PasteError, PasteKey};
+use std::{
+    env,
+    fs::{self, File, OpenOptions},
+    io::Write,
+    os::{fd::AsRawFd, unix::fs::OpenOptionsExt},
+    sync::OnceLock,
+    time::Instant,
+};
+
+// linux 输入子系统的键码：https://github.com/torvalds/linux/blob/master/include/uapi/linux/input-event-codes.h
+const KEY_ESC: u16 = 1;
+const KEY_BACKSPACE: u16 = 14;
+const KEY_TAB: u16 = 15;
+const KEY_ENTER: u16 = 28;
+const KEY_LEFTSHIFT: u16 = 42;
+const KEY_SPACE: u16 = 57;
+const KEY_UP: u16 = 103;
+const KEY_LEFT: u16 = 105;
+const KEY_RIGHT: u16 = 106;
+const KEY_DOWN: u16 = 108;
+const KEY_INSERT: u16 = 110;
+
+// 虚拟键盘需要注册的所有按键
+const KEYS: [u16; 11] = [
+    KEY_ESC,
+    KEY_BACKSPACE,
+    KEY_TAB,
+    KEY_ENTER,
+    KEY_LEFTSHIFT,
+    KEY_SPACE,
+    KEY_UP,
+    KEY_LEFT,
+    KEY_RIGHT,
+    KEY_DOWN,
+    KEY_INSERT,
+];
+
+static BACKEND: OnceLock<KeyboardBackend> = OnceLock::new();
+
+// wayland 下模拟按键的后端
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyboardBackend {
+    // 合成器提供的 zwp_virtual_keyboard_v1 协议
+    VirtualKeyboard,
+    // 通过 /dev/uinput 创建的内核虚拟设备
+    Uinput,
+}
+
+impl KeyboardBackend {
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeyboardBackend::VirtualKeyboard => "virtual-keyboard",
+            KeyboardBackend::Uinput => "uinput",
+        }
+    }
+}
+
+// 一次按键事件：发送前等待的毫秒数、键码、是否按下
+struct KeyEvent {
+    wait: u64,
+    code: u16,
+    pressed: bool,
+}
+
+// 是否运行在 wayland 会话中
+pub fn is_wayland_session() -> bool {
+    env::var_os("WAYLAND_DISPLAY").is_some()
+        || env::var("XDG_SESSION_TYPE").is_ok_and(|session| session == "wayland")
+}
+
+// 检测可用的后端，优先使用虚拟键盘协议，合成器不支持时使用 uinput
+// 只缓存检测成功的结果，合成器或 uinput 权限可能在之后才就绪
+pub fn detect_backend() -> Option<KeyboardBackend> {
+    if let Some(backend) = BACKEND.get() {
+        return Some(*backend);
+    }
+
+    let backend = if virtual_keyboard::is_available() {
+        KeyboardBackend::VirtualKeyboard
+    } else if uinput::is_available() {
+        KeyboardBackend::Uinput
+    } else {
+        log::warn!("No wayland keyboard backend available, falling back to X11");
+
+        return None;
+    };
+
+    Some(*BACKEND.get_or_init(|| backend))
+}
+
+// 转换为 linux 输入子系统的键码
+fn to_key_code(key: PasteKey) -> u16 {
+    match key {
+        PasteKey::Enter => KEY_ENTER,
+        PasteKey::Tab => KEY_TAB,
+        PasteKey::Escape => KEY_ESC,
+        PasteKey::Space => KEY_SPACE,
+        PasteKey::Backspace => KEY_BACKSPACE,
+        PasteKey::Up => KEY_UP,
+        PasteKey::Down => KEY_DOWN,
+        PasteKey::Left => KEY_LEFT,
+        PasteKey::Right => KEY_RIGHT,
+    }
+}
+
+// 生成粘贴快捷键（Shift+Insert）以及追加按键的事件序列
+fn paste_events(keys: &[PasteKey], delay: u64) -> Vec<KeyEvent> {
+    let mut events = vec![
+        KeyEvent {
+            wait: 0,
+            code: KEY_LEFTSHIFT,
+            pressed: true,
+        },
+        KeyEvent {
+            wait: 20,
+            code: KEY_INSERT,
+            pressed: true,
+        },
+        KeyEvent {
+            wait: 20,
+            code: KEY_INSERT,
+            pressed: false,
+        },
+        KeyEvent {
+            wait: 20,
+            code: KEY_LEFTSHIFT,
+            pressed: false,
+        },
+    ];
+
+    for key in keys {
+        let code = to_key_code(*key);
+
+        events.push(KeyEvent {
+            wait: delay,
+            code,
+            pressed: true,
+        });
+        events.push(KeyEvent {
+            wait: 20,
+            code,
+            pressed: false,
+        });
+    }
+
+    events
+}
+
+// 使用指定后端发送粘贴快捷键以及追加的按键
+pub fn send_paste(
+    backend: KeyboardBackend,
+    keys: &[PasteKey],
+    delay: u64,
+) -> Result<(), PasteError> {
+    let events = paste_events(keys, delay);
+
+    match backend {
+        KeyboardBackend::VirtualKeyboard => virtual_keyboard::send(&events),
+        KeyboardBackend::Uinput => uinput::send(&events),
+    }
+    .map_err(PasteError::KeystrokeFailed)
+}
+
+// zwp_virtual_keyboard_v1：https://wayland.app/protocols/virtual-keyboard-unstable-v1
+mod virtual_keyboard {
+    use super::{wait, File, Instant, KeyEvent, Write, KEY_LEFTSHIFT};
+    use std::os::fd::{AsFd, FromRawFd};
+    use wayland_client::{
+        delegate_noop,
+        protocol::{wl_keyboard::KeymapFormat, wl_registry, wl_seat::WlSeat},
+        Connection, Dispatch, EventQueue, Proxy, QueueHandle,
+    };
+    use wayland_protocols_misc::zwp_virtual_keyboard_v1::client::{
+        zwp_virtual_keyboard_manager_v1::ZwpVirtualKeyboardManagerV1,
+        zwp_virtual_keyboard_v1::ZwpVirtualKeyboardV1,
+    };
+
+    // 只包含粘贴所需按键的 xkb 键盘布局，键码为 linux 键码 + 8
+    const KEYMAP: &str = r#"xkb_keymap {
+xkb_keycodes "eco-paste" {
+    minimum = 8;
+    maximum = 255;
+    <ESC> = 9;
+    <BKSP> = 22;
+    <TAB> = 23;
+    <RTRN> = 36;
+    <LFSH> = 50;
+    <SPCE> = 65;
+    <UP> = 111;
+    <LEFT> = 113;
+    <RGHT> = 114;
+    <DOWN> = 116;
+    <INS> = 118;
+};
+xkb_types "eco-paste" { include "complete" };
+xkb_compat "eco-paste" { include "complete" };
+xkb_symbols "eco-paste" {
+    key <ESC> { [ Escape ] };
+    key <BKSP> { [ BackSpace ] };
+    key <TAB> { [ Tab ] };
+    key <RTRN> { [ Return ] };
+    key <LFSH> { [ Shift_L ] };
+    key <SPCE> { [ space ] };
+    key <UP> { [ Up ] };
+    key <LEFT> { [ Left ] };
+    key <RGHT> { [ Right ] };
+    key <DOWN> { [ Down ] };
+    key <INS> { [ Insert ] };
+    modifier_map Shift { <LFSH> };
+};
+};
+"#;
+
+    // xkb 中 Shift 的修饰键掩码
+    const SHIFT_MASK: u32 = 1;
+
+    #[derive(Default)]
+    struct State {
+        seat: Option<WlSeat>,
+        manager: Option<ZwpVirtualKeyboardManagerV1>,
+    }
+
+    impl Dispatch<wl_registry::WlRegistry, ()> for State {
+        fn event(
+            state: &mut Self,
+            registry: &wl_registry::WlRegistry,
+            event: wl_registry::Event,
+            _data: &(),
+            _conn: &Connection,
+            qh: &QueueHandle<Self>,
+        ) {
+            if let wl_registry::Event::Global {
+                name,
+                interface,
+                version,
+            } = event
+            {
+                if interface == WlSeat::interface().name && state.seat.is_none() {
+                    state.seat = Some(registry.bind(name, version.min(7), qh, ()));
+                } else if interface == ZwpVirtualKeyboardManagerV1::interface().name {
+                    state.manager = Some(registry.bind(name, 1, qh, ()));
+                }
+            }
+        }
+    }
+
+    delegate_noop!(State: ignore WlSeat);
+    delegate_noop!(State: ZwpVirtualKeyboardManagerV1);
+    delegate_noop!(State: ZwpVirtualKeyboardV1);
+
+    // 连接合成器并获取 seat 和虚拟键盘管理器
+    fn connect() -> Result<(Connection, EventQueue<State>, State), String> {
+        let conn = Connection::connect_to_env().map_err(|err| err.to_string())?;
+        let mut queue = conn.new_event_queue();
+        let mut state = State::default();
+
+        conn.display().get_registry(&queue.handle(), ());
+        queue.roundtrip(&mut state).map_err(|err| err.to_string())?;
+
+        Ok((conn, queue, state))
+    }
+
+    // 合成器是否支持虚拟键盘协议
+    pub fn is_available() -> bool {
+        connect().is_ok_and(|(_, _, state)| state.seat.is_some() && state.manager.is_some())
+    }
+
+    // 将键盘布局写入匿名的内存文件，合成器通过文件描述符读取
+    fn create_keymap_file() -> Result<File, String> {
+        let fd = unsafe { libc::memfd_create(c"eco-paste-keymap".as_ptr(), libc::MFD_CLOEXEC) };
+
+        if fd < 0 {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+
+        let mut file = unsafe { File::from_raw_fd(fd) };
+
+        file.write_all(KEYMAP.as_bytes())
+            .and_then(|_| file.write_all(&[0]))
+            .map_err(|err| err.to_string())?;
+
+        Ok(file)
+    }
+
+    // 通过虚拟键盘依次发送按键事件
+    pub fn send(events: &[KeyEvent]) -> Result<(), String> {
+        let (conn, mut queue, mut state) = connect()?;
+
+        let (Some(seat), Some(manager)) = (&state.seat, &state.manager) else {
+            return Err("compositor does not support zwp_virtual_keyboard_v1".to_string());
+        };
+
+        let qh = queue.handle();
+        let keyboard = manager.create_virtual_keyboard(seat, &qh, ());
+
+        let keymap = create_keymap_file()?;
+        keyboard.keymap(
+            KeymapFormat::XkbV1.into(),
+            keymap.as_fd(),
+            (KEYMAP.len() + 1) as u32,
+        );
+
+        let start = Instant::now();
+
+        for event in events {
+            wait(event.wait);
+
+            let time = start.elapsed().as_millis() as u32;
+
+            keyboard.key(time, event.code as u32, event.pressed as u32);
+
+            if event.code == KEY_LEFTSHIFT {
+                let depressed = if event.pressed { SHIFT_MASK } else { 0 };
+
+                keyboard.modifiers(depressed, 0, 0, 0);
+            }
+
+            conn.flush().map_err(|err| err.to_string())?;
+        }
+
+        queue.roundtrip(&mut state).map_err(|err| err.to_string())?;
+
+        keyboard.destroy();
+
+        let _ = conn.flush();
+
+        Ok(())
+    }
+}
+
+// /dev/uinput：https://www.kernel.org/doc/html/latest/input/uinput.html
+mod uinput {
+    use super::{fs, wait, AsRawFd, File, KeyEvent, OpenOptions, OpenOptionsExt, KEYS};
+    use std::{io::Write, mem, slice};
+
+    const UINPUT_PATH: &str = "/dev/uinput";
+    const EV_SYN: u16 = 0x00;
+    const EV_KEY: u16 = 0x01;
+    const SYN_REPORT: u16 = 0;
+    const BUS_VIRTUAL: u16 = 0x06;
+
+    // ioctl 请求码，参考 linux/uinput.h
+    const UI_SET_EVBIT: libc::c_ulong = 0x4004_5564;
+    const UI_SET_KEYBIT: libc::c_ulong = 0x4004_5565;
+    const UI_DEV_SETUP: libc::c_ulong = 0x405c_5503;
+    const UI_DEV_CREATE: libc::c_ulong = 0x5501;
+    const UI_DEV_DESTROY: libc::c_ulong = 0x5502;
+
+    // 当前用户是否有权限写入 /dev/uinput
+    pub fn is_available() -> bool {
+        fs::metadata(UINPUT_PATH).is_ok() && open().is_ok()
+    }
+
+    fn open() -> std::io::Result<File> {
+        OpenOptions::new()
+            .write(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(UINPUT_PATH)
+    }
+
+    fn ioctl(file: &File, request: libc::c_ulong, value: libc::c_ulong) -> Result<(), String> {
+        if unsafe { libc::ioctl(file.as_raw_fd(), request as _, value) } < 0 {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+
+        Ok(())
+    }
+
+    // 写入一个输入事件
+    fn emit(file: &mut File, kind: u16, code: u16, value: i32) -> Result<(), String> {
+        let mut event: libc::input_event = unsafe { mem::zeroed() };
+        event.type_ = kind;
+        event.code = code;
+        event.value = value;
+
+        let bytes = unsafe {
+            slice::from_raw_parts(
+                &event as *const libc::input_event as *const u8,
+                mem::size_of::<libc::input_event>(),
+            )
+        };
+
+        file.write_all(bytes).map_err(|err| err.to_string())
+    }
+
+    // 创建虚拟键盘设备
+    fn create_device() -> Result<File, String> {
+        let file = open().map_err(|err| err.to_string())?;
+
+        ioctl(&file, UI_SET_EVBIT, EV_KEY as _)?;
+
+        for key in KEYS {
+            ioctl(&file, UI_SET_KEYBIT, key as _)?;
+        }
+
+        let mut setup: libc::uinput_setup = unsafe { mem::zeroed() };
+        setup.id.bustype = BUS_VIRTUAL;
+        setup.id.vendor = 0xec0;
+        setup.id.product = 0x0001;
+
+        for (index, byte) in b"EcoPaste Virtual Keyboard".iter().enumerate() {
+            setup.name[index] = *byte as libc::c_char;
+        }
+
+        if unsafe { libc::ioctl(file.as_raw_fd(), UI_DEV_SETUP as _, &setup) } < 0 {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+
+        ioctl(&file, UI_DEV_CREATE, 0)?;
+
+        Ok(file)
+    }
+
+    // 通过虚拟设备依次发送按键事件
+    pub fn send(events: &[KeyEvent]) -> Result<(), String> {
+        let mut file = create_device()?;
+
+        // 等待合成器识别新的输入设备
+        wait(200);
+
+        let result = events.iter().try_for_each(|event| {
+            wait(event.wait);
+
+            emit(&mut file, EV_KEY, event.code, event.pressed as i32)?;
+            emit(&mut file, EV_SYN, SYN_REPORT, 0)
+        });
+
+        // 确保最后的事件被处理后再销毁设备
+        wait(50);
+
+        let _ = ioctl(&file, UI_DEV_DESTROY, 0);
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paste_events_release_shift_before_extra_keys() {
+        let events = paste_events(&[PasteKey::Enter], 50);
+
+        let sequence: Vec<(u16, bool)> = events
+            .iter()
+            .map(|event| (event.code, event.pressed))
+            .collect();
+
+        assert_eq!(
+            sequence,
+            [
+                (KEY_LEFTSHIFT, true),
+                (KEY_INSERT, true),
+                (KEY_INSERT, false),
+                (KEY_LEFTSHIFT, false),
+                (KEY_ENTER, true),
+                (KEY_ENTER, false),
+            ]
+        );
+        assert_eq!(events[4].wait, 50);
+    }
+
+    // 需要运行中的合成器，由 verify-wayland-paste.sh 在无头的 sway 中运行
+    #[test]
+    #[ignore]
+    fn sends_paste_through_virtual_keyboard() {
+        assert_eq!(detect_backend(), Some(KeyboardBackend::VirtualKeyboard));
+
+        send_paste(KeyboardBackend::VirtualKeyboard, &[PasteKey::Enter], 50).unwrap();
+    }
+}
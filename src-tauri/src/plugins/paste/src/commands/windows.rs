@@ -1,23 +1,30 @@
-use super::wait;
+use super::{
+    parse_key_sequence, wait, PasteError, PasteKey, PasteOutcome, DEFAULT_KEY_DELAY,
+    PASTE_INTO_INTERVAL,
+};
+use crate::focus::{focus_history, now_millis, push_focus_history, target_window, WindowInfo};
 use enigo::{
-    Direction::{Click, Press, Release},
+    Direction::{self, Click, Press, Release},
     Enigo, Key, Keyboard, Settings,
 };
 use std::ffi::OsString;
 use std::os::windows::ffi::OsStringExt;
+use std::path::Path;
 use std::ptr;
-use std::sync::Mutex;
 use tauri::command;
 use tauri_plugin_eco_window::MAIN_WINDOW_TITLE;
-use winapi::shared::minwindef::DWORD;
+use winapi::shared::minwindef::{BOOL, DWORD, FALSE, LPARAM, MAX_PATH, TRUE};
 use winapi::shared::windef::{HWINEVENTHOOK, HWND};
+use winapi::um::handleapi::CloseHandle;
+use winapi::um::processthreadsapi::OpenProcess;
+use winapi::um::winbase::QueryFullProcessImageNameW;
+use winapi::um::winnt::PROCESS_QUERY_LIMITED_INFORMATION;
 use winapi::um::winuser::{
-    GetWindowTextLengthW, GetWindowTextW, SetForegroundWindow, SetWinEventHook,
+    EnumWindows, GetClassNameW, GetForegroundWindow, GetWindowTextLengthW, GetWindowTextW,
+    GetWindowThreadProcessId, IsWindow, IsWindowVisible, SetForegroundWindow, SetWinEventHook,
     EVENT_SYSTEM_FOREGROUND, WINEVENT_OUTOFCONTEXT,
 };
 
-static PREVIOUS_WINDOW: Mutex<Option<isize>> = Mutex::new(None);
-
 // 获取窗口标题
 unsafe fn get_window_title(hwnd: HWND) -> String {
     let length = GetWindowTextLengthW(hwnd);
@@ -35,6 +42,69 @@ unsafe fn get_window_title(hwnd: HWND) -> String {
         .into_owned()
 }
 
+// 获取窗口类名
+unsafe fn get_class_name(hwnd: HWND) -> String {
+    let mut buffer: Vec<u16> = vec![0; 256];
+
+    let length = GetClassNameW(hwnd, buffer.as_mut_ptr(), buffer.len() as i32);
+
+    if length <= 0 {
+        return String::new();
+    }
+
+    OsString::from_wide(&buffer[..length as usize])
+        .to_string_lossy()
+        .into_owned()
+}
+
+// 根据进程 id 获取应用名称（可执行文件名）
+unsafe fn get_process_name(pid: DWORD) -> Option<String> {
+    let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, FALSE, pid);
+
+    if process.is_null() {
+        return None;
+    }
+
+    let mut buffer: Vec<u16> = vec![0; MAX_PATH];
+    let mut size = buffer.len() as DWORD;
+
+    let success = QueryFullProcessImageNameW(process, 0, buffer.as_mut_ptr(), &mut size);
+
+    CloseHandle(process);
+
+    if success == 0 {
+        return None;
+    }
+
+    let path = OsString::from_wide(&buffer[..size as usize]);
+
+    Path::new(&path)
+        .file_stem()
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
+// 收集窗口信息
+unsafe fn get_window_info(hwnd: HWND, title: String) -> WindowInfo {
+    let mut pid: DWORD = 0;
+
+    GetWindowThreadProcessId(hwnd, &mut pid);
+
+    let pid = (pid != 0).then_some(pid);
+    let class = get_class_name(hwnd);
+    let app_name = pid
+        .and_then(|pid| get_process_name(pid))
+        .unwrap_or_default();
+
+    WindowInfo {
+        id: hwnd as u64,
+        pid,
+        app_name,
+        title,
+        class,
+        timestamp: now_millis(),
+    }
+}
+
 // 定义事件钩子回调函数
 unsafe extern "system" fn event_hook_callback(
     _h_win_event_hook: HWINEVENTHOOK,
@@ -52,8 +122,7 @@ unsafe extern "system" fn event_hook_callback(
             return;
         }
 
-        let mut previous_window = PREVIOUS_WINDOW.lock().unwrap();
-        let _ = previous_window.insert(hwnd as isize);
+        push_focus_history(get_window_info(hwnd, window_title));
     }
 }
 
@@ -80,36 +149,207 @@ pub fn observe_app() {
 
 // 获取上一个窗口
 pub fn get_previous_window() -> Option<isize> {
-    return PREVIOUS_WINDOW.lock().unwrap().clone();
+    target_window().map(|window| window.id as isize)
 }
 
-// 聚焦上一个窗口
-fn focus_previous_window() {
+// 聚焦指定窗口，返回是否确认已获得焦点
+fn focus_window(hwnd: isize) -> Result<bool, PasteError> {
+    if (hwnd as HWND).is_null() {
+        return Err(PasteError::NoTargetWindow);
+    }
+
     unsafe {
-        let hwnd = match get_previous_window() {
-            Some(hwnd) => hwnd as HWND,
-            None => return,
-        };
+        if IsWindow(hwnd as HWND) == 0 {
+            return Err(PasteError::FocusFailed(format!(
+                "window {hwnd} no longer exists"
+            )));
+        }
 
-        if hwnd.is_null() {
-            return;
+        if SetForegroundWindow(hwnd as HWND) == 0 {
+            return Err(PasteError::FocusFailed(format!(
+                "SetForegroundWindow failed for {hwnd}"
+            )));
         }
 
-        SetForegroundWindow(hwnd);
+        Ok(GetForegroundWindow() as isize == hwnd)
+    }
+}
+
+// 聚焦上一个窗口，返回窗口以及是否确认已获得焦点
+// 没有上一个窗口或聚焦失败时不发送按键，避免粘贴到当前获得焦点的其它窗口
+fn focus_previous_window() -> Result<(isize, bool), PasteError> {
+    let hwnd = get_previous_window().ok_or(PasteError::NoTargetWindow)?;
+
+    Ok((hwnd, focus_window(hwnd)?))
+}
+
+// 枚举窗口的回调函数，收集可见且有标题的顶层窗口
+unsafe extern "system" fn enum_windows_callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
+    let windows = &mut *(lparam as *mut Vec<HWND>);
+
+    if IsWindowVisible(hwnd) != 0 && GetWindowTextLengthW(hwnd) > 0 {
+        windows.push(hwnd);
+    }
+
+    TRUE
+}
+
+// 获取可以粘贴的窗口列表
+pub fn get_window_list() -> Result<Vec<WindowInfo>, PasteError> {
+    let mut hwnds: Vec<HWND> = Vec::new();
+
+    unsafe {
+        EnumWindows(
+            Some(enum_windows_callback),
+            &mut hwnds as *mut Vec<HWND> as LPARAM,
+        );
+    }
+
+    let history = focus_history();
+
+    let windows = hwnds
+        .into_iter()
+        .filter_map(|hwnd| unsafe {
+            let title = get_window_title(hwnd);
+
+            if title == MAIN_WINDOW_TITLE {
+                return None;
+            }
+
+            let mut info = get_window_info(hwnd, title);
+
+            // 没有获得过焦点的窗口时间戳为 0
+            info.timestamp = history
+                .iter()
+                .find(|item| item.id == info.id)
+                .map(|item| item.timestamp)
+                .unwrap_or_default();
+
+            Some(info)
+        })
+        .collect();
+
+    Ok(windows)
+}
+
+// 转换为 enigo 的按键
+fn to_enigo_key(key: PasteKey) -> Key {
+    match key {
+        PasteKey::Enter => Key::Return,
+        PasteKey::Tab => Key::Tab,
+        PasteKey::Escape => Key::Escape,
+        PasteKey::Space => Key::Space,
+        PasteKey::Backspace => Key::Backspace,
+        PasteKey::Up => Key::UpArrow,
+        PasteKey::Down => Key::DownArrow,
+        PasteKey::Left => Key::LeftArrow,
+        PasteKey::Right => Key::RightArrow,
     }
 }
 
+// 模拟按键事件
+fn dispatch(enigo: &mut Enigo, key: Key, direction: Direction) -> Result<(), PasteError> {
+    enigo
+        .key(key, direction)
+        .map_err(|err| PasteError::KeystrokeFailed(err.to_string()))
+}
+
+// 粘贴后依次发送按键
+fn send_keys(enigo: &mut Enigo, keys: &[PasteKey], delay: u64) -> Result<(), PasteError> {
+    for key in keys {
+        wait(delay);
+
+        dispatch(enigo, to_enigo_key(*key), Click)?;
+    }
+
+    Ok(())
+}
+
+// 发送粘贴快捷键以及追加的按键
+fn send_paste(enigo: &mut Enigo, keys: &[PasteKey], delay: u64) -> Result<(), PasteError> {
+    dispatch(enigo, Key::Shift, Press)?;
+    // insert 的微软虚拟键码：https://learn.microsoft.com/en-us/windows/win32/inputdev/virtual-key-codes
+    let result = dispatch(enigo, Key::Other(0x2D), Click);
+    // 无论是否成功都要松开 shift，避免按键一直处于按下状态
+    let released = dispatch(enigo, Key::Shift, Release);
+
+    result.and(released)?;
+
+    send_keys(enigo, keys, delay)
+}
+
+// 创建模拟按键的实例
+fn create_enigo() -> Result<Enigo, PasteError> {
+    Enigo::new(&Settings::default()).map_err(|err| PasteError::KeystrokeFailed(err.to_string()))
+}
+
 // 粘贴
 #[command]
-pub async fn paste() {
-    let mut enigo = Enigo::new(&Settings::default()).unwrap();
+pub async fn paste(keys: Option<String>, delay: Option<u64>) -> Result<PasteOutcome, PasteError> {
+    let keys = parse_key_sequence(keys.as_deref())?;
 
-    focus_previous_window();
+    let mut enigo = create_enigo()?;
+
+    let (target, focused) = focus_previous_window()?;
 
     wait(100);
 
-    enigo.key(Key::Shift, Press).unwrap();
-    // insert 的微软虚拟键码：https://learn.microsoft.com/en-us/windows/win32/inputdev/virtual-key-codes
-    enigo.key(Key::Other(0x2D), Click).unwrap();
-    enigo.key(Key::Shift, Release).unwrap();
+    send_paste(&mut enigo, &keys, delay.unwrap_or(DEFAULT_KEY_DELAY))?;
+
+    Ok(PasteOutcome {
+        target: Some(target as u64),
+        focused,
+        keystroke: "Shift+Insert".to_string(),
+        backend: "enigo".to_string(),
+        keys,
+        error: None,
+    })
+}
+
+// 依次粘贴到指定的窗口
+#[command]
+pub async fn paste_into(
+    window_ids: Vec<u64>,
+    keys: Option<String>,
+    delay: Option<u64>,
+) -> Result<Vec<PasteOutcome>, PasteError> {
+    let keys = parse_key_sequence(keys.as_deref())?;
+
+    let mut enigo = create_enigo()?;
+
+    let mut outcomes = Vec::with_capacity(window_ids.len());
+
+    for (index, window) in window_ids.into_iter().enumerate() {
+        if index > 0 {
+            wait(PASTE_INTO_INTERVAL);
+        }
+
+        let outcome = focus_window(window as isize).and_then(|focused| {
+            wait(100);
+
+            send_paste(&mut enigo, &keys, delay.unwrap_or(DEFAULT_KEY_DELAY))?;
+
+            Ok(PasteOutcome {
+                target: Some(window),
+                focused,
+                keystroke: "Shift+Insert".to_string(),
+                backend: "enigo".to_string(),
+                keys: keys.clone(),
+                error: None,
+            })
+        });
+
+        // 某个窗口粘贴失败时记录原因，继续粘贴到其余的窗口
+        outcomes.push(outcome.unwrap_or_else(|err| {
+            PasteOutcome::failed(window, "Shift+Insert", "enigo", &keys, err)
+        }));
+    }
+
+    Ok(outcomes)
+}
+
+// 获取可以粘贴的窗口列表
+#[command]
+pub async fn list_windows() -> Result<Vec<WindowInfo>, PasteError> {
+    get_window_list()
 }
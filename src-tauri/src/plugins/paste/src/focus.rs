@@ -0,0 +1,58 @@
+use serde::Serialize;
+use std::{
+    collections::VecDeque,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+// 焦点历史最多保留的窗口数量
+pub const FOCUS_HISTORY_LIMIT: usize = 20;
+
+static FOCUS_HISTORY: Mutex<VecDeque<WindowInfo>> = Mutex::new(VecDeque::new());
+
+// 获得过焦点的窗口信息
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowInfo {
+    // 窗口标识：linux 为 X11 窗口 id，windows 为 HWND，macos 为进程 id
+    pub id: u64,
+    // 窗口所属的进程 id
+    pub pid: Option<u32>,
+    // 应用名称
+    pub app_name: String,
+    // 窗口标题
+    pub title: String,
+    // 窗口类名：linux 为 WM_CLASS，windows 为窗口类，macos 为 bundle id
+    pub class: String,
+    // 获得焦点的时间（毫秒时间戳）
+    pub timestamp: u64,
+}
+
+// 当前的毫秒时间戳
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+// 记录获得焦点的窗口，最近的窗口排在最前面
+pub fn push_focus_history(window: WindowInfo) {
+    let mut history = FOCUS_HISTORY.lock().unwrap();
+
+    history.retain(|item| item.id != window.id);
+
+    history.push_front(window);
+
+    history.truncate(FOCUS_HISTORY_LIMIT);
+}
+
+// 获取焦点历史
+pub fn focus_history() -> Vec<WindowInfo> {
+    FOCUS_HISTORY.lock().unwrap().iter().cloned().collect()
+}
+
+// 获取粘贴的目标窗口，即最近获得焦点的窗口
+pub fn target_window() -> Option<WindowInfo> {
+    FOCUS_HISTORY.lock().unwrap().front().cloned()
+}
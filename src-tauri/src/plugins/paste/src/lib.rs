@@ -5,8 +5,10 @@ use tauri::{
 };
 
 mod commands;
+mod focus;
 
 pub use commands::*;
+pub use focus::*;
 
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::new("eco-paste")
@@ -15,6 +17,12 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
 
             Ok(())
         })
-        .invoke_handler(generate_handler![commands::paste])
+        .invoke_handler(generate_handler![
+            commands::paste,
+            commands::get_focus_history,
+            commands::get_target_window,
+            commands::paste_into,
+            commands::list_windows
+        ])
         .build()
 }
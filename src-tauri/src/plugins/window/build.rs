@@ -1,4 +1,17 @@
-const COMMANDS: &[&str] = &["show_window", "hide_window", "show_taskbar_icon"];
+const COMMANDS: &[&str] = &[
+    "show_window",
+    "hide_window",
+    "show_taskbar_icon",
+    "set_window_position",
+    "pin_sticky",
+    "unpin_sticky",
+    "set_sticky_opacity",
+    "get_sticky",
+    "open_preview",
+    "close_preview",
+    "toggle_preview",
+    "update_preview",
+];
 
 fn main() {
     tauri_plugin::Builder::new(COMMANDS).build();
@@ -1,5 +1,9 @@
 use super::{is_main_window, shared_hide_window, shared_show_window};
-use crate::MAIN_WINDOW_LABEL;
+use crate::{
+    position::{place_main_window, remember_main_window},
+    preview::close_preview_window,
+    MAIN_WINDOW_LABEL,
+};
 use tauri::{command, AppHandle, Runtime, WebviewWindow};
 use tauri_nspanel::ManagerExt;
 
@@ -13,6 +17,8 @@ pub enum MacOSPanelStatus {
 #[command]
 pub async fn show_window<R: Runtime>(app_handle: AppHandle<R>, window: WebviewWindow<R>) {
     if is_main_window(&window) {
+        place_main_window(&window);
+
         set_macos_panel(&app_handle, &window, MacOSPanelStatus::Show);
     } else {
         shared_show_window(&window);
@@ -23,6 +29,10 @@ pub async fn show_window<R: Runtime>(app_handle: AppHandle<R>, window: WebviewWi
 #[command]
 pub async fn hide_window<R: Runtime>(app_handle: AppHandle<R>, window: WebviewWindow<R>) {
     if is_main_window(&window) {
+        remember_main_window(&window);
+
+        close_preview_window(&app_handle);
+
         set_macos_panel(&app_handle, &window, MacOSPanelStatus::Hide);
     } else {
         shared_hide_window(&window);
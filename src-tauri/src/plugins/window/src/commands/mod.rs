@@ -1,4 +1,14 @@
-use tauri::{async_runtime::spawn, AppHandle, Manager, Runtime, WebviewWindow};
+use crate::{
+    position::{remember_main_window, WindowPosition, WindowPositions},
+    preview::{
+        close_preview_window, open_preview_window, toggle_preview_window, update_preview_window,
+    },
+    sticky::{
+        pin_sticky_window, set_sticky_window_opacity, unpin_sticky_window, StickyWindow,
+        StickyWindows,
+    },
+};
+use tauri::{async_runtime::spawn, command, AppHandle, Manager, Runtime, State, WebviewWindow};
 
 // 主窗口的label
 pub static MAIN_WINDOW_LABEL: &str = "main";
@@ -33,9 +43,90 @@ fn shared_show_window<R: Runtime>(window: &WebviewWindow<R>) {
 
 // 共享隐藏窗口的方法
 fn shared_hide_window<R: Runtime>(window: &WebviewWindow<R>) {
+    remember_main_window(window);
+
+    if is_main_window(window) {
+        close_preview_window(window.app_handle());
+    }
+
     let _ = window.hide();
 }
 
+// 设置主窗口显示时的位置，不设置时保持窗口当前的位置
+#[command]
+pub async fn set_window_position(
+    positions: State<'_, WindowPositions>,
+    position: Option<WindowPosition>,
+) -> Result<(), String> {
+    positions.set(position);
+
+    Ok(())
+}
+
+// 将历史记录钉在屏幕上
+#[command]
+pub async fn pin_sticky<R: Runtime>(app_handle: AppHandle<R>, id: String) -> Result<(), String> {
+    pin_sticky_window(&app_handle, &id)
+}
+
+// 取消钉住历史记录，并关闭对应的窗口
+#[command]
+pub async fn unpin_sticky<R: Runtime>(
+    app_handle: AppHandle<R>,
+    id: String,
+) -> Result<bool, String> {
+    unpin_sticky_window(&app_handle, &id)
+}
+
+// 设置钉住窗口的不透明度
+#[command]
+pub async fn set_sticky_opacity<R: Runtime>(
+    app_handle: AppHandle<R>,
+    id: String,
+    opacity: f64,
+) -> Result<(), String> {
+    set_sticky_window_opacity(&app_handle, &id, opacity)
+}
+
+// 获取钉住的记录，没有钉住时返回空
+#[command]
+pub async fn get_sticky(
+    stickies: State<'_, StickyWindows>,
+    id: String,
+) -> Result<Option<StickyWindow>, String> {
+    Ok(stickies.get(&id))
+}
+
+// 打开预览窗口并预览指定的记录
+#[command]
+pub async fn open_preview<R: Runtime>(app_handle: AppHandle<R>, id: String) -> Result<(), String> {
+    open_preview_window(&app_handle, &id)
+}
+
+// 关闭预览窗口
+#[command]
+pub async fn close_preview<R: Runtime>(app_handle: AppHandle<R>) {
+    close_preview_window(&app_handle);
+}
+
+// 切换预览窗口的显示和隐藏，返回预览窗口是否打开
+#[command]
+pub async fn toggle_preview<R: Runtime>(
+    app_handle: AppHandle<R>,
+    id: String,
+) -> Result<bool, String> {
+    toggle_preview_window(&app_handle, &id)
+}
+
+// 预览窗口打开时切换预览的记录
+#[command]
+pub async fn update_preview<R: Runtime>(
+    app_handle: AppHandle<R>,
+    id: String,
+) -> Result<(), String> {
+    update_preview_window(&app_handle, &id)
+}
+
 // 显示主窗口
 pub fn show_main_window(app_handle: &AppHandle) {
     show_window_by_label(app_handle, MAIN_WINDOW_LABEL);
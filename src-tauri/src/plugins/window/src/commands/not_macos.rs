@@ -1,9 +1,12 @@
 use super::{shared_hide_window, shared_show_window};
+use crate::position::place_main_window;
 use tauri::{command, AppHandle, Runtime, WebviewWindow};
 
 // 显示窗口
 #[command]
 pub async fn show_window<R: Runtime>(_app_handle: AppHandle<R>, window: WebviewWindow<R>) {
+    place_main_window(&window);
+
     shared_show_window(&window);
 }
 
@@ -0,0 +1,162 @@
+use crate::geometry::{persists_geometry, restore_geometry, save_geometry, WindowGeometry};
+use std::{
+    collections::HashMap,
+    sync::{
+        mpsc::{channel, Receiver, RecvTimeoutError, Sender},
+        Mutex,
+    },
+    thread,
+    time::Duration,
+};
+use tauri::{AppHandle, Emitter, EventTarget, Manager, Runtime, Window, WindowEvent};
+
+// 各个平台统一发送给窗口的事件
+pub const WINDOW_FOCUS_EVENT: &str = "plugin:eco-window://focus";
+pub const WINDOW_BLUR_EVENT: &str = "plugin:eco-window://blur";
+pub const WINDOW_MOVED_EVENT: &str = "plugin:eco-window://move";
+pub const WINDOW_RESIZED_EVENT: &str = "plugin:eco-window://resize";
+
+// 移动或调整大小停止后，等待多久再保存窗口状态
+const SAVE_GEOMETRY_DELAY: Duration = Duration::from_millis(500);
+
+// 窗口事件的状态
+#[derive(Default)]
+pub struct WindowEvents {
+    // 每个窗口最后一次发送的焦点状态，用于去掉重复的焦点事件
+    focused: Mutex<HashMap<String, bool>>,
+    // 每个窗口的保存线程，位置或大小变化时通知对应的线程
+    saves: Mutex<HashMap<String, Sender<()>>>,
+}
+
+// 发送获得或失去焦点的事件，焦点状态没有变化时不发送
+pub fn emit_focus<R: Runtime>(app_handle: &AppHandle<R>, label: &str, focused: bool) {
+    let events = app_handle.state::<WindowEvents>();
+
+    let previous = events
+        .focused
+        .lock()
+        .unwrap()
+        .insert(label.to_string(), focused);
+
+    if previous == Some(focused) {
+        return;
+    }
+
+    let event = if focused {
+        WINDOW_FOCUS_EVENT
+    } else {
+        WINDOW_BLUR_EVENT
+    };
+
+    let _ = app_handle.emit_to(EventTarget::labeled(label), event, focused);
+}
+
+// 发送窗口移动的事件，载荷为外框的物理位置
+pub fn emit_moved<R: Runtime>(app_handle: &AppHandle<R>, label: &str) {
+    let Some(window) = app_handle.get_webview_window(label) else {
+        return;
+    };
+
+    if let Ok(position) = window.outer_position() {
+        let _ = app_handle.emit_to(EventTarget::labeled(label), WINDOW_MOVED_EVENT, position);
+    }
+
+    schedule_save(app_handle, label);
+}
+
+// 发送窗口大小改变的事件，载荷为内容区域的物理大小，调整大小时位置也可能改变
+pub fn emit_resized<R: Runtime>(app_handle: &AppHandle<R>, label: &str) {
+    emit_moved(app_handle, label);
+
+    let Some(window) = app_handle.get_webview_window(label) else {
+        return;
+    };
+
+    if let Ok(size) = window.inner_size() {
+        let _ = app_handle.emit_to(EventTarget::labeled(label), WINDOW_RESIZED_EVENT, size);
+    }
+}
+
+// 通知窗口的保存线程，第一次变化时创建线程
+fn schedule_save<R: Runtime>(app_handle: &AppHandle<R>, label: &str) {
+    if !persists_geometry(label) {
+        return;
+    }
+
+    let events = app_handle.state::<WindowEvents>();
+    let mut saves = events.saves.lock().unwrap();
+
+    if let Some(sender) = saves.get(label) {
+        if sender.send(()).is_ok() {
+            return;
+        }
+    }
+
+    let (sender, receiver) = channel();
+
+    let _ = sender.send(());
+
+    saves.insert(label.to_string(), sender);
+
+    let app_handle = app_handle.clone();
+    let label = label.to_string();
+
+    thread::spawn(move || save_when_settled(app_handle, label, receiver));
+}
+
+// 窗口停止移动或调整大小后保存位置和大小，窗口销毁后线程退出
+fn save_when_settled<R: Runtime>(app_handle: AppHandle<R>, label: String, receiver: Receiver<()>) {
+    while receiver.recv().is_ok() {
+        // 等待期间又发生了变化时重新计时
+        loop {
+            match receiver.recv_timeout(SAVE_GEOMETRY_DELAY) {
+                Ok(()) => continue,
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+
+        let Some(geometry) = app_handle
+            .get_webview_window(&label)
+            .and_then(|window| WindowGeometry::from_window(&window))
+        else {
+            continue;
+        };
+
+        if let Err(err) = save_geometry(&app_handle, &label, geometry) {
+            log::warn!("Failed to save window state of {}: {}", label, err);
+        }
+    }
+}
+
+// 窗口销毁后清理它的状态，并结束保存线程
+fn forget_window<R: Runtime>(app_handle: &AppHandle<R>, label: &str) {
+    let events = app_handle.state::<WindowEvents>();
+
+    events.focused.lock().unwrap().remove(label);
+    events.saves.lock().unwrap().remove(label);
+}
+
+// 将 tao 的窗口事件转换为统一的事件，macos 的主窗口为 ns_panel，由面板的委托发送
+pub fn listen_window_events<R: Runtime>(window: &Window<R>) {
+    // 窗口创建后先恢复保存的位置和大小
+    if persists_geometry(window.label()) {
+        restore_geometry(window);
+    }
+
+    #[cfg(target_os = "macos")]
+    if window.label() == crate::MAIN_WINDOW_LABEL {
+        return;
+    }
+
+    let app_handle = window.app_handle().clone();
+    let label = window.label().to_string();
+
+    window.on_window_event(move |event| match event {
+        WindowEvent::Focused(focused) => emit_focus(&app_handle, &label, *focused),
+        WindowEvent::Moved(_) => emit_moved(&app_handle, &label),
+        WindowEvent::Resized(_) => emit_resized(&app_handle, &label),
+        WindowEvent::Destroyed => forget_window(&app_handle, &label),
+        _ => {}
+    });
+}
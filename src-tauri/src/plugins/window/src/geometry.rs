@@ -0,0 +1,381 @@
+use crate::{is_sticky_window, MAIN_WINDOW_LABEL, PREFERENCE_WINDOW_LABEL};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, fs, path::PathBuf, sync::Mutex};
+use tauri::{
+    AppHandle, Manager, Monitor, PhysicalPosition, PhysicalSize, Runtime, WebviewWindow, Window,
+};
+
+// 窗口状态的文件名，保存在应用的配置目录中
+#[cfg(debug_assertions)]
+const WINDOW_STATE_FILE: &str = "window-state.dev.json";
+
+#[cfg(not(debug_assertions))]
+const WINDOW_STATE_FILE: &str = "window-state.json";
+
+// 旧版前端保存的窗口状态，在应用的数据目录中，启动时导入后删除
+#[cfg(debug_assertions)]
+const LEGACY_WINDOW_STATE_FILE: &str = ".window-state.dev.json";
+
+#[cfg(not(debug_assertions))]
+const LEGACY_WINDOW_STATE_FILE: &str = ".window-state.json";
+
+// 恢复位置时，窗口在显示器工作区内至少要露出的大小
+const MIN_VISIBLE_SIZE: i32 = 50;
+
+// 窗口的位置和大小（物理像素），位置为外框的位置，大小为内容区域的大小
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl WindowGeometry {
+    // 读取窗口当前的位置和大小，最小化时返回空
+    pub fn from_window<R: Runtime>(window: &WebviewWindow<R>) -> Option<Self> {
+        if window.is_minimized().unwrap_or_default() {
+            return None;
+        }
+
+        let position = window.outer_position().ok()?;
+        let size = window.inner_size().ok()?;
+
+        Some(Self {
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+        })
+    }
+
+    // 窗口是否有足够的部分在显示器的工作区内
+    fn is_visible_on(&self, monitor: &Monitor) -> bool {
+        let area = monitor.work_area();
+
+        let left = self.x.max(area.position.x);
+        let top = self.y.max(area.position.y);
+        let right = (self.x + self.width as i32).min(area.position.x + area.size.width as i32);
+        let bottom = (self.y + self.height as i32).min(area.position.y + area.size.height as i32);
+
+        right - left >= MIN_VISIBLE_SIZE && bottom - top >= MIN_VISIBLE_SIZE
+    }
+
+    // 是否能放进显示器的工作区
+    fn fits_in(&self, monitor: &Monitor) -> bool {
+        let area = monitor.work_area();
+
+        self.width <= area.size.width && self.height <= area.size.height
+    }
+}
+
+// 是否保存和恢复窗口的状态：主窗口、偏好设置窗口和钉住的窗口，预览等临时窗口由调用方定位
+pub fn persists_geometry(label: &str) -> bool {
+    label == MAIN_WINDOW_LABEL || label == PREFERENCE_WINDOW_LABEL || is_sticky_window(label)
+}
+
+// 按显示器布局保存的窗口状态：布局的哈希 -> 窗口的 label -> 位置和大小
+type WindowGeometries = HashMap<String, HashMap<String, WindowGeometry>>;
+
+// 旧版前端保存的窗口状态，字段可能不完整
+#[derive(Deserialize)]
+struct LegacyWindowGeometry {
+    x: Option<i32>,
+    y: Option<i32>,
+    width: Option<u32>,
+    height: Option<u32>,
+}
+
+// 解析旧版前端保存的状态，只保留字段完整且需要保存状态的窗口
+fn parse_legacy(content: &str) -> Vec<(String, WindowGeometry)> {
+    let legacy: HashMap<String, LegacyWindowGeometry> =
+        serde_json::from_str(content).unwrap_or_default();
+
+    legacy
+        .into_iter()
+        .filter(|(label, _)| persists_geometry(label))
+        .filter_map(|(label, geometry)| {
+            Some((
+                label,
+                WindowGeometry {
+                    x: geometry.x?,
+                    y: geometry.y?,
+                    width: geometry.width?,
+                    height: geometry.height?,
+                },
+            ))
+        })
+        .collect()
+}
+
+// 保存的窗口状态，在内存中读写，修改后写入文件
+pub struct WindowGeometryStore {
+    path: Option<PathBuf>,
+    geometries: Mutex<WindowGeometries>,
+}
+
+impl WindowGeometryStore {
+    pub fn load<R: Runtime>(app_handle: &AppHandle<R>) -> Self {
+        let path = app_handle
+            .path()
+            .app_config_dir()
+            .ok()
+            .map(|dir| dir.join(WINDOW_STATE_FILE));
+
+        let geometries = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        let store = Self {
+            path,
+            geometries: Mutex::new(geometries),
+        };
+
+        store.migrate_legacy(app_handle);
+
+        store
+    }
+
+    // 把旧版前端保存的状态导入到当前的显示器布局下，已有的状态优先
+    fn migrate_legacy<R: Runtime>(&self, app_handle: &AppHandle<R>) {
+        let Some(path) = app_handle
+            .path()
+            .app_data_dir()
+            .ok()
+            .map(|dir| dir.join(LEGACY_WINDOW_STATE_FILE))
+        else {
+            return;
+        };
+
+        // 文件不存在时已经迁移过，或者从未保存过
+        let Ok(content) = fs::read_to_string(&path) else {
+            return;
+        };
+
+        let imported = parse_legacy(&content);
+
+        if !imported.is_empty() {
+            let mut geometries = self.geometries.lock().unwrap();
+
+            let layout = geometries
+                .entry(monitor_layout_hash(app_handle))
+                .or_default();
+
+            for (label, geometry) in imported {
+                layout.entry(label).or_insert(geometry);
+            }
+
+            // 保存失败时保留旧文件，下次启动再导入
+            if let Err(err) = self.save(&geometries) {
+                log::warn!("Failed to migrate window state: {}", err);
+
+                return;
+            }
+        }
+
+        // 导入后或者文件无法解析时删除，不再需要旧文件
+        if let Err(err) = fs::remove_file(&path) {
+            log::warn!("Failed to remove legacy window state: {}", err);
+        }
+    }
+
+    // 窗口在指定布局下保存的状态
+    fn get(&self, layout: &str, label: &str) -> Option<WindowGeometry> {
+        self.geometries
+            .lock()
+            .unwrap()
+            .get(layout)?
+            .get(label)
+            .copied()
+    }
+
+    fn snapshot(&self) -> WindowGeometries {
+        self.geometries.lock().unwrap().clone()
+    }
+
+    // 保存窗口在指定布局下的状态
+    fn insert(&self, layout: String, label: &str, geometry: WindowGeometry) -> Result<(), String> {
+        let mut geometries = self.geometries.lock().unwrap();
+
+        let layout = geometries.entry(layout).or_default();
+
+        if layout.get(label) == Some(&geometry) {
+            return Ok(());
+        }
+
+        layout.insert(label.to_string(), geometry);
+
+        self.save(&geometries)
+    }
+
+    // 只保留满足条件的窗口的状态，例如去掉已经取消钉住的窗口
+    pub fn retain(&self, keep: impl Fn(&str) -> bool) -> Result<(), String> {
+        let mut geometries = self.geometries.lock().unwrap();
+
+        let count =
+            |geometries: &WindowGeometries| geometries.values().map(HashMap::len).sum::<usize>();
+
+        let len = count(&geometries);
+
+        geometries.values_mut().for_each(|layout| {
+            layout.retain(|label, _| keep(label));
+        });
+        geometries.retain(|_, layout| !layout.is_empty());
+
+        if count(&geometries) == len {
+            return Ok(());
+        }
+
+        self.save(&geometries)
+    }
+
+    // 删除窗口在所有布局下的状态
+    pub fn remove(&self, label: &str) -> Result<(), String> {
+        self.retain(|saved| saved != label)
+    }
+
+    // 在持有锁时写入，避免并发的保存互相覆盖
+    fn save(&self, geometries: &WindowGeometries) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+        }
+
+        let content = serde_json::to_string_pretty(geometries).map_err(|err| err.to_string())?;
+
+        fs::write(path, content).map_err(|err| err.to_string())
+    }
+}
+
+// 当前所有显示器的布局的哈希，显示器的增减、分辨率、排列或缩放比例变化时都会改变
+pub fn monitor_layout_hash<R: Runtime>(app_handle: &AppHandle<R>) -> String {
+    let mut monitors: Vec<_> = app_handle
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|monitor| {
+            let position = monitor.position();
+            let size = monitor.size();
+
+            (
+                monitor.name().cloned().unwrap_or_default(),
+                position.x,
+                position.y,
+                size.width,
+                size.height,
+                monitor.scale_factor().to_bits(),
+            )
+        })
+        .collect();
+
+    // 不依赖系统返回显示器的顺序
+    monitors.sort();
+
+    // 使用稳定的哈希，升级 rust 后保存的布局仍然有效
+    let content = serde_json::to_vec(&monitors).unwrap_or_default();
+
+    Sha256::digest(content)[..8]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+// 在当前的显示器布局下保存窗口的状态
+pub fn save_geometry<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    label: &str,
+    geometry: WindowGeometry,
+) -> Result<(), String> {
+    app_handle
+        .try_state::<WindowGeometryStore>()
+        .ok_or_else(|| "window state is not loaded".to_string())?
+        .insert(monitor_layout_hash(app_handle), label, geometry)
+}
+
+// 当前的显示器布局下保存的窗口状态
+pub fn saved_geometry<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    label: &str,
+) -> Option<WindowGeometry> {
+    app_handle
+        .try_state::<WindowGeometryStore>()?
+        .get(&monitor_layout_hash(app_handle), label)
+}
+
+// 恢复窗口的状态，优先使用当前显示器布局下保存的状态
+pub fn restore_geometry<R: Runtime>(window: &Window<R>) {
+    let app_handle = window.app_handle();
+    let label = window.label();
+
+    let Some(store) = app_handle.try_state::<WindowGeometryStore>() else {
+        return;
+    };
+
+    let geometries = store.snapshot();
+    let monitors = app_handle.available_monitors().unwrap_or_default();
+
+    if let Some(geometry) = geometries
+        .get(&monitor_layout_hash(app_handle))
+        .and_then(|layout| layout.get(label))
+    {
+        // 布局相同时显示器也可能被替换为同样规格的其它显示器，仍然检查是否可见
+        if monitors
+            .iter()
+            .any(|monitor| geometry.is_visible_on(monitor))
+        {
+            let _ = window.set_size(PhysicalSize::new(geometry.width, geometry.height));
+            let _ = window.set_position(PhysicalPosition::new(geometry.x, geometry.y));
+
+            return;
+        }
+    }
+
+    // 保存时的显示器已经不在时，只恢复在其它布局下保存的大小，位置保持默认
+    let size = geometries
+        .values()
+        .filter_map(|layout| layout.get(label))
+        .find(|geometry| monitors.iter().any(|monitor| geometry.fits_in(monitor)));
+
+    if let Some(geometry) = size {
+        let _ = window.set_size(PhysicalSize::new(geometry.width, geometry.height));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_legacy_window_state() {
+        let content = r#"{
+            "main": { "x": 10, "y": 20, "width": 360, "height": 600 },
+            "preference": { "x": 30, "y": 40 },
+            "preview": { "x": 0, "y": 0, "width": 100, "height": 100 }
+        }"#;
+
+        assert_eq!(
+            parse_legacy(content),
+            vec![(
+                MAIN_WINDOW_LABEL.to_string(),
+                WindowGeometry {
+                    x: 10,
+                    y: 20,
+                    width: 360,
+                    height: 600,
+                },
+            )]
+        );
+    }
+
+    #[test]
+    fn ignores_invalid_legacy_window_state() {
+        assert!(parse_legacy("").is_empty());
+        assert!(parse_legacy("[]").is_empty());
+    }
+}
@@ -1,19 +1,61 @@
 use tauri::{
     generate_handler,
     plugin::{Builder, TauriPlugin},
-    Runtime,
+    Manager, Runtime,
 };
 
 mod commands;
+mod events;
+mod geometry;
+mod position;
+mod preview;
+mod sticky;
 
 pub use commands::*;
+pub use events::*;
+pub use geometry::*;
+pub use position::*;
+pub use preview::*;
+pub use sticky::*;
 
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::new("eco-window")
         .invoke_handler(generate_handler![
             commands::show_window,
             commands::hide_window,
-            commands::show_taskbar_icon
+            commands::show_taskbar_icon,
+            commands::set_window_position,
+            commands::pin_sticky,
+            commands::unpin_sticky,
+            commands::set_sticky_opacity,
+            commands::get_sticky,
+            commands::open_preview,
+            commands::close_preview,
+            commands::toggle_preview,
+            commands::update_preview
         ])
+        .setup(|app, _api| {
+            app.manage(WindowPositions::default());
+            app.manage(WindowEvents::default());
+            app.manage(StickyWindows::load(app));
+
+            // 清理已经取消钉住的窗口和不再保存状态的窗口
+            let geometries = WindowGeometryStore::load(app);
+
+            if let Err(err) = geometries.retain(|label| {
+                persists_geometry(label)
+                    && (!is_sticky_window(label) || is_pinned_window(app.app_handle(), label))
+            }) {
+                log::warn!("Failed to prune window state: {}", err);
+            }
+
+            app.manage(geometries);
+            app.manage(PreviewState::default());
+
+            restore_sticky_windows(app);
+
+            Ok(())
+        })
+        .on_window_ready(|window| listen_window_events(&window))
         .build()
 }
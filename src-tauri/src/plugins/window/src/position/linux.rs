@@ -0,0 +1,157 @@
+use std::{
+    mem::zeroed,
+    os::raw::c_int,
+    ptr::null_mut,
+    sync::{Mutex, OnceLock},
+};
+use tauri::{PhysicalPosition, PhysicalRect, PhysicalSize};
+use x11::xlib::{
+    self, Atom, Display, XCloseDisplay, XDefaultRootWindow, XErrorEvent, XFree, XGetInputFocus,
+    XGetWindowAttributes, XGetWindowProperty, XInternAtom, XOpenDisplay, XSetErrorHandler, XSync,
+    XTranslateCoordinates, XWindowAttributes, XA_WINDOW,
+};
+
+// 本模块打开的 X 连接，其它连接的错误仍然交给之前的错误处理函数
+static DISPLAYS: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+// XSetErrorHandler 接收和返回的错误处理函数
+type XErrorHandler = Option<unsafe extern "C" fn(*mut Display, *mut XErrorEvent) -> c_int>;
+
+// 安装错误处理函数前的错误处理函数
+static PREVIOUS_ERROR_HANDLER: OnceLock<XErrorHandler> = OnceLock::new();
+
+// 激活的窗口可能在查询过程中被销毁，xlib 默认的错误处理函数收到 BadWindow 时会退出进程
+unsafe extern "C" fn handle_x_error(display: *mut Display, event: *mut XErrorEvent) -> c_int {
+    let owned = DISPLAYS
+        .lock()
+        .is_ok_and(|displays| displays.contains(&(display as usize)));
+
+    if owned {
+        let event = &*event;
+
+        log::warn!(
+            "X error {} on request {} for resource {}",
+            event.error_code,
+            event.request_code,
+            event.resourceid
+        );
+
+        return 0;
+    }
+
+    match PREVIOUS_ERROR_HANDLER.get().copied().flatten() {
+        Some(handler) => handler(display, event),
+        None => 0,
+    }
+}
+
+// 打开与 X 服务的连接，连接上的错误只记录日志，不会退出进程
+fn open_display() -> Option<*mut Display> {
+    PREVIOUS_ERROR_HANDLER.get_or_init(|| unsafe { XSetErrorHandler(Some(handle_x_error)) });
+
+    let display = unsafe { XOpenDisplay(std::ptr::null()) };
+
+    if display.is_null() {
+        return None;
+    }
+
+    DISPLAYS.lock().unwrap().push(display as usize);
+
+    Some(display)
+}
+
+// 关闭连接，让之前请求的错误在连接关闭前由错误处理函数处理
+fn close_display(display: *mut Display) {
+    unsafe {
+        XSync(display, xlib::False);
+        XCloseDisplay(display);
+    }
+
+    DISPLAYS
+        .lock()
+        .unwrap()
+        .retain(|owned| *owned != display as usize);
+}
+
+// X11 没有通用的输入光标位置接口，跟随鼠标
+pub fn caret_position() -> Option<PhysicalPosition<i32>> {
+    None
+}
+
+// 获取当前激活的窗口，优先使用 _NET_ACTIVE_WINDOW
+unsafe fn active_window(display: *mut Display) -> Option<u64> {
+    let root = XDefaultRootWindow(display);
+    let atom = XInternAtom(display, c"_NET_ACTIVE_WINDOW".as_ptr(), xlib::False);
+
+    let mut actual_type: Atom = 0;
+    let mut actual_format: i32 = 0;
+    let mut nitems: u64 = 0;
+    let mut bytes_after: u64 = 0;
+    let mut prop: *mut u8 = null_mut();
+
+    let result = XGetWindowProperty(
+        display,
+        root,
+        atom,
+        0,
+        1,
+        xlib::False,
+        XA_WINDOW,
+        &mut actual_type,
+        &mut actual_format,
+        &mut nitems,
+        &mut bytes_after,
+        &mut prop,
+    );
+
+    let mut window = 0;
+
+    if result == xlib::Success as i32 && !prop.is_null() {
+        if actual_format == 32 && nitems > 0 {
+            window = *(prop as *const std::os::raw::c_ulong);
+        }
+
+        XFree(prop as *mut _);
+    }
+
+    if window == 0 {
+        let mut revert_to = 0;
+
+        XGetInputFocus(display, &mut window, &mut revert_to);
+    }
+
+    (window > 1 && window != root).then_some(window)
+}
+
+// 当前激活窗口在屏幕上的位置和大小
+pub fn focused_window_rect() -> Option<PhysicalRect<i32, u32>> {
+    let display = open_display()?;
+
+    unsafe {
+        let rect = (|| {
+            let window = active_window(display)?;
+            let root = XDefaultRootWindow(display);
+
+            let mut attributes: XWindowAttributes = zeroed();
+
+            if XGetWindowAttributes(display, window, &mut attributes) == 0 {
+                return None;
+            }
+
+            let (mut x, mut y, mut child) = (0, 0, 0);
+
+            if XTranslateCoordinates(display, window, root, 0, 0, &mut x, &mut y, &mut child) == 0 {
+                return None;
+            }
+
+            Some(PhysicalRect {
+                position: PhysicalPosition::new(x, y),
+                size: PhysicalSize::new(attributes.width as u32, attributes.height as u32),
+            })
+        })();
+
+        close_display(display);
+
+        rect
+    }
+}
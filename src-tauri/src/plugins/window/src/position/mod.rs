@@ -0,0 +1,215 @@
+use crate::{
+    geometry::{save_geometry, saved_geometry, WindowGeometry},
+    is_main_window, MAIN_WINDOW_LABEL,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tauri::{
+    Manager, Monitor, PhysicalPosition, PhysicalRect, PhysicalSize, Runtime, WebviewWindow,
+};
+
+#[cfg(target_os = "linux")]
+mod linux;
+
+#[cfg(target_os = "windows")]
+mod windows;
+
+#[cfg(target_os = "linux")]
+use linux::*;
+
+#[cfg(target_os = "windows")]
+use windows::*;
+
+// 主窗口显示时的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WindowPosition {
+    // 记住上次的位置，鼠标在其它显示器上时显示在该显示器的中心
+    Remember,
+    // 跟随鼠标
+    Follow,
+    // 跟随输入光标，无法获取时跟随鼠标
+    Caret,
+    // 在当前激活窗口所在显示器的中心
+    Center,
+}
+
+// 主窗口的位置设置，记住的位置保存在 WindowGeometryStore 中
+#[derive(Default)]
+pub struct WindowPositions {
+    // 未设置时不调整位置，例如停靠模式由前端设置位置
+    position: Mutex<Option<WindowPosition>>,
+}
+
+impl WindowPositions {
+    pub fn set(&self, position: Option<WindowPosition>) {
+        *self.position.lock().unwrap() = position;
+    }
+
+    pub fn get(&self) -> Option<WindowPosition> {
+        *self.position.lock().unwrap()
+    }
+}
+
+// 包含指定坐标的显示器
+fn monitor_at<R: Runtime>(window: &WebviewWindow<R>, x: i32, y: i32) -> Option<Monitor> {
+    window.monitor_from_point(x as f64, y as f64).ok().flatten()
+}
+
+fn cursor_position<R: Runtime>(window: &WebviewWindow<R>) -> Option<PhysicalPosition<i32>> {
+    let position = window.cursor_position().ok()?;
+
+    Some(PhysicalPosition::new(position.x as i32, position.y as i32))
+}
+
+// 鼠标所在的显示器，获取不到时使用主显示器
+fn cursor_monitor<R: Runtime>(window: &WebviewWindow<R>) -> Option<Monitor> {
+    cursor_position(window)
+        .and_then(|position| monitor_at(window, position.x, position.y))
+        .or_else(|| window.primary_monitor().ok().flatten())
+}
+
+// 当前激活窗口所在的显示器，获取不到时使用鼠标所在的显示器
+fn focused_monitor<R: Runtime>(window: &WebviewWindow<R>) -> Option<Monitor> {
+    focused_window_rect()
+        .and_then(|rect| {
+            let x = rect.position.x + rect.size.width as i32 / 2;
+            let y = rect.position.y + rect.size.height as i32 / 2;
+
+            monitor_at(window, x, y)
+        })
+        .or_else(|| cursor_monitor(window))
+}
+
+// 窗口在目标显示器上的物理大小，窗口在缩放比例不同的显示器之间移动时大小会变化
+fn window_size<R: Runtime>(
+    window: &WebviewWindow<R>,
+    monitor: &Monitor,
+) -> Option<PhysicalSize<u32>> {
+    let size = window.outer_size().ok()?;
+    let scale_factor = window.scale_factor().ok()?;
+
+    Some(
+        size.to_logical::<f64>(scale_factor)
+            .to_physical(monitor.scale_factor()),
+    )
+}
+
+// 将窗口限制在显示器的工作区内
+pub(crate) fn clamp(
+    position: PhysicalPosition<i32>,
+    size: PhysicalSize<u32>,
+    work_area: &PhysicalRect<i32, u32>,
+) -> PhysicalPosition<i32> {
+    let left = work_area.position.x;
+    let top = work_area.position.y;
+    let right = left + work_area.size.width as i32 - size.width as i32;
+    let bottom = top + work_area.size.height as i32 - size.height as i32;
+
+    // 窗口比工作区大时对齐左上角
+    PhysicalPosition::new(
+        position.x.min(right).max(left),
+        position.y.min(bottom).max(top),
+    )
+}
+
+// 工作区的中心
+fn center(size: PhysicalSize<u32>, work_area: &PhysicalRect<i32, u32>) -> PhysicalPosition<i32> {
+    PhysicalPosition::new(
+        work_area.position.x + (work_area.size.width as i32 - size.width as i32) / 2,
+        work_area.position.y + (work_area.size.height as i32 - size.height as i32) / 2,
+    )
+}
+
+// 计算主窗口显示的显示器和位置
+fn resolve<R: Runtime>(
+    window: &WebviewWindow<R>,
+    position: WindowPosition,
+) -> Option<(Monitor, PhysicalPosition<i32>)> {
+    match position {
+        WindowPosition::Follow => {
+            let cursor = cursor_position(window)?;
+
+            Some((monitor_at(window, cursor.x, cursor.y)?, cursor))
+        }
+        WindowPosition::Caret => match caret_position() {
+            Some(caret) => Some((monitor_at(window, caret.x, caret.y)?, caret)),
+            None => resolve(window, WindowPosition::Follow),
+        },
+        WindowPosition::Center => {
+            let monitor = focused_monitor(window)?;
+            let size = window_size(window, &monitor)?;
+            let position = center(size, monitor.work_area());
+
+            Some((monitor, position))
+        }
+        WindowPosition::Remember => {
+            let monitor = cursor_monitor(window)?;
+
+            // 以窗口的中心判断上次的位置所在的显示器
+            let remembered = saved_geometry(window.app_handle(), MAIN_WINDOW_LABEL)
+                .filter(|geometry| {
+                    let x = geometry.x + geometry.width as i32 / 2;
+                    let y = geometry.y + geometry.height as i32 / 2;
+
+                    monitor_at(window, x, y)
+                        .is_some_and(|saved| saved.position() == monitor.position())
+                })
+                .map(|geometry| PhysicalPosition::new(geometry.x, geometry.y));
+
+            let position = match remembered {
+                Some(position) => position,
+                None => center(window_size(window, &monitor)?, monitor.work_area()),
+            };
+
+            Some((monitor, position))
+        }
+    }
+}
+
+// 显示主窗口前按设置调整位置
+pub fn place_main_window<R: Runtime>(window: &WebviewWindow<R>) {
+    if !is_main_window(window) {
+        return;
+    }
+
+    let Some(position) = window.state::<WindowPositions>().get() else {
+        return;
+    };
+
+    let Some((monitor, position)) = resolve(window, position) else {
+        return;
+    };
+
+    let Some(size) = window_size(window, &monitor) else {
+        return;
+    };
+
+    let _ = window.set_position(clamp(position, size, monitor.work_area()));
+}
+
+// 隐藏主窗口前保存窗口的状态，macos 的主窗口没有移动事件，只在这里保存
+pub fn remember_main_window<R: Runtime>(window: &WebviewWindow<R>) {
+    if !is_main_window(window) {
+        return;
+    }
+
+    let Some(geometry) = WindowGeometry::from_window(window) else {
+        return;
+    };
+
+    if let Err(err) = save_geometry(window.app_handle(), window.label(), geometry) {
+        log::warn!("Failed to save window state of {}: {}", window.label(), err);
+    }
+}
+
+// 无法获取输入光标和激活窗口的平台，跟随鼠标所在的显示器
+#[cfg(target_os = "macos")]
+fn caret_position() -> Option<PhysicalPosition<i32>> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+fn focused_window_rect() -> Option<PhysicalRect<i32, u32>> {
+    None
+}
@@ -0,0 +1,63 @@
+use std::{mem::size_of, mem::zeroed, ptr::null_mut};
+use tauri::{PhysicalPosition, PhysicalRect, PhysicalSize};
+use winapi::shared::windef::{POINT, RECT};
+use winapi::um::winuser::{
+    ClientToScreen, GetForegroundWindow, GetGUIThreadInfo, GetWindowRect, GetWindowThreadProcessId,
+    GUITHREADINFO,
+};
+
+// 前台窗口的输入光标位置，返回光标的左下角
+pub fn caret_position() -> Option<PhysicalPosition<i32>> {
+    unsafe {
+        let hwnd = GetForegroundWindow();
+
+        if hwnd.is_null() {
+            return None;
+        }
+
+        let thread = GetWindowThreadProcessId(hwnd, null_mut());
+
+        let mut info: GUITHREADINFO = zeroed();
+        info.cbSize = size_of::<GUITHREADINFO>() as u32;
+
+        if GetGUIThreadInfo(thread, &mut info) == 0 || info.hwndCaret.is_null() {
+            return None;
+        }
+
+        let mut point = POINT {
+            x: info.rcCaret.left,
+            y: info.rcCaret.bottom,
+        };
+
+        if ClientToScreen(info.hwndCaret, &mut point) == 0 {
+            return None;
+        }
+
+        Some(PhysicalPosition::new(point.x, point.y))
+    }
+}
+
+// 前台窗口在屏幕上的位置和大小
+pub fn focused_window_rect() -> Option<PhysicalRect<i32, u32>> {
+    unsafe {
+        let hwnd = GetForegroundWindow();
+
+        if hwnd.is_null() {
+            return None;
+        }
+
+        let mut rect: RECT = zeroed();
+
+        if GetWindowRect(hwnd, &mut rect) == 0 {
+            return None;
+        }
+
+        Some(PhysicalRect {
+            position: PhysicalPosition::new(rect.left, rect.top),
+            size: PhysicalSize::new(
+                (rect.right - rect.left) as u32,
+                (rect.bottom - rect.top) as u32,
+            ),
+        })
+    }
+}
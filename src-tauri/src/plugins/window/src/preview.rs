@@ -0,0 +1,241 @@
+use crate::{position::clamp, MAIN_WINDOW_LABEL, MAIN_WINDOW_TITLE};
+use std::sync::Mutex;
+use tauri::{
+    AppHandle, Emitter, Manager, PhysicalPosition, PhysicalSize, Runtime, WebviewUrl,
+    WebviewWindow, WebviewWindowBuilder,
+};
+
+// 预览窗口的 label，所有记录共用一个窗口
+pub const PREVIEW_WINDOW_LABEL: &str = "preview";
+
+// 切换预览的记录时发送给预览窗口的事件，载荷为历史记录的 id
+const PREVIEW_EVENT: &str = "plugin:eco-window://preview";
+
+// 预览窗口的默认大小（逻辑像素）
+const PREVIEW_WINDOW_WIDTH: f64 = 480.0;
+const PREVIEW_WINDOW_HEIGHT: f64 = 400.0;
+
+// 预览窗口与主窗口的间距（逻辑像素）
+const PREVIEW_WINDOW_GAP: f64 = 8.0;
+
+// 正在预览的记录
+#[derive(Default)]
+pub struct PreviewState {
+    id: Mutex<Option<String>>,
+}
+
+// 获取预览窗口，不存在时创建一个隐藏的窗口
+fn preview_window<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    id: &str,
+) -> Result<WebviewWindow<R>, String> {
+    if let Some(window) = app_handle.get_webview_window(PREVIEW_WINDOW_LABEL) {
+        return Ok(window);
+    }
+
+    // 首次打开时页面还没有监听事件，通过地址传递 id
+    let url = WebviewUrl::App(format!("index.html/#/preview?id={}", id).into());
+
+    // 标题与主窗口相同，粘贴时不会被当作目标窗口
+    let window = WebviewWindowBuilder::new(app_handle, PREVIEW_WINDOW_LABEL, url)
+        .title(MAIN_WINDOW_TITLE)
+        .inner_size(PREVIEW_WINDOW_WIDTH, PREVIEW_WINDOW_HEIGHT)
+        .min_inner_size(240.0, 160.0)
+        .decorations(false)
+        .transparent(true)
+        .resizable(true)
+        .maximizable(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .visible_on_all_workspaces(true)
+        .visible(false)
+        .focused(false)
+        .build()
+        .map_err(|err| err.to_string())?;
+
+    // 需要在主线程中转换，之后显示和隐藏面板的操作也在主线程中依次执行
+    #[cfg(target_os = "macos")]
+    {
+        let window = window.clone();
+
+        let _ = window
+            .clone()
+            .run_on_main_thread(move || to_preview_panel(&window));
+    }
+
+    Ok(window)
+}
+
+// 在主窗口所在显示器上，优先放在主窗口的右侧，右侧放不下时放在左侧
+fn place_preview_window<R: Runtime>(window: &WebviewWindow<R>) {
+    let Some(main_window) = window.get_webview_window(MAIN_WINDOW_LABEL) else {
+        return;
+    };
+
+    let (Ok(Some(monitor)), Ok(position), Ok(main_size)) = (
+        main_window.current_monitor(),
+        main_window.outer_position(),
+        main_window.outer_size(),
+    ) else {
+        return;
+    };
+
+    let Ok(size) = window.outer_size() else {
+        return;
+    };
+
+    // 预览窗口可能在其它缩放比例的显示器上，按主窗口所在显示器换算大小
+    let size: PhysicalSize<u32> = window
+        .scale_factor()
+        .map(|scale_factor| {
+            size.to_logical::<f64>(scale_factor)
+                .to_physical(monitor.scale_factor())
+        })
+        .unwrap_or(size);
+
+    let gap = (PREVIEW_WINDOW_GAP * monitor.scale_factor()) as i32;
+    let work_area = monitor.work_area();
+
+    let right = position.x + main_size.width as i32 + gap;
+    let left = position.x - gap - size.width as i32;
+
+    let fits_right =
+        right + size.width as i32 <= work_area.position.x + work_area.size.width as i32;
+    let x = if fits_right || left < work_area.position.x {
+        right
+    } else {
+        left
+    };
+
+    let _ = window.set_position(clamp(PhysicalPosition::new(x, position.y), size, work_area));
+}
+
+// 显示预览窗口，不获取焦点
+fn show_preview_window<R: Runtime>(window: &WebviewWindow<R>) {
+    #[cfg(target_os = "macos")]
+    {
+        use tauri_nspanel::ManagerExt;
+
+        let app_handle = window.app_handle().clone();
+
+        let _ = window.run_on_main_thread(move || {
+            if let Ok(panel) = app_handle.get_webview_panel(PREVIEW_WINDOW_LABEL) {
+                panel.order_front_regardless();
+            }
+        });
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    let _ = window.show();
+}
+
+// 把预览窗口转换为不激活应用的 ns_panel，打开时不影响粘贴的目标窗口
+#[cfg(target_os = "macos")]
+fn to_preview_panel<R: Runtime>(window: &WebviewWindow<R>) {
+    use tauri_nspanel::{cocoa::appkit::NSWindowCollectionBehavior, WebviewWindowExt};
+
+    #[allow(non_upper_case_globals)]
+    const NSWindowStyleMaskNonActivatingPanel: i32 = 1 << 7;
+    #[allow(non_upper_case_globals)]
+    const NSResizableWindowMask: i32 = 1 << 3;
+
+    let Ok(panel) = window.to_panel() else {
+        return;
+    };
+
+    // 显示在主窗口之上
+    panel.set_level(21);
+
+    panel.set_style_mask(NSWindowStyleMaskNonActivatingPanel | NSResizableWindowMask);
+
+    panel.set_collection_behaviour(
+        NSWindowCollectionBehavior::NSWindowCollectionBehaviorCanJoinAllSpaces
+            | NSWindowCollectionBehavior::NSWindowCollectionBehaviorStationary
+            | NSWindowCollectionBehavior::NSWindowCollectionBehaviorFullScreenAuxiliary,
+    );
+}
+
+// 预览窗口是否可见
+fn is_preview_visible<R: Runtime>(app_handle: &AppHandle<R>) -> bool {
+    app_handle
+        .get_webview_window(PREVIEW_WINDOW_LABEL)
+        .and_then(|window| window.is_visible().ok())
+        .unwrap_or_default()
+}
+
+// 打开预览窗口并预览指定的记录，窗口已打开时切换预览的记录
+pub fn open_preview_window<R: Runtime>(app_handle: &AppHandle<R>, id: &str) -> Result<(), String> {
+    let window = preview_window(app_handle, id)?;
+
+    *app_handle.state::<PreviewState>().id.lock().unwrap() = Some(id.to_string());
+
+    window
+        .emit_to(PREVIEW_WINDOW_LABEL, PREVIEW_EVENT, id)
+        .map_err(|err| err.to_string())?;
+
+    if !is_preview_visible(app_handle) {
+        place_preview_window(&window);
+    }
+
+    show_preview_window(&window);
+
+    Ok(())
+}
+
+// 隐藏预览窗口，保留 webview 供下次使用
+pub fn close_preview_window<R: Runtime>(app_handle: &AppHandle<R>) {
+    *app_handle.state::<PreviewState>().id.lock().unwrap() = None;
+
+    let Some(window) = app_handle.get_webview_window(PREVIEW_WINDOW_LABEL) else {
+        return;
+    };
+
+    #[cfg(target_os = "macos")]
+    {
+        use tauri_nspanel::ManagerExt;
+
+        let app_handle = app_handle.clone();
+
+        let _ = window.run_on_main_thread(move || {
+            if let Ok(panel) = app_handle.get_webview_panel(PREVIEW_WINDOW_LABEL) {
+                panel.order_out(None);
+            }
+        });
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    let _ = window.hide();
+}
+
+// 正在预览同一条记录时关闭，否则打开，返回预览窗口是否打开
+pub fn toggle_preview_window<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    id: &str,
+) -> Result<bool, String> {
+    let previewing = app_handle
+        .state::<PreviewState>()
+        .id
+        .lock()
+        .unwrap()
+        .clone();
+
+    if is_preview_visible(app_handle) && previewing.as_deref() == Some(id) {
+        close_preview_window(app_handle);
+
+        return Ok(false);
+    }
+
+    open_preview_window(app_handle, id).map(|_| true)
+}
+
+// 预览窗口打开时切换预览的记录，未打开时不做处理
+pub fn update_preview_window<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    id: &str,
+) -> Result<(), String> {
+    if !is_preview_visible(app_handle) {
+        return Ok(());
+    }
+
+    open_preview_window(app_handle, id)
+}
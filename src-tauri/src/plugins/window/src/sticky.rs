@@ -0,0 +1,213 @@
+use crate::{WindowGeometryStore, MAIN_WINDOW_TITLE};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::PathBuf, sync::Mutex};
+use tauri::{AppHandle, Manager, Runtime, WebviewUrl, WebviewWindowBuilder};
+
+// 钉在屏幕上的窗口的 label 前缀，后面接历史记录的 id
+pub const STICKY_WINDOW_PREFIX: &str = "sticky-";
+
+// 钉住的记录，保存在应用的配置目录中，启动时重新打开
+const STICKY_WINDOWS_FILE: &str = "sticky-windows.json";
+
+// 新窗口的默认大小（逻辑像素）
+const STICKY_WINDOW_WIDTH: f64 = 280.0;
+const STICKY_WINDOW_HEIGHT: f64 = 160.0;
+
+// 钉在屏幕上的记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StickyWindow {
+    // 历史记录的 id
+    pub id: String,
+    // 不透明度：0.2 ~ 1
+    pub opacity: f64,
+}
+
+// 所有钉住的记录
+pub struct StickyWindows {
+    path: Option<PathBuf>,
+    windows: Mutex<Vec<StickyWindow>>,
+}
+
+impl StickyWindows {
+    pub fn load<R: Runtime>(app_handle: &AppHandle<R>) -> Self {
+        let path = app_handle
+            .path()
+            .app_config_dir()
+            .ok()
+            .map(|dir| dir.join(STICKY_WINDOWS_FILE));
+
+        let windows = path
+            .as_ref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        Self {
+            path,
+            windows: Mutex::new(windows),
+        }
+    }
+
+    pub fn list(&self) -> Vec<StickyWindow> {
+        self.windows.lock().unwrap().clone()
+    }
+
+    pub fn get(&self, id: &str) -> Option<StickyWindow> {
+        self.windows
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|window| window.id == id)
+            .cloned()
+    }
+
+    // 添加或更新钉住的记录
+    fn upsert(&self, sticky: StickyWindow) -> Result<(), String> {
+        let mut windows = self.windows.lock().unwrap();
+
+        match windows.iter_mut().find(|window| window.id == sticky.id) {
+            Some(window) => *window = sticky,
+            None => windows.push(sticky),
+        }
+
+        self.save(&windows)
+    }
+
+    // 移除钉住的记录，没有钉住时返回 false
+    fn remove(&self, id: &str) -> Result<bool, String> {
+        let mut windows = self.windows.lock().unwrap();
+
+        let len = windows.len();
+
+        windows.retain(|window| window.id != id);
+
+        if windows.len() == len {
+            return Ok(false);
+        }
+
+        self.save(&windows).map(|_| true)
+    }
+
+    fn save(&self, windows: &[StickyWindow]) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+        }
+
+        let content = serde_json::to_string_pretty(windows).map_err(|err| err.to_string())?;
+
+        fs::write(path, content).map_err(|err| err.to_string())
+    }
+}
+
+// 记录对应的窗口 label
+pub fn sticky_window_label(id: &str) -> String {
+    format!("{}{}", STICKY_WINDOW_PREFIX, id)
+}
+
+// 是否为钉在屏幕上的窗口
+pub fn is_sticky_window(label: &str) -> bool {
+    label.starts_with(STICKY_WINDOW_PREFIX)
+}
+
+// 窗口是否为已经钉住的记录，取消钉住后留下的窗口状态会被清理
+pub fn is_pinned_window<R: Runtime>(app_handle: &AppHandle<R>, label: &str) -> bool {
+    label
+        .strip_prefix(STICKY_WINDOW_PREFIX)
+        .is_some_and(|id| app_handle.state::<StickyWindows>().get(id).is_some())
+}
+
+// 创建钉在屏幕上的窗口，窗口已存在时显示出来
+fn create_sticky_window<R: Runtime>(app_handle: &AppHandle<R>, id: &str) -> Result<(), String> {
+    let label = sticky_window_label(id);
+
+    if let Some(window) = app_handle.get_webview_window(&label) {
+        let _ = window.show();
+
+        return Ok(());
+    }
+
+    let url = WebviewUrl::App(format!("index.html/#/sticky?id={}", id).into());
+
+    // 标题与主窗口相同，粘贴时不会被当作目标窗口
+    WebviewWindowBuilder::new(app_handle, label, url)
+        .title(MAIN_WINDOW_TITLE)
+        .inner_size(STICKY_WINDOW_WIDTH, STICKY_WINDOW_HEIGHT)
+        .min_inner_size(120.0, 60.0)
+        .decorations(false)
+        .transparent(true)
+        .resizable(true)
+        .maximizable(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .visible_on_all_workspaces(true)
+        .focused(false)
+        .build()
+        .map(|_| ())
+        .map_err(|err| err.to_string())
+}
+
+// 钉住记录并打开窗口，已钉住时保留原来的不透明度
+pub fn pin_sticky_window<R: Runtime>(app_handle: &AppHandle<R>, id: &str) -> Result<(), String> {
+    let stickies = app_handle.state::<StickyWindows>();
+
+    if stickies.get(id).is_none() {
+        stickies.upsert(StickyWindow {
+            id: id.to_string(),
+            opacity: 1.0,
+        })?;
+    }
+
+    create_sticky_window(app_handle, id)
+}
+
+// 取消钉住并关闭窗口
+pub fn unpin_sticky_window<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    id: &str,
+) -> Result<bool, String> {
+    let removed = app_handle.state::<StickyWindows>().remove(id)?;
+
+    let label = sticky_window_label(id);
+
+    if let Some(window) = app_handle.get_webview_window(&label) {
+        let _ = window.destroy();
+    }
+
+    // 不再需要恢复窗口的位置
+    if let Err(err) = app_handle.state::<WindowGeometryStore>().remove(&label) {
+        log::warn!("Failed to remove window state of {}: {}", label, err);
+    }
+
+    Ok(removed)
+}
+
+// 设置窗口的不透明度，由窗口的页面根据保存的值绘制
+pub fn set_sticky_window_opacity<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    id: &str,
+    opacity: f64,
+) -> Result<(), String> {
+    let stickies = app_handle.state::<StickyWindows>();
+
+    let Some(mut sticky) = stickies.get(id) else {
+        return Err(format!("entry is not pinned: {}", id));
+    };
+
+    sticky.opacity = opacity.clamp(0.2, 1.0);
+
+    stickies.upsert(sticky)
+}
+
+// 启动时重新打开所有钉住的窗口
+pub fn restore_sticky_windows<R: Runtime>(app_handle: &AppHandle<R>) {
+    for sticky in app_handle.state::<StickyWindows>().list() {
+        if let Err(err) = create_sticky_window(app_handle, &sticky.id) {
+            log::warn!("Failed to reopen sticky window {}: {}", sticky.id, err);
+        }
+    }
+}
@@ -14,12 +14,9 @@ const { defaultAlgorithm, darkAlgorithm } = theme;
 const App = () => {
 	const { appearance } = useSnapshot(globalStore);
 	const syncStoreSnapshot = useSnapshot(syncStore);
-	const { restoreState } = useWindowState();
 	const [ready, { toggle }] = useBoolean();
 
 	useMount(async () => {
-		await restoreState();
-
 		await restoreStore();
 
 		toggle();
@@ -69,8 +66,16 @@ const App = () => {
 		openUrl(href);
 	});
 
-	// 隐藏窗口
-	useKeyPress(["esc", PRESET_SHORTCUT.HIDE_WINDOW], hideWindow);
+	// 隐藏窗口，钉在屏幕上的窗口只能取消钉住
+	useKeyPress(["esc", PRESET_SHORTCUT.HIDE_WINDOW], () => {
+		if (isStickyWindow()) return;
+
+		if (isPreviewWindow()) {
+			return closePreview();
+		}
+
+		hideWindow();
+	});
 
 	// 监听 promise 的错误，输出到日志
 	useEventListener("unhandledrejection", ({ reason }) => {
@@ -11,8 +11,12 @@ export const UPDATE_MESSAGE_KEY = "app-update-message";
 export const WINDOW_LABEL = {
 	MAIN: "main",
 	PREFERENCE: "preference",
+	PREVIEW: "preview",
 } as const;
 
+// 钉在屏幕上的窗口的 label 前缀，后面接历史记录的 id
+export const STICKY_WINDOW_PREFIX = "sticky-";
+
 export const LANGUAGE = {
 	ZH_CN: "zh-CN",
 	ZH_TW: "zh-TW",
@@ -33,12 +37,19 @@ export const LISTEN_KEY = {
 	CLOSE_DATABASE: "close-database",
 	TOGGLE_LISTEN_CLIPBOARD: "toggle-listen-clipboard",
 	CLIPBOARD_ITEM_FAVORITE: "clipboard-item-favorite",
+	CLIPBOARD_ITEM_COPY_OCR_TEXT: "clipboard-item-copy-ocr-text",
 	ACTIVATE_BACK_TOP: "activate-back-top",
+	CLIPBOARD_SEARCH: "clipboard-search",
+	BRIDGE_REQUEST: "bridge-request",
+	BRIDGE_RESPONSE: "bridge-response",
+	BRIDGE_READY: "bridge-ready",
+	HISTORY_ADDED: "history-added",
 };
 
 export const PRESET_SHORTCUT = {
 	SEARCH: isMac ? "meta.f" : "ctrl.f",
 	FAVORITE: isMac ? "meta.d" : "ctrl.d",
+	COPY_OCR_TEXT: isMac ? "meta.shift.c" : "ctrl.shift.c",
 	OPEN_PREFERENCES: isMac ? "meta.comma" : "ctrl.comma",
 	HIDE_WINDOW: isMac ? "meta.w" : "ctrl.w",
 	FIXED_WINDOW: isMac ? "meta.p" : "ctrl.p",
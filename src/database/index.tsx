@@ -29,9 +29,12 @@ export const initDatabase = async () => {
 			favorite INTEGER DEFAULT 0,
 			createTime TEXT,
 			note TEXT,
-			subtype TEXT
+			subtype TEXT,
+			codes TEXT
 		);
         `);
+
+	await addField("history", "codes", "TEXT");
 };
 
 /**
@@ -152,8 +155,15 @@ export const deleteSQL = async (tableName: TableName, item: TablePayload) => {
 
 	await executeSQL(`DELETE FROM ${tableName} WHERE id = ?;`, [id]);
 
+	// 关闭钉在屏幕上的窗口
+	if (id) {
+		unpinSticky(id);
+	}
+
 	if (type !== "image" || !value) return;
 
+	cancelOCR(id);
+
 	const path = resolveImagePath(value);
 	const existed = await exists(path);
 
@@ -0,0 +1,163 @@
+import type { HistoryTablePayload } from "@/types/database";
+import type { BridgeParams, BridgeRequest, Timeout } from "@/types/shared";
+import { emit, listen } from "@tauri-apps/api/event";
+import { isNil } from "lodash-es";
+import { nanoid } from "nanoid";
+
+/**
+ * 执行命令行、本地 api 等外部调用的方法，并返回执行结果
+ */
+export const useBridge = () => {
+	const resumeTimerRef = useRef<Timeout>();
+	const recordingRef = useRef(true);
+
+	useTauriListen<boolean>(LISTEN_KEY.TOGGLE_LISTEN_CLIPBOARD, ({ payload }) => {
+		recordingRef.current = payload;
+	});
+
+	const unlistenRef = useRef(() => {});
+
+	useMount(async () => {
+		unlistenRef.current = await listen<BridgeRequest>(
+			LISTEN_KEY.BRIDGE_REQUEST,
+			async ({ payload }) => {
+				const { id, method, params } = payload;
+
+				try {
+					const result = await execute(method, params ?? {});
+
+					emit(LISTEN_KEY.BRIDGE_RESPONSE, { id, result: result ?? null });
+				} catch (error) {
+					const message =
+						error instanceof Error ? error.message : String(error);
+
+					emit(LISTEN_KEY.BRIDGE_RESPONSE, { id, error: message });
+				}
+			},
+		);
+
+		// 开始接收请求后通知后台，守护模式下由界面接管历史记录
+		emit(LISTEN_KEY.BRIDGE_READY);
+	});
+
+	useUnmount(() => unlistenRef.current());
+
+	// 暂停或恢复记录，托盘菜单会同步状态
+	const toggleRecording = (value: boolean) => {
+		clearTimeout(resumeTimerRef.current);
+
+		return emit(LISTEN_KEY.TOGGLE_LISTEN_CLIPBOARD, value);
+	};
+
+	const getItem = async (params: BridgeParams) => {
+		const { id } = params;
+
+		if (!id) {
+			throw new Error("Missing param: id");
+		}
+
+		const [item] = await selectSQL<HistoryTablePayload[]>("history", { id });
+
+		return item;
+	};
+
+	const requireItem = async (params: BridgeParams) => {
+		const item = await getItem(params);
+
+		if (!item) {
+			throw new Error(`No entry with id ${params.id}`);
+		}
+
+		return item;
+	};
+
+	const requireText = (params: BridgeParams) => {
+		const { text } = params;
+
+		if (isNil(text)) {
+			throw new Error("Missing param: text");
+		}
+
+		return text;
+	};
+
+	const execute = async (method: string, params: BridgeParams) => {
+		switch (method) {
+			case "window.search":
+				await emit(LISTEN_KEY.CLIPBOARD_SEARCH, params.query ?? "");
+
+				return showWindow();
+			case "history.query": {
+				const { search, group, favorite, offset = 0, limit } = params;
+
+				const list = await selectSQL<HistoryTablePayload[]>("history", {
+					search,
+					group,
+					favorite,
+				});
+
+				const end = isNil(limit) ? void 0 : offset + limit;
+
+				return list.slice(offset, end).map(resolveHistoryItem);
+			}
+			case "history.get": {
+				const item = await getItem(params);
+
+				return item ? resolveHistoryItem(item) : null;
+			}
+			case "history.copy":
+				return writeClipboard(await requireItem(params));
+			case "history.paste":
+				return pasteClipboard(await requireItem(params), params.plain);
+			case "history.add": {
+				const text = requireText(params);
+
+				const item: HistoryTablePayload = {
+					id: nanoid(),
+					type: "text",
+					group: "text",
+					value: text,
+					search: text,
+					count: text.length,
+					createTime: formatDate(),
+					favorite: false,
+				};
+
+				await insertSQL("history", item);
+
+				emit(LISTEN_KEY.REFRESH_CLIPBOARD_LIST);
+				emit(LISTEN_KEY.HISTORY_ADDED, item);
+
+				return item;
+			}
+			case "clipboard.write":
+				return writeText(requireText(params));
+			case "recording.pause": {
+				const { seconds } = params;
+
+				await toggleRecording(false);
+
+				if (!seconds) {
+					return { recording: false, resumeAt: null };
+				}
+
+				resumeTimerRef.current = setTimeout(() => {
+					toggleRecording(true);
+				}, seconds * 1000);
+
+				return {
+					recording: false,
+					resumeAt: formatDate(dayjs().add(seconds, "second")),
+				};
+			}
+			case "recording.resume":
+				await toggleRecording(true);
+
+				return { recording: true };
+			case "recording.status":
+				return { recording: recordingRef.current };
+			default:
+				throw new Error(`Unknown method: ${method}`);
+		}
+	};
+};
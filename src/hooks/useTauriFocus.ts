@@ -1,4 +1,3 @@
-import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
 import { debounce } from "lodash-es";
 
 interface Props {
@@ -11,24 +10,17 @@ export const useTauriFocus = (props: Props) => {
 	const unlistenRef = useRef(() => {});
 
 	useMount(async () => {
-		const appWindow = getCurrentWebviewWindow();
-
-		if (!appWindow) {
-			console.warn("无法获取 Tauri 窗口，跳过焦点监听");
-			return;
-		}
-
 		const wait = isMac ? 0 : 100;
 
-		const debounced = debounce(({ payload }) => {
-			if (payload) {
+		const debounced = debounce((focused: boolean) => {
+			if (focused) {
 				onFocus?.();
 			} else {
 				onBlur?.();
 			}
 		}, wait);
 
-		unlistenRef.current = await appWindow.onFocusChanged(debounced);
+		unlistenRef.current = await onWindowFocusChanged(debounced);
 	});
 
 	useUnmount(unlistenRef.current);
@@ -10,7 +10,7 @@ let globalTrayInstance: TrayIcon | null = null;
 let isCreatingTray = false;
 
 export const useTray = () => {
-	const [startListen, { toggle }] = useBoolean(true);
+	const [startListen, { toggle, set }] = useBoolean(true);
 	const { t } = useTranslation();
 
 	// 监听是否显示菜单栏图标
@@ -43,6 +43,11 @@ export const useTray = () => {
 		emit(LISTEN_KEY.TOGGLE_LISTEN_CLIPBOARD, startListen);
 	}, [startListen]);
 
+	// 同步命令行暂停和恢复记录的状态
+	useTauriListen<boolean>(LISTEN_KEY.TOGGLE_LISTEN_CLIPBOARD, ({ payload }) => {
+		set(payload);
+	});
+
 	// 通过 id 获取托盘图标
 	const getTrayById = () => {
 		return TrayIcon.getById(TRAY_ID);
@@ -1,92 +0,0 @@
-import { PhysicalPosition, PhysicalSize } from "@tauri-apps/api/dpi";
-import type { Event } from "@tauri-apps/api/event";
-import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
-import { exists, readTextFile, writeTextFile } from "@tauri-apps/plugin-fs";
-
-// 安全获取当前窗口
-let appWindow: any = null;
-let label = "main";
-
-try {
-	if (typeof window !== "undefined" && (window as any).__TAURI__) {
-		appWindow = getCurrentWebviewWindow();
-		label = appWindow.label;
-	}
-} catch (error) {
-	console.warn("获取 Tauri 窗口失败:", error);
-}
-
-export const useWindowState = () => {
-	const state = useReactive<Partial<PhysicalPosition & PhysicalSize>>({});
-
-	useMount(() => {
-		if (appWindow) {
-			appWindow.onMoved(onChange);
-			appWindow.onResized(onChange);
-		}
-	});
-
-	useTauriFocus({
-		onBlur() {
-			saveState();
-		},
-	});
-
-	const onChange = async (event: Event<PhysicalPosition | PhysicalSize>) => {
-		if (!appWindow) return;
-
-		const minimized = await appWindow.isMinimized();
-
-		if (minimized) return;
-
-		Object.assign(state, event.payload);
-	};
-
-	const getSavedStates = async () => {
-		const path = await getSaveWindowStatePath();
-
-		const existed = await exists(path);
-
-		if (!existed) return {};
-
-		const states = await readTextFile(path);
-
-		return JSON.parse(states);
-	};
-
-	const saveState = async () => {
-		const path = await getSaveWindowStatePath();
-
-		const states = await getSavedStates();
-
-		states[label] = state;
-
-		return writeTextFile(path, JSON.stringify(states, null, 2));
-	};
-
-	const restoreState = async () => {
-		if (!appWindow) {
-			console.warn("无法恢复窗口状态：appWindow 为 null");
-			return;
-		}
-
-		const states = await getSavedStates();
-
-		Object.assign(state, states[label]);
-
-		const { x, y, width, height } = state;
-
-		if (x && y) {
-			appWindow.setPosition(new PhysicalPosition(x, y));
-		}
-
-		if (width && height) {
-			appWindow.setSize(new PhysicalSize(width, height));
-		}
-	};
-
-	return {
-		saveState,
-		restoreState,
-	};
-};
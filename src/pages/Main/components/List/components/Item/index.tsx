@@ -1,6 +1,7 @@
 import UnoIcon from "@/components/UnoIcon";
 import { MainContext } from "@/pages/Main";
 import type { HistoryTablePayload } from "@/types/database";
+import type { DecodedCode } from "@/types/plugin";
 import { startDrag } from "@crabnebula/tauri-plugin-drag";
 import { Menu, MenuItem, type MenuItemOptions } from "@tauri-apps/api/menu";
 import { downloadDir, resolveResource } from "@tauri-apps/api/path";
@@ -32,7 +33,8 @@ interface ContextMenuItem extends MenuItemOptions {
 
 const Item: FC<ItemProps> = (props) => {
 	const { index, data, className, deleteModal, openNoteModel, ...rest } = props;
-	const { id, type, value, search, group, favorite, note, subtype } = data;
+	const { id, type, value, search, group, favorite, note, subtype, codes } =
+		data;
 	const { state } = useContext(MainContext);
 	const { t } = useTranslation();
 	const { env } = useSnapshot(globalStore);
@@ -42,8 +44,9 @@ const Item: FC<ItemProps> = (props) => {
 		if (id !== state.eventBusId) return;
 
 		switch (key) {
+			// 图片仍然使用默认的应用打开，其它记录打开或关闭预览窗口
 			case LISTEN_KEY.CLIPBOARD_ITEM_PREVIEW:
-				return preview();
+				return type === "image" ? openImage() : preview();
 			case LISTEN_KEY.CLIPBOARD_ITEM_PASTE:
 				return pasteValue();
 			case LISTEN_KEY.CLIPBOARD_ITEM_DELETE:
@@ -54,6 +57,8 @@ const Item: FC<ItemProps> = (props) => {
 				return selectNextOrPrev();
 			case LISTEN_KEY.CLIPBOARD_ITEM_FAVORITE:
 				return toggleFavorite();
+			case LISTEN_KEY.CLIPBOARD_ITEM_COPY_OCR_TEXT:
+				return copyOcrText();
 		}
 	});
 
@@ -67,6 +72,55 @@ const Item: FC<ItemProps> = (props) => {
 		pasteClipboard(data, true);
 	};
 
+	// 识别图片中的文字并复制
+	const copyOcrText = async () => {
+		if (type !== "image") return;
+
+		try {
+			const { text } = await ocrToClipboard(
+				resolveImagePath(value),
+				void 0,
+				void 0,
+				id,
+			);
+
+			if (!text) {
+				message.warning(t("clipboard.hints.ocr_text_empty"));
+			}
+		} catch (error) {
+			if (!isOcrError(error)) {
+				return message.error(String(error));
+			}
+
+			switch (error.kind) {
+				// 记录被删除时会取消识别
+				case "cancelled":
+					return;
+				case "timeout":
+					return message.error(t("clipboard.hints.ocr_timeout"));
+				case "spawn":
+				case "unsupported":
+				case "other":
+					return message.error(error.message);
+				case "exit":
+					return message.error(
+						t("clipboard.hints.ocr_failed", {
+							replace: [error.program, error.stderr || error.code],
+						}),
+					);
+			}
+		}
+	};
+
+	// 复制二维码和条形码的内容
+	const copyCodes = () => {
+		if (!codes) return;
+
+		const list: DecodedCode[] = JSON.parse(codes);
+
+		return writeText(list.map(({ text }) => text).join("\n"));
+	};
+
 	// 切换收藏状态
 	const toggleFavorite = () => {
 		const nextFavorite = !favorite;
@@ -99,10 +153,13 @@ const Item: FC<ItemProps> = (props) => {
 		revealItemInDir(path);
 	};
 
-	// 预览
+	// 在预览窗口中打开或关闭
 	const preview = () => {
-		if (type !== "image") return;
+		togglePreview(id);
+	};
 
+	// 使用默认的应用打开图片
+	const openImage = () => {
 		openPath(value);
 	};
 
@@ -206,11 +263,25 @@ const Item: FC<ItemProps> = (props) => {
 				hide: type !== "image" || /^[\s]*$/.test(search),
 				action: pastePlain,
 			},
+			{
+				text: t("clipboard.button.context_menu.copy_ocr_text"),
+				hide: type !== "image",
+				action: copyOcrText,
+			},
+			{
+				text: t("clipboard.button.context_menu.copy_code_content"),
+				hide: !codes,
+				action: copyCodes,
+			},
 			{
 				text: t("clipboard.button.context_menu.paste_as_path"),
 				hide: type !== "files",
 				action: pastePlain,
 			},
+			{
+				text: t("clipboard.button.context_menu.pin_to_screen"),
+				action: () => pinSticky(id),
+			},
 			{
 				text: favorite
 					? t("clipboard.button.context_menu.unfavorite")
@@ -232,10 +303,14 @@ const Item: FC<ItemProps> = (props) => {
 				hide: group !== "text",
 				action: exportFile,
 			},
+			{
+				text: t("clipboard.button.context_menu.quick_look"),
+				action: preview,
+			},
 			{
 				text: t("clipboard.button.context_menu.preview_image"),
 				hide: type !== "image",
-				action: preview,
+				action: openImage,
 			},
 			{
 				text: t("clipboard.button.context_menu.download_image"),
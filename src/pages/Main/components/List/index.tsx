@@ -42,6 +42,13 @@ const List = () => {
 		rowVirtualizer.scrollToIndex?.(index);
 	}, [state.activeId]);
 
+	// 预览窗口打开时跟随选中的记录
+	useUpdateEffect(() => {
+		if (!state.activeId) return;
+
+		updatePreview(state.activeId);
+	}, [state.activeId]);
+
 	// 始终保持有一个选中
 	useUpdateEffect(() => {
 		if (state.list.length === 0) {
@@ -61,6 +68,7 @@ const List = () => {
 			"downarrow",
 			"home",
 			PRESET_SHORTCUT.FAVORITE,
+			PRESET_SHORTCUT.COPY_OCR_TEXT,
 		],
 		(_, key) => {
 			state.eventBusId = state.activeId;
@@ -88,6 +96,11 @@ const List = () => {
 				// 收藏和取消收藏
 				case PRESET_SHORTCUT.FAVORITE:
 					return state.$eventBus?.emit(LISTEN_KEY.CLIPBOARD_ITEM_FAVORITE);
+				// 识别图片中的文字并复制
+				case PRESET_SHORTCUT.COPY_OCR_TEXT:
+					return state.$eventBus?.emit(
+						LISTEN_KEY.CLIPBOARD_ITEM_COPY_OCR_TEXT,
+					);
 			}
 		},
 		{
@@ -38,6 +38,11 @@ const Search: FC<HTMLAttributes<HTMLDivElement>> = (props) => {
 		},
 	});
 
+	// 命令行的 `--search` 设置搜索内容
+	useTauriListen<string>(LISTEN_KEY.CLIPBOARD_SEARCH, ({ payload }) => {
+		setValue(payload);
+	});
+
 	useKeyPress(PRESET_SHORTCUT.SEARCH, () => {
 		inputRef.current?.focus();
 	});
@@ -4,10 +4,20 @@ import syncPlugin from "@/plugins/sync";
 import { syncStore } from "@/stores/sync";
 import type { HistoryTablePayload, TablePayload } from "@/types/database";
 import type { Store } from "@/types/store";
+import { emit } from "@tauri-apps/api/event";
 import type { EventEmitter } from "ahooks/lib/useEventEmitter";
-import { find, findIndex, isNil, last, range } from "lodash-es";
+import {
+	find,
+	findIndex,
+	isEmpty,
+	isNil,
+	last,
+	range,
+	uniq,
+} from "lodash-es";
 import { nanoid } from "nanoid";
 import { createContext } from "react";
+import { fullName, metadata } from "tauri-plugin-fs-pro-api";
 import { useSnapshot } from "valtio";
 import Dock from "./components/Dock";
 import Float from "./components/Float";
@@ -43,6 +53,9 @@ const Main = () => {
 	const $eventBus = useEventEmitter<string>();
 	const { createTray } = useTray();
 
+	// 执行命令行、本地 api 等外部调用的方法
+	useBridge();
+
 	useMount(() => {
 		state.$eventBus = $eventBus;
 
@@ -52,6 +65,27 @@ const Main = () => {
 		// 开启剪贴板监听
 		startListen();
 
+		// 图片识别完成后写回搜索文本
+		onOCRResult(({ id, result, codes }) => {
+			// 二维码和条形码的内容也可以被搜索到
+			const search = uniq([result.text, ...codes.map(({ text }) => text)])
+				.filter(Boolean)
+				.join("\n");
+
+			const payload = {
+				search,
+				codes: isEmpty(codes) ? void 0 : JSON.stringify(codes),
+			};
+
+			const item = find(state.list, { id });
+
+			if (item) {
+				Object.assign(item, payload);
+			}
+
+			updateSQL("history", { id, ...payload });
+		});
+
 		// 监听剪贴板更新
 		onClipboardUpdate(async (payload) => {
 			if (clipboardStore.audio.copy) {
@@ -92,6 +126,12 @@ const Main = () => {
 				}
 
 				insertSQL("history", itemData);
+
+				emit(LISTEN_KEY.HISTORY_ADDED, resolveHistoryItem(itemData));
+
+				if (type === "image" && clipboardStore.content.ocr) {
+					enqueueOCR(itemData.id, resolveImagePath(value));
+				}
 			}
 
 			// 🚀 自动同步功能：如果启用同步且启用自动同步，上传到云端
@@ -192,6 +232,26 @@ const Main = () => {
 	// 监听是否显示任务栏图标
 	useImmediateKey(globalStore.app, "showTaskbarIcon", showTaskbarIcon);
 
+	// 监听窗口位置的变更，停靠模式由前端设置位置
+	useImmediate(clipboardStore.window, () => {
+		const { style, position } = clipboardStore.window;
+
+		setWindowPosition(style === "float" ? position : void 0);
+	});
+
+	// 监听识别引擎的变更
+	useImmediate(clipboardStore.content.ocrEngine, () => {
+		const { kind, program, args } = clipboardStore.content.ocrEngine;
+
+		setOcrEngine({
+			kind: kind === "auto" ? void 0 : kind,
+			command: {
+				program,
+				args: args.split(/\s+/).filter(Boolean),
+			},
+		});
+	});
+
 	// 监听刷新列表
 	useTauriListen(LISTEN_KEY.REFRESH_CLIPBOARD_LIST, () => getList());
 
@@ -241,6 +301,41 @@ const Main = () => {
 		[state.quickPasteKeys],
 	);
 
+	// 监听截取屏幕区域并识别文字的快捷键
+	useRegister(async () => {
+		const captured = await captureRegion();
+
+		if (!captured) return;
+
+		const { image, width, height, result, codes } = captured;
+
+		const { size: count } = await metadata(image);
+
+		const itemData: HistoryTablePayload = {
+			type: "image",
+			group: "image",
+			value: await fullName(image),
+			search: uniq([result.text, ...codes.map(({ text }) => text)])
+				.filter(Boolean)
+				.join("\n"),
+			codes: isEmpty(codes) ? void 0 : JSON.stringify(codes),
+			count,
+			width,
+			height,
+			createTime: formatDate(),
+			id: nanoid(),
+			favorite: false,
+		};
+
+		if (state.group === "image" || (isNil(state.group) && !state.favorite)) {
+			state.list.unshift(itemData);
+		}
+
+		insertSQL("history", itemData);
+
+		emit(LISTEN_KEY.HISTORY_ADDED, resolveHistoryItem(itemData));
+	}, [shortcut.textGrabber]);
+
 	// 打开偏好设置窗口
 	useKeyPress(PRESET_SHORTCUT.OPEN_PREFERENCES, () => {
 		showWindow("preference");
@@ -0,0 +1,86 @@
+import ProListItem from "@/components/ProListItem";
+import ProSelect from "@/components/ProSelect";
+import type { OcrEngineInfo } from "@/types/plugin";
+import type { ClipboardStore } from "@/types/store";
+import { Input } from "antd";
+import { useSnapshot } from "valtio";
+
+interface Option {
+	label: string;
+	value: ClipboardStore["content"]["ocrEngine"]["kind"];
+	disabled?: boolean;
+	title?: string;
+}
+
+const OcrEngine = () => {
+	const { content } = useSnapshot(clipboardStore);
+	const { t } = useTranslation();
+	const [engines, setEngines] = useState<OcrEngineInfo[]>([]);
+
+	useImmediate(clipboardStore.content.ocrEngine, async () => {
+		setEngines(await listOcrEngines());
+	});
+
+	const options: Option[] = [
+		{
+			label: t("preference.clipboard.content_settings.label.ocr_engine_auto"),
+			value: "auto",
+		},
+		...engines.map(({ kind, available, reason }) => ({
+			label: t(
+				`preference.clipboard.content_settings.label.ocr_engine_${kind}`,
+			),
+			value: kind,
+			// 外部命令需要先选择才能配置
+			disabled: !available && kind !== "command",
+			title: reason,
+		})),
+	];
+
+	return (
+		<>
+			<ProSelect
+				title={t("preference.clipboard.content_settings.label.ocr_engine")}
+				description={t(
+					"preference.clipboard.content_settings.hints.ocr_engine",
+				)}
+				value={content.ocrEngine.kind}
+				options={options}
+				onChange={(value) => {
+					clipboardStore.content.ocrEngine.kind = value;
+				}}
+			/>
+
+			{content.ocrEngine.kind === "command" && (
+				<ProListItem
+					title={t(
+						"preference.clipboard.content_settings.label.ocr_engine_command",
+					)}
+					description={t(
+						"preference.clipboard.content_settings.hints.ocr_engine_command",
+					)}
+				>
+					<Input
+						value={content.ocrEngine.program}
+						placeholder="paddleocr"
+						className="w-40"
+						onChange={(event) => {
+							clipboardStore.content.ocrEngine.program = event.target.value;
+						}}
+					/>
+
+					<Input
+						value={content.ocrEngine.args}
+						placeholder="--image_dir {path}"
+						className="w-40"
+						onChange={(event) => {
+							clipboardStore.content.ocrEngine.args = event.target.value;
+						}}
+					/>
+				</ProListItem>
+			)}
+		</>
+	);
+};
+
+export default OcrEngine;
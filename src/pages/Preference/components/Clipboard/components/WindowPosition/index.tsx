@@ -24,6 +24,12 @@ const WindowPosition = () => {
 			),
 			value: "follow",
 		},
+		{
+			label: t(
+				"preference.clipboard.window_settings.label.window_position_caret",
+			),
+			value: "caret",
+		},
 		{
 			label: t(
 				"preference.clipboard.window_settings.label.window_position_center",
@@ -4,6 +4,7 @@ import ProSwitch from "@/components/ProSwitch";
 import { Typography } from "antd";
 import { useSnapshot } from "valtio";
 import AutoPaste from "./components/AutoPaste";
+import OcrEngine from "./components/OcrEngine";
 import OperationButton from "./components/OperationButton";
 import SearchPosition from "./components/SearchPosition";
 import WindowPosition from "./components/WindowPosition";
@@ -101,6 +102,8 @@ const ClipboardSettings = () => {
 					}}
 				/>
 
+				{content.ocr && <OcrEngine />}
+
 				<ProSwitch
 					title={t("preference.clipboard.content_settings.label.copy_as_plain")}
 					description={t(
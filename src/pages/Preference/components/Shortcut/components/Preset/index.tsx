@@ -32,7 +32,11 @@ const Preset = () => {
 			value: PRESET_SHORTCUT.FAVORITE,
 		},
 		{
-			label: "preference.shortcut.preset.preview_image",
+			label: "preference.shortcut.preset.copy_ocr_text",
+			value: PRESET_SHORTCUT.COPY_OCR_TEXT,
+		},
+		{
+			label: "preference.shortcut.preset.quick_look",
 			value: "space",
 		},
 		{
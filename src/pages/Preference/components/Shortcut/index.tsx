@@ -38,6 +38,17 @@ const Shortcut = () => {
 						globalStore.shortcut.pastePlain = value;
 					}}
 				/>
+
+				{isLinux && (
+					<ProShortcut
+						title={t("preference.shortcut.shortcut.label.text_grabber")}
+						description={t("preference.shortcut.shortcut.hints.text_grabber")}
+						value={shortcut.textGrabber}
+						onChange={(value) => {
+							globalStore.shortcut.textGrabber = value;
+						}}
+					/>
+				)}
 			</ProList>
 
 			<Preset />
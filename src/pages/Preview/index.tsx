@@ -0,0 +1,100 @@
+import UnoIcon from "@/components/UnoIcon";
+import HTML from "@/pages/Main/components/List/components/Item/components/HTML";
+import Image from "@/pages/Main/components/List/components/Item/components/Image";
+import RTF from "@/pages/Main/components/List/components/Item/components/RTF";
+import type { HistoryTablePayload } from "@/types/database";
+import { Flex } from "antd";
+import { MacScrollbar } from "mac-scrollbar";
+import { useSearchParams } from "react-router-dom";
+
+interface State {
+	loaded: boolean;
+	item?: HistoryTablePayload;
+}
+
+const Preview = () => {
+	const { t } = useTranslation();
+	const [searchParams] = useSearchParams();
+	const state = useReactive<State>({
+		loaded: false,
+	});
+	const unlistenRef = useRef(() => {});
+
+	const loadItem = async (id: string) => {
+		const [item] = await selectSQL<HistoryTablePayload[]>("history", { id });
+
+		state.item = item;
+		state.loaded = true;
+	};
+
+	// 窗口复用，切换预览的记录时重新查询
+	useMount(async () => {
+		const id = searchParams.get("id");
+
+		if (id) {
+			loadItem(id);
+		}
+
+		unlistenRef.current = await onPreviewChanged(loadItem);
+	});
+
+	useUnmount(unlistenRef.current);
+
+	const renderContent = () => {
+		const { item, loaded } = state;
+
+		if (!loaded) return;
+
+		if (!item) {
+			return (
+				<div className="text-color-3">
+					{t("clipboard.hints.entry_not_found")}
+				</div>
+			);
+		}
+
+		const { id, type, value } = item;
+
+		switch (type) {
+			case "image":
+				return (
+					<Image
+						value={resolveImagePath(value)}
+						className="max-w-full object-contain"
+					/>
+				);
+			case "files":
+				return JSON.parse(value).map((path: string) => (
+					<div key={path}>{path}</div>
+				));
+			case "html":
+				return <HTML value={value} />;
+			case "rtf":
+				return <RTF key={id} {...item} />;
+			default:
+				return value;
+		}
+	};
+
+	return (
+		<Flex
+			vertical
+			className="b-color-1 h-screen overflow-hidden rounded-md bg-color-1"
+		>
+			<Flex
+				data-tauri-drag-region
+				align="center"
+				justify="flex-end"
+				className="px-2 pt-1 text-color-2"
+			>
+				<UnoIcon hoverable name="i-lucide:x" onClick={closePreview} />
+			</Flex>
+
+			<MacScrollbar className="flex-1 select-text whitespace-pre-wrap break-words px-2 pb-2">
+				{renderContent()}
+			</MacScrollbar>
+		</Flex>
+	);
+};
+
+export default Preview;
@@ -0,0 +1,130 @@
+import UnoIcon from "@/components/UnoIcon";
+import Image from "@/pages/Main/components/List/components/Item/components/Image";
+import type { HistoryTablePayload } from "@/types/database";
+import { Flex, Popover, Slider } from "antd";
+import { MacScrollbar } from "mac-scrollbar";
+import { useSearchParams } from "react-router-dom";
+
+interface State {
+	loaded: boolean;
+	item?: HistoryTablePayload;
+	opacity: number;
+}
+
+const Sticky = () => {
+	const { t } = useTranslation();
+	const [searchParams] = useSearchParams();
+	const id = searchParams.get("id") ?? "";
+	const state = useReactive<State>({
+		loaded: false,
+		opacity: 1,
+	});
+
+	useMount(async () => {
+		const [item] = await selectSQL<HistoryTablePayload[]>("history", { id });
+
+		state.item = item;
+		state.loaded = true;
+
+		const sticky = await getSticky(id);
+
+		if (sticky) {
+			state.opacity = sticky.opacity;
+		}
+	});
+
+	const changeOpacity = (value: number) => {
+		state.opacity = value;
+
+		setStickyOpacity(id, value);
+	};
+
+	const renderContent = () => {
+		const { item, loaded } = state;
+
+		if (!loaded) return;
+
+		if (!item) {
+			return (
+				<div className="text-color-3">
+					{t("clipboard.hints.entry_not_found")}
+				</div>
+			);
+		}
+
+		const { type, value, search } = item;
+
+		switch (type) {
+			case "image":
+				return (
+					<Flex vertical gap="small">
+						<Image value={resolveImagePath(value)} className="max-w-full" />
+
+						{search}
+					</Flex>
+				);
+			case "files":
+				return JSON.parse(value).map((path: string) => (
+					<div key={path}>{path}</div>
+				));
+			case "html":
+			case "rtf":
+				return search;
+			default:
+				return value;
+		}
+	};
+
+	return (
+		<Flex
+			vertical
+			className="b-color-1 h-screen overflow-hidden rounded-md bg-color-1"
+			style={{ opacity: state.opacity }}
+		>
+			<Flex
+				data-tauri-drag-region
+				align="center"
+				justify="flex-end"
+				gap="small"
+				className="px-2 pt-1 text-color-2"
+			>
+				<Popover
+					trigger="click"
+					placement="bottomRight"
+					content={
+						<Slider
+							className="w-30"
+							min={0.2}
+							max={1}
+							step={0.05}
+							value={state.opacity}
+							tooltip={{
+								formatter: (value = 0) => `${Math.round(value * 100)}%`,
+							}}
+							onChange={changeOpacity}
+						/>
+					}
+				>
+					<UnoIcon
+						hoverable
+						name="i-lucide:blend"
+						title={t("clipboard.button.sticky.opacity")}
+					/>
+				</Popover>
+
+				<UnoIcon
+					hoverable
+					name="i-lucide:pin-off"
+					title={t("clipboard.button.sticky.unpin")}
+					onClick={() => unpinSticky(id)}
+				/>
+			</Flex>
+
+			<MacScrollbar className="flex-1 select-text whitespace-pre-wrap break-words px-2 pb-2">
+				{renderContent()}
+			</MacScrollbar>
+		</Flex>
+	);
+};
+
+export default Sticky;
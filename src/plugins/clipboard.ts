@@ -1,9 +1,9 @@
 import type { HistoryTablePayload } from "@/types/database";
-import type { ClipboardPayload, ReadImage, WindowsOCR } from "@/types/plugin";
+import type { ClipboardPayload, ReadImage } from "@/types/plugin";
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
 import { exists } from "@tauri-apps/plugin-fs";
-import { isEmpty, isEqual } from "lodash-es";
+import { isEqual } from "lodash-es";
 import { fullName, metadata } from "tauri-plugin-fs-pro-api";
 
 const COMMAND = {
@@ -124,34 +124,14 @@ export const readImage = async (): Promise<ClipboardPayload> => {
 
 		const { size: count } = await metadata(image);
 
-		let search = "";
-
-		if (clipboardStore.content.ocr) {
-			try {
-				search = await systemOCR(image);
-
-				if (isWin) {
-					const { content, qr } = JSON.parse(search) as WindowsOCR;
-
-					if (isEmpty(qr)) {
-						search = content;
-					} else {
-						search = qr[0].content;
-					}
-				}
-			} catch (error) {
-				console.warn("图片OCR识别失败，将使用空搜索文本:", error);
-				search = "";
-			}
-		}
-
 		const value = await fullName(image);
 
 		return {
 			...rest,
 			count,
 			value,
-			search,
+			// 识别结果由后台识别队列写回
+			search: "",
 			group: "image",
 		};
 	} catch (error) {
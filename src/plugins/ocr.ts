@@ -1,9 +1,128 @@
+import type {
+	CaptureResult,
+	OcrBox,
+	OcrEngineConfig,
+	OcrEngineInfo,
+	OcrError,
+	OcrJobResult,
+	OcrOptions,
+	OcrResult,
+} from "@/types/plugin";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 
 const COMMAND = {
 	SYSTEM_OCR: "plugin:eco-ocr|system_ocr",
+	OCR_TO_CLIPBOARD: "plugin:eco-ocr|ocr_to_clipboard",
+	CAPTURE_REGION: "plugin:eco-ocr|capture_region",
+	LIST_OCR_LANGUAGES: "plugin:eco-ocr|list_ocr_languages",
+	LIST_OCR_ENGINES: "plugin:eco-ocr|list_ocr_engines",
+	SET_OCR_ENGINE: "plugin:eco-ocr|set_ocr_engine",
+	ENQUEUE_OCR: "plugin:eco-ocr|enqueue_ocr",
+	CANCEL_OCR: "plugin:eco-ocr|cancel_ocr",
+	OCR_RESULT: "plugin:eco-ocr://ocr_result",
 };
 
-export const systemOCR = (path: string) => {
-	return invoke<string>(COMMAND.SYSTEM_OCR, { path });
+/**
+ * 识别图片中的文字
+ * @param path 图片路径
+ * @param options 识别的语言、页面分割模式等选项
+ * @param jobId 任务 id，指定后可以通过 `cancelOCR` 取消
+ */
+export const systemOCR = (
+	path: string,
+	options?: OcrOptions,
+	jobId?: string,
+) => {
+	return invoke<OcrResult>(COMMAND.SYSTEM_OCR, { path, options, jobId });
+};
+
+/**
+ * 识别图片的指定区域，并将识别出的文字写入剪贴板
+ * @param path 图片路径
+ * @param region 识别区域，不指定时识别整张图片
+ * @param options 识别的语言、页面分割模式等选项
+ * @param jobId 任务 id，指定后可以通过 `cancelOCR` 取消
+ */
+export const ocrToClipboard = (
+	path: string,
+	region?: OcrBox,
+	options?: OcrOptions,
+	jobId?: string,
+) => {
+	return invoke<OcrResult>(COMMAND.OCR_TO_CLIPBOARD, {
+		path,
+		region,
+		options,
+		jobId,
+	});
+};
+
+/**
+ * 截取屏幕区域并识别，目前只支持 X11
+ * @param region 截取区域，不指定时由用户拖动选择
+ * @param options 识别的语言、页面分割模式等选项
+ */
+export const captureRegion = (region?: OcrBox, options?: OcrOptions) => {
+	return invoke<CaptureResult | null>(COMMAND.CAPTURE_REGION, {
+		path: getSaveImagePath(),
+		region,
+		options,
+	});
+};
+
+/**
+ * 获取已安装的识别语言
+ */
+export const listOcrLanguages = () => {
+	return invoke<string[]>(COMMAND.LIST_OCR_LANGUAGES);
+};
+
+/**
+ * 获取所有识别引擎及其可用状态
+ */
+export const listOcrEngines = () => {
+	return invoke<OcrEngineInfo[]>(COMMAND.LIST_OCR_ENGINES);
+};
+
+/**
+ * 设置使用的识别引擎
+ * @param config 引擎类型和外部命令，不指定类型时自动选择
+ */
+export const setOcrEngine = (config: OcrEngineConfig) => {
+	return invoke(COMMAND.SET_OCR_ENGINE, { config });
+};
+
+/**
+ * 将图片加入后台识别队列
+ * @param id 历史记录的 id
+ * @param path 图片路径
+ * @param options 识别的语言、页面分割模式等选项
+ */
+export const enqueueOCR = (id: string, path: string, options?: OcrOptions) => {
+	return invoke(COMMAND.ENQUEUE_OCR, { id, path, options });
+};
+
+/**
+ * 取消识别，正在运行的识别进程会被结束
+ * @param id 历史记录的 id 或识别时指定的任务 id
+ */
+export const cancelOCR = (id: string) => {
+	return invoke<boolean>(COMMAND.CANCEL_OCR, { id });
+};
+
+/**
+ * 是否为识别失败时返回的错误
+ */
+export const isOcrError = (error: unknown): error is OcrError => {
+	return typeof error === "object" && error !== null && "kind" in error;
+};
+
+/**
+ * 后台识别完成
+ */
+export const onOCRResult = (fn: (payload: OcrJobResult) => void) => {
+	return listen<OcrJobResult>(COMMAND.OCR_RESULT, ({ payload }) => {
+		fn(payload);
+	});
 };
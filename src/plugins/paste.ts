@@ -1,12 +1,55 @@
+import type { FocusedWindow, PasteOutcome } from "@/types/plugin";
 import { invoke } from "@tauri-apps/api/core";
 
 export const COMMAND = {
 	PASTE: "plugin:eco-paste|paste",
+	GET_FOCUS_HISTORY: "plugin:eco-paste|get_focus_history",
+	GET_TARGET_WINDOW: "plugin:eco-paste|get_target_window",
+	PASTE_INTO: "plugin:eco-paste|paste_into",
+	LIST_WINDOWS: "plugin:eco-paste|list_windows",
 };
 
 /**
  * 粘贴剪贴板内容
+ * @param keys 粘贴后追加发送的按键序列，例如 `Tab,Tab,Enter`
+ * @param delay 按键之间的间隔（毫秒）
+ * @returns 粘贴结果，失败时抛出 `PasteError`
  */
-export const paste = () => {
-	return invoke(COMMAND.PASTE);
+export const paste = (keys?: string, delay?: number) => {
+	return invoke<PasteOutcome>(COMMAND.PASTE, { keys, delay });
+};
+
+/**
+ * 获取最近获得过焦点的窗口
+ */
+export const getFocusHistory = () => {
+	return invoke<FocusedWindow[]>(COMMAND.GET_FOCUS_HISTORY);
+};
+
+/**
+ * 获取粘贴的目标窗口
+ */
+export const getTargetWindow = () => {
+	return invoke<FocusedWindow | null>(COMMAND.GET_TARGET_WINDOW);
+};
+
+/**
+ * 依次粘贴到指定的窗口，某个窗口失败时在对应结果的 `error` 中返回原因
+ * @param windowIds 窗口 id，来自焦点历史或窗口列表
+ * @param keys 粘贴后追加发送的按键序列
+ * @param delay 按键之间的间隔（毫秒）
+ */
+export const pasteInto = (
+	windowIds: number[],
+	keys?: string,
+	delay?: number,
+) => {
+	return invoke<PasteOutcome[]>(COMMAND.PASTE_INTO, { windowIds, keys, delay });
+};
+
+/**
+ * 获取可以粘贴的窗口列表
+ */
+export const listWindows = () => {
+	return invoke<FocusedWindow[]>(COMMAND.LIST_WINDOWS);
 };
@@ -1,17 +1,29 @@
-import type { WindowLabel } from "@/types/plugin";
+import type { StickyWindow, WindowLabel } from "@/types/plugin";
+import type { ClipboardStore } from "@/types/store";
 import { invoke } from "@tauri-apps/api/core";
+import type { PhysicalPosition, PhysicalSize } from "@tauri-apps/api/dpi";
 import { emit } from "@tauri-apps/api/event";
 import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
-import {
-	LogicalPosition,
-	LogicalSize,
-	currentMonitor,
-} from "@tauri-apps/api/window";
+import { LogicalPosition, LogicalSize } from "@tauri-apps/api/window";
 
 const COMMAND = {
 	SHOW_WINDOW: "plugin:eco-window|show_window",
 	HIDE_WINDOW: "plugin:eco-window|hide_window",
 	SHOW_TASKBAR_ICON: "plugin:eco-window|show_taskbar_icon",
+	SET_WINDOW_POSITION: "plugin:eco-window|set_window_position",
+	PIN_STICKY: "plugin:eco-window|pin_sticky",
+	UNPIN_STICKY: "plugin:eco-window|unpin_sticky",
+	SET_STICKY_OPACITY: "plugin:eco-window|set_sticky_opacity",
+	GET_STICKY: "plugin:eco-window|get_sticky",
+	OPEN_PREVIEW: "plugin:eco-window|open_preview",
+	CLOSE_PREVIEW: "plugin:eco-window|close_preview",
+	TOGGLE_PREVIEW: "plugin:eco-window|toggle_preview",
+	UPDATE_PREVIEW: "plugin:eco-window|update_preview",
+	PREVIEW_CHANGED: "plugin:eco-window://preview",
+	WINDOW_FOCUS: "plugin:eco-window://focus",
+	WINDOW_BLUR: "plugin:eco-window://blur",
+	WINDOW_MOVED: "plugin:eco-window://move",
+	WINDOW_RESIZED: "plugin:eco-window://resize",
 };
 
 /**
@@ -55,28 +67,8 @@ export const toggleWindowVisible = async () => {
 				await emit(LISTEN_KEY.ACTIVATE_BACK_TOP);
 			}
 
-			if (window.style === "float" && window.position !== "remember") {
-				const current = await currentMonitor();
-				const monitor = await getCursorMonitor();
-
-				if (current && monitor) {
-					let { position, size, cursorX, cursorY } = monitor;
-					const windowSize = await appWindow.innerSize();
-					const { width, height } = windowSize.toLogical(current.scaleFactor);
-
-					if (window.position === "follow") {
-						cursorX = Math.min(cursorX, position.x + size.width - width);
-						cursorY = Math.min(cursorY, position.y + size.height - height);
-					} else {
-						cursorX = position.x + (size.width - width) / 2;
-						cursorY = position.y + (size.height - height) / 2;
-					}
-
-					await appWindow.setPosition(
-						new LogicalPosition(Math.round(cursorX), Math.round(cursorY)),
-					);
-				}
-			} else if (window.style === "dock") {
+			// 悬浮模式的位置在显示窗口时由后端设置
+			if (window.style === "dock") {
 				const monitor = await getCursorMonitor();
 
 				if (monitor) {
@@ -95,9 +87,147 @@ export const toggleWindowVisible = async () => {
 	}
 };
 
+/**
+ * 设置主窗口显示时的位置
+ * @param position 显示的位置，不指定时保持窗口当前的位置
+ */
+export const setWindowPosition = (
+	position?: ClipboardStore["window"]["position"],
+) => {
+	invoke(COMMAND.SET_WINDOW_POSITION, { position });
+};
+
+/**
+ * 将历史记录钉在屏幕上
+ * @param id 历史记录的 id
+ */
+export const pinSticky = (id: string) => {
+	return invoke(COMMAND.PIN_STICKY, { id });
+};
+
+/**
+ * 取消钉住历史记录，并关闭对应的窗口
+ * @param id 历史记录的 id
+ */
+export const unpinSticky = (id: string) => {
+	return invoke<boolean>(COMMAND.UNPIN_STICKY, { id });
+};
+
+/**
+ * 设置钉住窗口的不透明度
+ * @param id 历史记录的 id
+ * @param opacity 不透明度：0.2 ~ 1
+ */
+export const setStickyOpacity = (id: string, opacity: number) => {
+	return invoke(COMMAND.SET_STICKY_OPACITY, { id, opacity });
+};
+
+/**
+ * 获取钉住的记录，没有钉住时返回 null
+ * @param id 历史记录的 id
+ */
+export const getSticky = (id: string) => {
+	return invoke<StickyWindow | null>(COMMAND.GET_STICKY, { id });
+};
+
+/**
+ * 当前窗口是否为钉在屏幕上的窗口
+ */
+export const isStickyWindow = () => {
+	return getCurrentWebviewWindow().label.startsWith(STICKY_WINDOW_PREFIX);
+};
+
+/**
+ * 打开预览窗口并预览历史记录，预览窗口显示在主窗口旁边
+ * @param id 历史记录的 id
+ */
+export const openPreview = (id: string) => {
+	return invoke(COMMAND.OPEN_PREVIEW, { id });
+};
+
+/**
+ * 关闭预览窗口
+ */
+export const closePreview = () => {
+	return invoke(COMMAND.CLOSE_PREVIEW);
+};
+
+/**
+ * 正在预览同一条记录时关闭预览窗口，否则打开，返回预览窗口是否打开
+ * @param id 历史记录的 id
+ */
+export const togglePreview = (id: string) => {
+	return invoke<boolean>(COMMAND.TOGGLE_PREVIEW, { id });
+};
+
+/**
+ * 预览窗口打开时切换预览的历史记录，未打开时不做处理
+ * @param id 历史记录的 id
+ */
+export const updatePreview = (id: string) => {
+	return invoke(COMMAND.UPDATE_PREVIEW, { id });
+};
+
+/**
+ * 预览窗口切换预览的历史记录，载荷为历史记录的 id
+ */
+export const onPreviewChanged = (fn: (id: string) => void) => {
+	return getCurrentWebviewWindow().listen<string>(
+		COMMAND.PREVIEW_CHANGED,
+		({ payload }) => fn(payload),
+	);
+};
+
+/**
+ * 当前窗口是否为预览窗口
+ */
+export const isPreviewWindow = () => {
+	return getCurrentWebviewWindow().label === WINDOW_LABEL.PREVIEW;
+};
+
 /**
  * 显示任务栏图标
  */
 export const showTaskbarIcon = (visible = true) => {
 	invoke(COMMAND.SHOW_TASKBAR_ICON, { visible });
 };
+
+/**
+ * 当前窗口获得或失去焦点，各个平台的事件一致
+ */
+export const onWindowFocusChanged = async (fn: (focused: boolean) => void) => {
+	const appWindow = getCurrentWebviewWindow();
+
+	const unlistenFocus = await appWindow.listen(COMMAND.WINDOW_FOCUS, () => {
+		fn(true);
+	});
+
+	const unlistenBlur = await appWindow.listen(COMMAND.WINDOW_BLUR, () => {
+		fn(false);
+	});
+
+	return () => {
+		unlistenFocus();
+		unlistenBlur();
+	};
+};
+
+/**
+ * 当前窗口移动，载荷为窗口外框的物理位置
+ */
+export const onWindowMoved = (fn: (position: PhysicalPosition) => void) => {
+	return getCurrentWebviewWindow().listen<PhysicalPosition>(
+		COMMAND.WINDOW_MOVED,
+		({ payload }) => fn(payload),
+	);
+};
+
+/**
+ * 当前窗口大小改变，载荷为窗口内容区域的物理大小
+ */
+export const onWindowResized = (fn: (size: PhysicalSize) => void) => {
+	return getCurrentWebviewWindow().listen<PhysicalSize>(
+		COMMAND.WINDOW_RESIZED,
+		({ payload }) => fn(payload),
+	);
+};
@@ -1,5 +1,7 @@
 import Main from "@/pages/Main";
 import Preference from "@/pages/Preference";
+import Preview from "@/pages/Preview";
+import Sticky from "@/pages/Sticky";
 import { createHashRouter } from "react-router-dom";
 
 export const router = createHashRouter([
@@ -11,4 +13,12 @@ export const router = createHashRouter([
 		path: "/preference",
 		Component: Preference,
 	},
+	{
+		path: "/preview",
+		Component: Preview,
+	},
+	{
+		path: "/sticky",
+		Component: Sticky,
+	},
 ]);
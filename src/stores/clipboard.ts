@@ -22,6 +22,11 @@ export const clipboardStore = proxy<ClipboardStore>({
 	content: {
 		autoPaste: "double",
 		ocr: true,
+		ocrEngine: {
+			kind: "auto",
+			program: "",
+			args: "",
+		},
 		copyPlain: false,
 		pastePlain: false,
 		operationButtons: ["copy", "star", "delete"],
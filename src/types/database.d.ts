@@ -7,6 +7,8 @@ export interface HistoryTablePayload extends ClipboardPayload {
 	favorite: boolean;
 	createTime: string;
 	note?: string;
+	// 图片中的二维码和条形码，`DecodedCode[]` 的 json
+	codes?: string;
 }
 
 export type TablePayload = Partial<HistoryTablePayload>;
@@ -1,5 +1,10 @@
 export type WindowLabel = (typeof WINDOW_LABEL)[keyof typeof WINDOW_LABEL];
 
+export interface StickyWindow {
+	id: string;
+	opacity: number;
+}
+
 export interface ReadImage {
 	width: number;
 	height: number;
@@ -17,10 +22,141 @@ export interface ClipboardPayload {
 	height?: number;
 }
 
-export interface WindowsOCR {
-	content: string;
-	qr: Array<{
-		bounds: Array<{ x: number; y: number }>;
-		content: string;
-	}>;
+export type PasteKey =
+	| "Enter"
+	| "Tab"
+	| "Escape"
+	| "Space"
+	| "Backspace"
+	| "Up"
+	| "Down"
+	| "Left"
+	| "Right";
+
+export interface PasteOutcome {
+	target?: number;
+	focused: boolean;
+	keystroke: string;
+	backend: string;
+	keys: PasteKey[];
+	error?: PasteError;
+}
+
+export interface PasteError {
+	kind:
+		| "invalidKey"
+		| "noTargetWindow"
+		| "displayUnavailable"
+		| "focusFailed"
+		| "keystrokeFailed";
+	message?: string;
+}
+
+export interface FocusedWindow {
+	id: number;
+	pid?: number;
+	appName: string;
+	title: string;
+	class: string;
+	timestamp: number;
+}
+
+export interface OcrBox {
+	x: number;
+	y: number;
+	width: number;
+	height: number;
+}
+
+export interface OcrWord {
+	text: string;
+	bbox: OcrBox;
+	confidence: number;
+}
+
+export interface OcrLine {
+	text: string;
+	bbox: OcrBox;
+	confidence: number;
+	paragraph: number;
+	words: OcrWord[];
+}
+
+export interface OcrResult {
+	text: string;
+	lines: OcrLine[];
+	confidence?: number;
+}
+
+export interface OcrJobResult {
+	id: string;
+	hash: string;
+	cached: boolean;
+	result: OcrResult;
+	codes: DecodedCode[];
+}
+
+export interface CaptureResult {
+	image: string;
+	width: number;
+	height: number;
+	result: OcrResult;
+	codes: DecodedCode[];
+}
+
+export interface DecodedCode {
+	format: string;
+	text: string;
+	kind: "url" | "wifi" | "otp" | "text";
+	wifi?: {
+		ssid: string;
+		security?: string;
+		password?: string;
+		hidden: boolean;
+	};
+}
+
+export type OcrEngineKind =
+	| "tesseract"
+	| "libtesseract"
+	| "sidecar"
+	| "command";
+
+export interface OcrEngineConfig {
+	kind?: OcrEngineKind;
+	command?: {
+		program: string;
+		args: string[];
+	};
+}
+
+export interface OcrEngineInfo {
+	kind: OcrEngineKind;
+	available: boolean;
+	reason?: string;
+}
+
+export interface OcrOptions {
+	languages?: string[];
+	psm?: number;
+	detectScript?: boolean;
+	preprocess?: OcrPreprocessOptions;
+	/** 超时时间（秒），默认 30 秒 */
+	timeout?: number;
+}
+
+export type OcrError =
+	| { kind: "spawn"; program: string; message: string }
+	| { kind: "timeout"; program: string; seconds: number }
+	| { kind: "cancelled" }
+	| { kind: "exit"; program: string; code: number | null; stderr: string }
+	| { kind: "unsupported"; message: string }
+	| { kind: "other"; message: string };
+
+export interface OcrPreprocessOptions {
+	grayscale?: boolean;
+	invert?: boolean;
+	upscale?: boolean;
+	binarize?: boolean;
+	deskew?: boolean;
 }
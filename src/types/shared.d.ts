@@ -1,3 +1,24 @@
+import type { HistoryTablePayload } from "@/types/database";
+
 export type Interval = ReturnType<typeof setInterval>;
 
 export type Timeout = ReturnType<typeof setTimeout>;
+
+export interface BridgeParams {
+	id?: string;
+	text?: string;
+	query?: string;
+	search?: string;
+	group?: HistoryTablePayload["group"];
+	favorite?: boolean;
+	limit?: number;
+	offset?: number;
+	plain?: boolean;
+	seconds?: number;
+}
+
+export interface BridgeRequest {
+	id: number;
+	method: string;
+	params: BridgeParams | null;
+}
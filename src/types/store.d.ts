@@ -1,3 +1,4 @@
+import type { OcrEngineKind } from "@/types/plugin";
 import type { Platform } from "@tauri-apps/plugin-os";
 
 export type Theme = "auto" | "light" | "dark";
@@ -39,6 +40,8 @@ export interface GlobalStore {
 			value: string;
 		};
 		pastePlain: string;
+		// 截取屏幕区域并识别文字，目前只支持 X11
+		textGrabber: string;
 	};
 
 	// 只在当前系统环境使用
@@ -63,7 +66,7 @@ export interface ClipboardStore {
 	// 窗口设置
 	window: {
 		style: "float" | "dock";
-		position: "remember" | "follow" | "center";
+		position: "remember" | "follow" | "caret" | "center";
 		backTop: boolean;
 		showAll: boolean;
 	};
@@ -84,6 +87,12 @@ export interface ClipboardStore {
 	content: {
 		autoPaste: "single" | "double";
 		ocr: boolean;
+		ocrEngine: {
+			kind: "auto" | OcrEngineKind;
+			// 外部命令及其参数，参数以空格分隔，`{path}` 会被替换为图片路径
+			program: string;
+			args: string;
+		};
 		copyPlain: boolean;
 		pastePlain: boolean;
 		operationButtons: OperationButton[];
@@ -1,3 +1,4 @@
+import type { HistoryTablePayload } from "@/types/database";
 import { getName } from "@tauri-apps/api/app";
 import { appDataDir, sep } from "@tauri-apps/api/path";
 import { last } from "lodash-es";
@@ -52,6 +53,16 @@ export const resolveImagePath = (file: string) => {
 	return joinPath(getSaveImagePath(), file);
 };
 
+/**
+ * 图片的历史记录使用完整的图片路径，用于提供给外部调用
+ * @param item 历史记录
+ */
+export const resolveHistoryItem = (item: HistoryTablePayload) => {
+	if (item.type !== "image") return item;
+
+	return { ...item, value: resolveImagePath(item.value) };
+};
+
 /**
  * 存储数据的目录名
  */
@@ -72,12 +83,3 @@ export const getSaveStorePath = async (backup = false) => {
 
 	return joinPath(await appDataDir(), `.store.${extname}`);
 };
-
-/**
- * 存储窗口位置的路径
- */
-export const getSaveWindowStatePath = async () => {
-	const extname = isDev() ? "dev.json" : "json";
-
-	return joinPath(await appDataDir(), `.window-state.${extname}`);
-};
@@ -0,0 +1,77 @@
+#!/bin/bash
+# EcoPaste wayland 粘贴验证脚本 (Linux 版本)
+# 在无头的 sway 中运行虚拟键盘的测试，并用 wev 检查是否收到了 Shift+Insert
+
+echo "========================================"
+echo "    EcoPaste wayland 粘贴验证 (Linux)"
+echo "========================================"
+echo ""
+
+# 检查是否在正确的目录
+if [ ! -f "package.json" ]; then
+    echo -e "\033[31m[错误] 请在 EcoPaste 项目根目录下运行此脚本\033[0m"
+    exit 1
+fi
+
+# 检查依赖的工具
+for tool in sway wev cargo; do
+    if ! command -v $tool &> /dev/null; then
+        echo -e "\033[31m[错误] 未找到 $tool，请先安装\033[0m"
+        exit 1
+    fi
+done
+
+# 使用独立的运行目录，避免连接到当前会话的合成器
+RUNTIME_DIR=$(mktemp -d)
+chmod 700 "$RUNTIME_DIR"
+WEV_LOG="$RUNTIME_DIR/wev.log"
+
+cleanup() {
+    kill $WEV_PID $SWAY_PID &> /dev/null
+    rm -rf "$RUNTIME_DIR"
+}
+trap cleanup EXIT
+
+# 启动无头的 sway
+export XDG_RUNTIME_DIR="$RUNTIME_DIR"
+unset WAYLAND_DISPLAY DISPLAY
+WLR_BACKENDS=headless WLR_LIBINPUT_NO_DEVICES=1 WLR_RENDERER=pixman \
+    sway --config /dev/null &> "$RUNTIME_DIR/sway.log" &
+SWAY_PID=$!
+
+for _ in $(seq 1 50); do
+    SOCKET=$(ls "$RUNTIME_DIR" | grep -m1 '^wayland-[0-9]*$')
+    [ -n "$SOCKET" ] && break
+    sleep 0.1
+done
+
+if [ -z "$SOCKET" ]; then
+    echo -e "\033[31m[错误] sway 启动失败\033[0m"
+    cat "$RUNTIME_DIR/sway.log"
+    exit 1
+fi
+export WAYLAND_DISPLAY="$SOCKET"
+export XDG_SESSION_TYPE=wayland
+echo -e "\033[32m[信息] sway 已启动: $WAYLAND_DISPLAY\033[0m"
+
+# wev 的窗口获得焦点后记录收到的按键
+wev &> "$WEV_LOG" &
+WEV_PID=$!
+sleep 1
+
+# 运行需要合成器的测试
+cd src-tauri
+if ! cargo test -p tauri-plugin-eco-paste sends_paste_through_virtual_keyboard -- --ignored; then
+    echo -e "\033[31m[错误] 虚拟键盘测试失败\033[0m"
+    exit 1
+fi
+cd ..
+sleep 1
+
+if grep -q "Insert" "$WEV_LOG" && grep -q "Return" "$WEV_LOG"; then
+    echo -e "\033[32m[成功] wev 收到了 Shift+Insert 和 Enter\033[0m"
+else
+    echo -e "\033[31m[错误] wev 没有收到粘贴的按键\033[0m"
+    cat "$WEV_LOG"
+    exit 1
+fi
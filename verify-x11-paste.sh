@@ -0,0 +1,56 @@
+#!/bin/bash
+# EcoPaste X11 粘贴验证脚本 (Linux 版本)
+# 在 Xvfb 中运行需要 X 服务的测试，检查查询或激活已销毁的窗口时进程不会退出
+
+echo "========================================"
+echo "    EcoPaste X11 粘贴验证 (Linux)"
+echo "========================================"
+echo ""
+
+# 检查是否在正确的目录
+if [ ! -f "package.json" ]; then
+    echo -e "\033[31m[错误] 请在 EcoPaste 项目根目录下运行此脚本\033[0m"
+    exit 1
+fi
+
+# 检查依赖的工具
+for tool in Xvfb cargo; do
+    if ! command -v $tool &> /dev/null; then
+        echo -e "\033[31m[错误] 未找到 $tool，请先安装\033[0m"
+        exit 1
+    fi
+done
+
+# 使用空闲的显示编号，避免影响当前的 X 会话
+DISPLAY_NUMBER=99
+while [ -e "/tmp/.X11-unix/X$DISPLAY_NUMBER" ]; do
+    DISPLAY_NUMBER=$((DISPLAY_NUMBER + 1))
+done
+
+Xvfb ":$DISPLAY_NUMBER" -screen 0 1280x720x24 -nolisten tcp &> /dev/null &
+XVFB_PID=$!
+trap 'kill $XVFB_PID &> /dev/null' EXIT
+
+for _ in $(seq 1 50); do
+    [ -e "/tmp/.X11-unix/X$DISPLAY_NUMBER" ] && break
+    sleep 0.1
+done
+
+if [ ! -e "/tmp/.X11-unix/X$DISPLAY_NUMBER" ]; then
+    echo -e "\033[31m[错误] Xvfb 启动失败\033[0m"
+    exit 1
+fi
+
+export DISPLAY=":$DISPLAY_NUMBER"
+unset WAYLAND_DISPLAY
+export XDG_SESSION_TYPE=x11
+echo -e "\033[32m[信息] Xvfb 已启动: $DISPLAY\033[0m"
+
+# 运行需要 X 服务的测试
+cd src-tauri
+if cargo test -p tauri-plugin-eco-paste survives_bad_window -- --ignored; then
+    echo -e "\033[32m[成功] 访问已销毁的窗口时进程没有退出\033[0m"
+else
+    echo -e "\033[31m[错误] X11 测试失败\033[0m"
+    exit 1
+fi